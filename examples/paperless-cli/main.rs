@@ -0,0 +1,185 @@
+//! Small command line client, built only on the public API of the crate
+//!
+//! The server is configured with the `PAPERLESS_URL` (for example
+//! `https://paperless.example.com/api/`) and `PAPERLESS_TOKEN` environment variables.
+//!
+//! ```text
+//! cargo run --features cli --example paperless-cli -- list tags
+//! ```
+
+use paperless::bulk_edit::Operation;
+use paperless::document::{self, FileVersion};
+use paperless::{tag, Error, Paperless};
+use std::process::ExitCode;
+use std::time::Duration;
+
+const USAGE: &str = "usage: paperless-cli <command>
+
+commands:
+    list <documents|correspondents|document-types|storage-paths|tags>
+    get <document id>
+    search <query>
+    upload <file>
+    download <document id> <output file> [--original]
+    bulk-edit <add-tag|remove-tag> <tag id> <document id>...
+    bulk-edit <delete|reprocess> <document id>...";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let (Ok(url), Ok(token)) = (
+        std::env::var("PAPERLESS_URL"),
+        std::env::var("PAPERLESS_TOKEN"),
+    ) else {
+        eprintln!("PAPERLESS_URL and PAPERLESS_TOKEN must be set");
+        return ExitCode::FAILURE;
+    };
+
+    let result = Paperless::new(&url, &token).and_then(|paperless| match args.as_slice() {
+        ["list", kind] => list(&paperless, kind),
+        ["get", id] => get(&paperless, parse_id(id)?),
+        ["search", query @ ..] => search(&paperless, &query.join(" ")),
+        ["upload", path] => upload(&paperless, path),
+        ["download", id, output] => download(&paperless, parse_id(id)?, output, false),
+        ["download", id, output, "--original"] => download(&paperless, parse_id(id)?, output, true),
+        ["bulk-edit", operation, args @ ..] => bulk_edit(&paperless, operation, args),
+        _ => Err(usage("unknown command")),
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Error for invalid arguments, followed by the usage
+fn usage(message: &str) -> Error {
+    Error::InvalidArgument(format!("{message}\n\n{USAGE}"))
+}
+
+fn parse_id(value: &str) -> Result<u64, Error> {
+    value
+        .parse()
+        .map_err(|_| Error::InvalidArgument(format!("invalid id: {value}")))
+}
+
+fn list(paperless: &Paperless, kind: &str) -> Result<(), Error> {
+    match kind {
+        "documents" => {
+            for d in paperless.documents(Default::default())? {
+                let d = d?;
+                println!("{}\t{}", d.id.to_string(), d.title);
+            }
+        }
+        "correspondents" => {
+            for c in paperless.correspondents(Default::default())? {
+                let c = c?;
+                println!("{}\t{}\t{}", c.id.to_string(), c.name, c.document_count);
+            }
+        }
+        "document-types" => {
+            for t in paperless.document_types(Default::default())? {
+                let t = t?;
+                println!("{}\t{}\t{}", t.id.to_string(), t.name, t.document_count);
+            }
+        }
+        "storage-paths" => {
+            for p in paperless.storage_paths(Default::default())? {
+                let p = p?;
+                println!("{}\t{}\t{}", p.id.to_string(), p.name, p.path);
+            }
+        }
+        "tags" => {
+            for t in paperless.tags(Default::default())? {
+                let t = t?;
+                println!("{}\t{}\t{}", t.id.to_string(), t.name, t.document_count);
+            }
+        }
+        _ => return Err(usage(&format!("unknown kind: {kind}"))),
+    }
+    Ok(())
+}
+
+fn get(paperless: &Paperless, id: u64) -> Result<(), Error> {
+    let document = paperless
+        .documents_detailed(document::Filter {
+            id_in: vec![id.into()],
+            ..Default::default()
+        })?
+        .next()
+        .ok_or(Error::NotFound)??;
+    let d = &document.document;
+    println!("id:            {}", d.id.to_string());
+    println!("title:         {}", d.title);
+    println!("created:       {:?}", d.created_date);
+    println!(
+        "correspondent: {}",
+        document.correspondent.as_deref().unwrap_or("-")
+    );
+    println!(
+        "document type: {}",
+        document.document_type.as_deref().unwrap_or("-")
+    );
+    println!(
+        "storage path:  {}",
+        document.storage_path.as_deref().unwrap_or("-")
+    );
+    println!("tags:          {}", document.tags.join(", "));
+    Ok(())
+}
+
+fn search(paperless: &Paperless, query: &str) -> Result<(), Error> {
+    let results = paperless.search_documents(query)?;
+    if let Some(corrected) = &results.corrected_query {
+        println!("did you mean: {corrected}");
+    }
+    println!("{} documents", results.count);
+    for hit in results.documents {
+        let hit = hit?;
+        println!("{}\t{}", hit.document.id.to_string(), hit.document.title);
+    }
+    Ok(())
+}
+
+fn upload(paperless: &Paperless, path: &str) -> Result<(), Error> {
+    let task = paperless.upload_document(document::Upload::from_path(path)?)?;
+    println!("consuming, task {task}");
+    let id = paperless.wait_for_consumption(&task, Duration::from_secs(300))?;
+    println!("created document {}", id.to_string());
+    Ok(())
+}
+
+fn download(paperless: &Paperless, id: u64, output: &str, original: bool) -> Result<(), Error> {
+    let version = if original {
+        FileVersion::Original
+    } else {
+        FileVersion::Archive
+    };
+    let mut content = paperless.document_download_stream(id.into(), version)?;
+    let written = std::io::copy(&mut content, &mut std::fs::File::create(output)?)?;
+    println!("{written} bytes written to {output}");
+    Ok(())
+}
+
+fn bulk_edit(paperless: &Paperless, operation: &str, args: &[&str]) -> Result<(), Error> {
+    let (operation, ids) = match (operation, args) {
+        ("add-tag", [tag, ids @ ..]) => (Operation::AddTag(tag::Id::from(parse_id(tag)?)), ids),
+        ("remove-tag", [tag, ids @ ..]) => {
+            (Operation::RemoveTag(tag::Id::from(parse_id(tag)?)), ids)
+        }
+        ("delete", ids) => (Operation::Delete, ids),
+        ("reprocess", ids) => (Operation::Reprocess, ids),
+        _ => return Err(usage(&format!("unknown operation: {operation}"))),
+    };
+    let ids = ids
+        .iter()
+        .map(|id| parse_id(id).map(document::Id::from))
+        .collect::<Result<Vec<_>, _>>()?;
+    paperless.bulk_edit(&ids, operation)?;
+    println!("{} documents modified", ids.len());
+    Ok(())
+}
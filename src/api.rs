@@ -0,0 +1,291 @@
+//! Trait of the endpoints of the api, to replace the client with a mock in tests
+
+use crate::{
+    asn, bulk_edit, config, correspondent, custom_field, dashboard, date, document, document_type,
+    group, history, mail_account, mail_rule, note, permissions, saved_view, search, share_link,
+    statistics, storage_path, tag, task, trash, ui_settings, user, version, workflow, Error,
+    Paginated, Paperless,
+};
+use serde::de::DeserializeOwned;
+use std::io::Read;
+
+/// Declare the methods of the trait, implemented for [`Paperless`] by calling its methods of the
+/// same name
+macro_rules! api {
+    ($(fn $name:ident(&self $(, $arg:ident: $ty:ty)*) -> $ret:ty;)*) => {
+        /// Every endpoint of the api, implemented by [`Paperless`]
+        ///
+        /// Code taking an `impl PaperlessApi` instead of a [`Paperless`] can be tested without a
+        /// server, with a mock returning canned values. The helpers built on top of the endpoints,
+        /// such as the caches or [`Paperless::wait_for_consumption`], are only available on
+        /// [`Paperless`].
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        /// use paperless::{Error, PaperlessApi};
+        ///
+        /// fn tag_names(api: &impl PaperlessApi) -> Result<Vec<String>, Error> {
+        ///     api.tags(Default::default())?.map(|t| t.map(|t| t.name)).collect()
+        /// }
+        /// ```
+        pub trait PaperlessApi {
+            /// Lazy listing of objects, [`Paginated`] for [`Paperless`], which can be any
+            /// iterator for a mock, like a [`std::vec::IntoIter`]
+            type Listing<'a, T>: Iterator<Item = Result<T, Error>>
+            where
+                Self: 'a,
+                T: DeserializeOwned + 'a;
+
+            /// File of a document read while it is downloaded, [`document::Download`] for
+            /// [`Paperless`], which can be any reader for a mock, like a [`std::io::Cursor`]
+            type Download: Read;
+
+            $(
+                #[doc = concat!("See [`Paperless::", stringify!($name), "`]")]
+                fn $name(&self $(, $arg: $ty)*) -> $ret;
+            )*
+        }
+
+        impl PaperlessApi for Paperless {
+            type Listing<'a, T> = Paginated<'a, T>
+            where
+                T: DeserializeOwned + 'a;
+
+            type Download = document::Download;
+
+            $(
+                fn $name(&self $(, $arg: $ty)*) -> $ret {
+                    Paperless::$name(self $(, $arg)*)
+                }
+            )*
+        }
+    };
+}
+
+api! {
+    fn correspondents(
+        &self,
+        filter: correspondent::Filter
+    ) -> Result<Self::Listing<'_, correspondent::Correspondent>, Error>;
+    fn correspondent(&self, id: correspondent::Id) -> Result<correspondent::Correspondent, Error>;
+    fn correspondent_exists(&self, id: correspondent::Id) -> Result<bool, Error>;
+    fn create_correspondent(
+        &self,
+        correspondent: &correspondent::Create
+    ) -> Result<correspondent::Correspondent, Error>;
+    fn update_correspondent(
+        &self,
+        id: correspondent::Id,
+        update: &correspondent::Update
+    ) -> Result<correspondent::Correspondent, Error>;
+    fn delete_correspondent(&self, id: correspondent::Id) -> Result<(), Error>;
+
+    fn custom_fields(&self) -> Result<Self::Listing<'_, custom_field::CustomField>, Error>;
+    fn custom_field(&self, id: custom_field::Id) -> Result<custom_field::CustomField, Error>;
+    fn create_custom_field(
+        &self,
+        custom_field: &custom_field::Create
+    ) -> Result<custom_field::CustomField, Error>;
+    fn delete_custom_field(&self, id: custom_field::Id) -> Result<(), Error>;
+    fn refresh_custom_fields(&self) -> Result<Vec<custom_field::CustomField>, Error>;
+
+    fn document_types(
+        &self,
+        filter: document_type::Filter
+    ) -> Result<Self::Listing<'_, document_type::DocumentType>, Error>;
+    fn document_type(&self, id: document_type::Id) -> Result<document_type::DocumentType, Error>;
+    fn document_type_exists(&self, id: document_type::Id) -> Result<bool, Error>;
+    fn create_document_type(
+        &self,
+        document_type: &document_type::Create
+    ) -> Result<document_type::DocumentType, Error>;
+    fn update_document_type(
+        &self,
+        id: document_type::Id,
+        update: &document_type::Update
+    ) -> Result<document_type::DocumentType, Error>;
+    fn delete_document_type(&self, id: document_type::Id) -> Result<(), Error>;
+
+    fn documents(
+        &self,
+        filter: document::Filter
+    ) -> Result<Self::Listing<'_, document::Document>, Error>;
+    fn documents_modified_since(
+        &self,
+        since: date::DateTime
+    ) -> Result<Self::Listing<'_, document::Document>, Error>;
+    fn document(&self, id: document::Id) -> Result<document::Document, Error>;
+    fn document_exists(&self, id: document::Id) -> Result<bool, Error>;
+    fn document_thumbnail(&self, id: document::Id) -> Result<document::File, Error>;
+    fn document_preview(&self, id: document::Id) -> Result<document::File, Error>;
+    fn document_size(
+        &self,
+        id: document::Id,
+        version: document::FileVersion
+    ) -> Result<usize, Error>;
+    fn document_metadata(&self, id: document::Id) -> Result<document::Metadata, Error>;
+    fn document_download(
+        &self,
+        id: document::Id,
+        version: document::FileVersion
+    ) -> Result<Vec<u8>, Error>;
+    fn document_download_stream(
+        &self,
+        id: document::Id,
+        version: document::FileVersion
+    ) -> Result<Self::Download, Error>;
+    fn document_history(&self, id: document::Id) -> Result<Vec<history::Entry>, Error>;
+    fn document_page_text(&self, id: document::Id, page: usize) -> Result<Option<String>, Error>;
+    fn upload_document(&self, upload: document::Upload) -> Result<String, Error>;
+    fn update_document(
+        &self,
+        id: document::Id,
+        update: &document::Update
+    ) -> Result<document::Document, Error>;
+    fn set_custom_fields(
+        &self,
+        id: document::Id,
+        values: Vec<(custom_field::Id, Option<custom_field::Value>)>
+    ) -> Result<document::Document, Error>;
+    fn delete_document(&self, id: document::Id) -> Result<(), Error>;
+    fn email_document(
+        &self,
+        id: document::Id,
+        addresses: &[&str],
+        subject: &str,
+        message: &str,
+        use_archive_version: bool
+    ) -> Result<(), Error>;
+
+    fn bulk_edit(&self, ids: &[document::Id], operation: bulk_edit::Operation) -> Result<(), Error>;
+    fn set_document_permissions(
+        &self,
+        ids: &[document::Id],
+        owner: Option<user::Id>,
+        permissions: permissions::Permissions,
+        merge: bool
+    ) -> Result<(), Error>;
+    fn merge_documents(
+        &self,
+        ids: &[document::Id],
+        metadata_from: Option<document::Id>,
+        delete_originals: bool
+    ) -> Result<(), Error>;
+    fn split_document(
+        &self,
+        id: document::Id,
+        pages: Vec<bulk_edit::PageRange>,
+        delete_original: bool
+    ) -> Result<(), Error>;
+    fn rotate_document(&self, id: document::Id, degrees: u16) -> Result<(), Error>;
+    fn delete_pages(&self, id: document::Id, pages: Vec<u32>) -> Result<(), Error>;
+
+    fn document_notes(&self, id: document::Id) -> Result<Vec<note::Note>, Error>;
+    fn add_note(&self, id: document::Id, text: &str) -> Result<Vec<note::Note>, Error>;
+    fn delete_note(&self, id: document::Id, note_id: note::Id) -> Result<Vec<note::Note>, Error>;
+
+    fn share_links(&self) -> Result<Self::Listing<'_, share_link::ShareLink>, Error>;
+    fn document_share_links(&self, id: document::Id) -> Result<Vec<share_link::ShareLink>, Error>;
+    fn create_share_link(
+        &self,
+        share_link: &share_link::Create
+    ) -> Result<share_link::ShareLink, Error>;
+    fn delete_share_link(&self, id: share_link::Id) -> Result<(), Error>;
+
+    fn storage_paths(
+        &self,
+        filter: storage_path::Filter
+    ) -> Result<Self::Listing<'_, storage_path::StoragePath>, Error>;
+    fn storage_path(&self, id: storage_path::Id) -> Result<storage_path::StoragePath, Error>;
+    fn storage_path_exists(&self, id: storage_path::Id) -> Result<bool, Error>;
+    fn create_storage_path(
+        &self,
+        storage_path: &storage_path::Create
+    ) -> Result<storage_path::StoragePath, Error>;
+    fn update_storage_path(
+        &self,
+        id: storage_path::Id,
+        update: &storage_path::Update
+    ) -> Result<storage_path::StoragePath, Error>;
+    fn delete_storage_path(&self, id: storage_path::Id) -> Result<(), Error>;
+
+    fn tags(&self, filters: tag::Filter) -> Result<Self::Listing<'_, tag::Tag>, Error>;
+    fn tag(&self, id: tag::Id) -> Result<tag::Tag, Error>;
+    fn tag_exists(&self, id: tag::Id) -> Result<bool, Error>;
+    fn create_tag(&self, tag: &tag::Create) -> Result<tag::Tag, Error>;
+    fn update_tag(&self, id: tag::Id, update: &tag::Update) -> Result<tag::Tag, Error>;
+    fn merge_tags(&self, from: tag::Id, into: tag::Id) -> Result<(), Error>;
+    fn delete_tag(&self, id: tag::Id) -> Result<(), Error>;
+
+    fn saved_views(&self) -> Result<Self::Listing<'_, saved_view::SaveView>, Error>;
+    fn saved_view(&self, id: saved_view::Id) -> Result<saved_view::SaveView, Error>;
+    fn saved_view_exists(&self, id: saved_view::Id) -> Result<bool, Error>;
+
+    fn users(&self, filters: user::Filter) -> Result<Self::Listing<'_, user::User>, Error>;
+    fn user(&self, id: user::Id) -> Result<user::User, Error>;
+    fn groups(&self, filters: group::Filter) -> Result<Self::Listing<'_, group::Group>, Error>;
+    fn group(&self, id: group::Id) -> Result<group::Group, Error>;
+    fn group_by_name(&self, name: &str) -> Result<Option<group::Group>, Error>;
+
+    fn workflows(&self) -> Result<Self::Listing<'_, workflow::Workflow>, Error>;
+    fn workflow(&self, id: workflow::Id) -> Result<workflow::Workflow, Error>;
+    fn create_workflow(&self, workflow: &workflow::Create) -> Result<workflow::Workflow, Error>;
+    fn update_workflow(
+        &self,
+        id: workflow::Id,
+        update: &workflow::Update
+    ) -> Result<workflow::Workflow, Error>;
+    fn delete_workflow(&self, id: workflow::Id) -> Result<(), Error>;
+
+    fn mail_accounts(&self) -> Result<Self::Listing<'_, mail_account::MailAccount>, Error>;
+    fn mail_account(&self, id: mail_account::Id) -> Result<mail_account::MailAccount, Error>;
+    fn create_mail_account(
+        &self,
+        mail_account: &mail_account::Create
+    ) -> Result<mail_account::MailAccount, Error>;
+    fn update_mail_account(
+        &self,
+        id: mail_account::Id,
+        update: &mail_account::Update
+    ) -> Result<mail_account::MailAccount, Error>;
+    fn delete_mail_account(&self, id: mail_account::Id) -> Result<(), Error>;
+    fn test_mail_account(
+        &self,
+        mail_account: &mail_account::Create,
+        id: Option<mail_account::Id>
+    ) -> Result<bool, Error>;
+
+    fn mail_rules(&self) -> Result<Self::Listing<'_, mail_rule::MailRule>, Error>;
+    fn mail_rule(&self, id: mail_rule::Id) -> Result<mail_rule::MailRule, Error>;
+    fn create_mail_rule(&self, mail_rule: &mail_rule::Create) -> Result<mail_rule::MailRule, Error>;
+    fn update_mail_rule(
+        &self,
+        id: mail_rule::Id,
+        update: &mail_rule::Update
+    ) -> Result<mail_rule::MailRule, Error>;
+    fn delete_mail_rule(&self, id: mail_rule::Id) -> Result<(), Error>;
+
+    fn trash(&self) -> Result<Self::Listing<'_, trash::Deleted>, Error>;
+    fn restore_documents(&self, ids: &[document::Id]) -> Result<(), Error>;
+    fn empty_trash(&self, selection: trash::Selection) -> Result<(), Error>;
+
+    fn task(&self, uuid: &str) -> Result<Option<task::Task>, Error>;
+    fn tasks(&self, filter: task::Filter) -> Result<Vec<task::Task>, Error>;
+    fn acknowledge_tasks(&self, ids: &[task::Id]) -> Result<u64, Error>;
+
+    fn search(&self, query: &str) -> Result<search::SearchResults, Error>;
+    fn search_documents(
+        &self,
+        query: &str
+    ) -> Result<search::DocumentResults<Self::Listing<'_, search::Hit>>, Error>;
+    fn next_asn(&self) -> Result<asn::ASN, Error>;
+    fn statistics(&self) -> Result<statistics::Statistics, Error>;
+    fn dashboard_summary(&self) -> Result<dashboard::Summary, Error>;
+    fn remote_version(&self) -> Result<version::RemoteVersion, Error>;
+    fn logs(&self) -> Result<Vec<String>, Error>;
+    fn log(&self, name: &str) -> Result<Vec<String>, Error>;
+    fn ui_settings(&self) -> Result<ui_settings::UiSettings, Error>;
+    fn config(&self) -> Result<config::Config, Error>;
+    fn update_config(&self, update: &config::Update) -> Result<config::Config, Error>;
+}
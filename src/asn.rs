@@ -3,9 +3,9 @@
 //! Archive serial number is a way to identify a physical document by writing on it a unique number.
 //! It allows to easily find the numeric version of any of your documents.
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Copy, Clone, Deserialize)]
+#[derive(Debug, Copy, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
 pub struct ASN(u64);
 
 impl From<u64> for ASN {
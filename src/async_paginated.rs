@@ -0,0 +1,228 @@
+use crate::paginated::{self, PaginatedResult};
+use crate::query;
+use crate::{AsyncPaperless, Error};
+use futures_util::future::MaybeDone;
+use futures_util::Stream;
+use reqwest::Url;
+use serde::de::DeserializeOwned;
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+type PageFuture<'p, T> =
+    Pin<Box<dyn Future<Output = Result<PaginatedResult<T>, Error>> + Send + 'p>>;
+
+/// Asynchronous version of [`Paginated`](crate::Paginated)
+///
+/// Pages are only requested when all the items of the previous one have been consumed, unless
+/// [`AsyncPaginated::prefetch`] is used. Items are returned by [`AsyncPaginated::next`], or as a
+/// [`Stream`] to use the combinators of `StreamExt`. The stream ends after returning an error.
+pub struct AsyncPaginated<'p, T> {
+    paperless: &'p AsyncPaperless,
+    url: Url,
+    last_result: Option<PaginatedResult<T>>,
+    pages_fetched: u64,
+    /// Request the next page while the current one is consumed
+    prefetch: bool,
+    /// Next page, progressing each time an item is returned by the stream
+    pending: Option<MaybeDone<PageFuture<'p, T>>>,
+    /// An error was returned by the stream
+    failed: bool,
+}
+
+impl<'p, T> AsyncPaginated<'p, T> {
+    pub fn new(paperless: &'p AsyncPaperless, url: Url) -> Self {
+        Self {
+            paperless,
+            url,
+            last_result: None,
+            pages_fetched: 0,
+            prefetch: false,
+            pending: None,
+            failed: false,
+        }
+    }
+
+    /// Number of items requested per page, the server sends 25 by default
+    ///
+    /// Bigger pages need fewer requests to list many items. It has no effect once the first page
+    /// was requested, as the next pages keep the size of the first one.
+    ///
+    /// # Arguments
+    ///
+    /// * `page_size` - Number of items per page, at most 100000
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        query::replace_pair(&mut self.url, "page_size", &page_size.to_string());
+        self
+    }
+}
+
+impl<'p, T> AsyncPaginated<'p, T>
+where
+    T: DeserializeOwned + Send + Unpin + 'p,
+{
+    async fn fetch_next(&mut self) -> Result<(), Error> {
+        let page = match self.pending.take() {
+            Some(MaybeDone::Done(page)) => page?,
+            Some(MaybeDone::Future(page)) => page.await?,
+            Some(MaybeDone::Gone) | None => {
+                let next_url = match &self.last_result {
+                    Some(last) => last.next_url(&self.paperless.server)?,
+                    None => Some(self.url.clone()),
+                };
+                match next_url {
+                    None => return Ok(()),
+                    Some(path) => self.paperless.get(path).await?,
+                }
+            }
+        };
+        self.set_page(page)
+    }
+
+    /// Replace the current page by the next one
+    fn set_page(&mut self, page: PaginatedResult<T>) -> Result<(), Error> {
+        self.last_result = Some(page);
+        self.pages_fetched += 1;
+        if self.prefetch {
+            self.prefetch_next()?;
+        }
+        Ok(())
+    }
+
+    /// Prepare the request of the page after the current one, if there is one
+    ///
+    /// Nothing is sent until the request is polled by [`Stream::poll_next`].
+    fn prefetch_next(&mut self) -> Result<(), Error> {
+        if let Some(last) = &self.last_result {
+            if let Some(url) = last.next_url(&self.paperless.server)? {
+                let page: PageFuture<'p, T> = Box::pin(self.paperless.get(url));
+                self.pending = Some(MaybeDone::Future(page));
+            }
+        }
+        Ok(())
+    }
+
+    /// Request the next page while the current one is consumed, instead of waiting for the
+    /// current one to be consumed
+    ///
+    /// The request progresses each time an item is returned, concurrently with the processing of
+    /// the items, and wakes the task using the listing when it does. A page may be requested even
+    /// if it is never consumed.
+    pub fn prefetch(mut self) -> Result<Self, Error> {
+        self.prefetch = true;
+        if self.pending.is_none() {
+            self.prefetch_next()?;
+        }
+        Ok(self)
+    }
+
+    /// Get the items of the current page which were not returned yet, or the next page if they
+    /// all were
+    ///
+    /// Returns `None` once every item has been returned. It can be mixed with
+    /// [`AsyncPaginated::next`], which continues after the returned page.
+    pub async fn next_page(&mut self) -> Result<Option<Vec<T>>, Error> {
+        if self.last_result.iter().all(|last| last.results.is_empty()) {
+            self.fetch_next().await?;
+        }
+        match &mut self.last_result {
+            Some(last) if !last.results.is_empty() => Ok(Some(std::mem::take(&mut last.results))),
+            _ => Ok(None),
+        }
+    }
+
+    /// Get a page of the listing, without changing the items returned by
+    /// [`AsyncPaginated::next`]
+    ///
+    /// Fails with [`Error::NotFound`] for pages after the last one.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - Number of the page, starting at 1
+    pub async fn page(&self, n: u64) -> Result<Vec<T>, Error> {
+        let url = paginated::page_url(&self.url, n)?;
+        let page: PaginatedResult<T> = self.paperless.get(url).await?;
+        Ok(page.results)
+    }
+
+    /// Number of items of the listing, announced by the server in the first page
+    ///
+    /// The first page is requested if needed, its items are still returned by
+    /// [`AsyncPaginated::next`].
+    pub async fn total_count(&mut self) -> Result<u64, Error> {
+        if self.last_result.is_none() {
+            self.fetch_next().await?;
+        }
+        Ok(self.last_result.as_ref().map_or(0, |last| last.count))
+    }
+
+    /// Number of pages requested to the server so far
+    pub fn pages_fetched(&self) -> u64 {
+        self.pages_fetched
+    }
+
+    /// Get the next item, fetching the next page when needed
+    ///
+    /// Returns `None` once every item has been returned, or after an error.
+    pub async fn next(&mut self) -> Option<Result<T, Error>> {
+        poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+    }
+}
+
+impl<'p, T> Stream for AsyncPaginated<'p, T>
+where
+    T: DeserializeOwned + Send + Unpin + 'p,
+{
+    type Item = Result<T, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.failed {
+            return Poll::Ready(None);
+        }
+        loop {
+            if let Some(last) = &mut this.last_result {
+                if !last.results.is_empty() {
+                    let item = last.results.remove(0);
+                    // The result is kept by `MaybeDone`, the task is woken when it progresses
+                    if let Some(pending) = &mut this.pending {
+                        let _ = Pin::new(pending).poll(cx);
+                    }
+                    return Poll::Ready(Some(Ok(item)));
+                }
+            }
+
+            if this.pending.is_none() {
+                let next_url = match &this.last_result {
+                    Some(last) => last.next_url(&this.paperless.server),
+                    None => Ok(Some(this.url.clone())),
+                };
+                match next_url {
+                    Err(e) => {
+                        this.failed = true;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                    Ok(None) => return Poll::Ready(None),
+                    Ok(Some(url)) => {
+                        let page: PageFuture<'p, T> = Box::pin(this.paperless.get(url));
+                        this.pending = Some(MaybeDone::Future(page));
+                    }
+                }
+            }
+
+            if let Some(pending) = &mut this.pending {
+                if Pin::new(pending).poll(cx).is_pending() {
+                    return Poll::Pending;
+                }
+            }
+            let result = match this.pending.take() {
+                Some(MaybeDone::Done(page)) => page.and_then(|page| this.set_page(page)),
+                _ => Ok(()),
+            };
+            if let Err(e) = result {
+                this.failed = true;
+                return Poll::Ready(Some(Err(e)));
+            }
+        }
+    }
+}
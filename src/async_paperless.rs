@@ -0,0 +1,379 @@
+use crate::routes;
+use crate::server::Server;
+use crate::size;
+use crate::trace;
+use crate::{
+    correspondent, document, document_type, saved_view, storage_path, tag, AsyncPaginated, Error,
+    PaperlessBuilder,
+};
+
+use bytes::Bytes;
+use futures_util::{Stream, StreamExt};
+use reqwest::header::{HeaderValue, RANGE};
+use reqwest::{Client, Method, Request, Response, StatusCode, Url};
+use serde::de::DeserializeOwned;
+
+/// Asynchronous version of [`Paperless`](crate::Paperless)
+///
+/// It exposes the same endpoints, but every call returns a future instead of blocking the thread.
+pub struct AsyncPaperless {
+    pub(crate) http_client: Client,
+    pub(crate) server: Server,
+    size_probe: size::Probe,
+}
+
+impl AsyncPaperless {
+    /// Create a new instance of paperless API
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - Root URL of the api, for example `https://paperless.com/api/`, or of the server
+    /// * `token` - A token to access this instance
+    ///
+    /// # Examples
+    ///
+    /// You can create a new instance like this:
+    /// ```rust
+    /// use paperless::AsyncPaperless;
+    /// let paperless = AsyncPaperless::new("https://example.com/paperless/api/", "thisIsAToken").unwrap();
+    /// ```
+    pub fn new(root: &str, token: &str) -> Result<Self, Error> {
+        PaperlessBuilder::new(root, token).build_async()
+    }
+
+    pub(crate) fn with_server(server: Server, http_client: Client) -> Self {
+        Self {
+            http_client,
+            server,
+            size_probe: size::Probe::default(),
+        }
+    }
+
+    /// Generate a request object with authorization tokens.
+    ///
+    /// Caution: this will take any url and can leak token to wrong destination
+    pub(crate) fn request(&self, method: Method, mut path: Url) -> Request {
+        if method == Method::GET {
+            self.server.prepare_get(&mut path);
+        }
+        let mut request = Request::new(method, path);
+        request.headers_mut().extend(self.server.headers());
+        request
+    }
+
+    /// Send a request and check the status of the response
+    pub(crate) async fn send(&self, request: Request) -> Result<Response, Error> {
+        let traced = trace::Request::start(request.method(), request.url());
+        let result = self.http_client.execute(request).await;
+        traced.finish(result.as_ref().map(Response::status));
+        let response = result?;
+        if response.status() == StatusCode::BAD_REQUEST {
+            return Err(Error::bad_request(&response.bytes().await?));
+        }
+        Error::check_status(response.status())?;
+        Ok(response)
+    }
+
+    /// Send a GET request and deserialize the response
+    pub(crate) async fn get<T: DeserializeOwned>(&self, url: Url) -> Result<T, Error> {
+        let body = self
+            .send(self.request(Method::GET, url))
+            .await?
+            .bytes()
+            .await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Send a GET request and keep the content type of the response
+    async fn file(&self, url: Url) -> Result<document::File, Error> {
+        let response = self.send(self.request(Method::GET, url)).await?;
+        let headers = response.headers().clone();
+        let content = response.bytes().await?.to_vec();
+        Ok(document::File::from_headers(&headers, content))
+    }
+
+    /// Generate the url to download a version of a document
+    fn url_download(&self, id: document::Id, version: document::FileVersion) -> Result<Url, Error> {
+        let mut url = self.url_api(&routes::document_download(id))?;
+        version.insert_query(&mut url);
+        Ok(url)
+    }
+
+    /// Generate the url of an endpoint
+    fn url_api(&self, path: &str) -> Result<Url, Error> {
+        self.server.url_api(path)
+    }
+
+    /// List all the correspondents, page by page to avoid loading everything
+    ///
+    /// # Arguments
+    ///
+    /// * `filters` - Filter to apply during the listing of all document types
+    pub fn correspondents(
+        &self,
+        filter: correspondent::Filter,
+    ) -> Result<AsyncPaginated<'_, correspondent::Correspondent>, Error> {
+        let mut url = self.server.url_list(routes::CORRESPONDENTS)?;
+        filter.insert_query(&mut url);
+        Ok(AsyncPaginated::new(self, url))
+    }
+
+    /// List all the document types, page by page to avoid loading everything
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - Filter to apply during the listing of all document types
+    pub fn document_types(
+        &self,
+        filter: document_type::Filter,
+    ) -> Result<AsyncPaginated<'_, document_type::DocumentType>, Error> {
+        let mut url = self.server.url_list(routes::DOCUMENT_TYPES)?;
+        filter.insert_query(&mut url);
+        Ok(AsyncPaginated::new(self, url))
+    }
+
+    /// List all documents, page by page to avoid loading everything
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - Filter to apply during the listing of all documents
+    pub fn documents(
+        &self,
+        filter: document::Filter,
+    ) -> Result<AsyncPaginated<'_, document::Document>, Error> {
+        let mut url = self.server.url_list(routes::DOCUMENTS)?;
+        filter.insert_query(&mut url);
+        Ok(AsyncPaginated::new(self, url))
+    }
+
+    /// List all storage paths, page by page to avoid loading everything
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - Filter to apply during the listing of all storage paths
+    pub fn storage_paths(
+        &self,
+        filter: storage_path::Filter,
+    ) -> Result<AsyncPaginated<'_, storage_path::StoragePath>, Error> {
+        let mut url = self.server.url_list(routes::STORAGE_PATHS)?;
+        filter.insert_query(&mut url);
+        Ok(AsyncPaginated::new(self, url))
+    }
+
+    /// List all tags, page by page to avoid loading everything
+    ///
+    /// # Arguments
+    ///
+    /// * `filters` - Filter to apply during the listing of all tags
+    pub fn tags(&self, filters: tag::Filter) -> Result<AsyncPaginated<'_, tag::Tag>, Error> {
+        let mut url = self.server.url_list(routes::TAGS)?;
+        filters.insert_query(&mut url);
+        Ok(AsyncPaginated::new(self, url))
+    }
+
+    /// List all saved views, page by page to avoid loading everything
+    pub fn saved_views(&self) -> Result<AsyncPaginated<'_, saved_view::SaveView>, Error> {
+        Ok(AsyncPaginated::new(
+            self,
+            self.server.url_list(routes::SAVED_VIEWS)?,
+        ))
+    }
+
+    /// Get information about a correspondent
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the correspondent from which you are trying to retrieve information
+    pub async fn correspondent(
+        &self,
+        id: correspondent::Id,
+    ) -> Result<correspondent::Correspondent, Error> {
+        self.get(self.url_api(&routes::correspondent(id))?).await
+    }
+
+    /// Get information about a document_type
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document_type from which you are trying to retrieve information
+    pub async fn document_type(
+        &self,
+        id: document_type::Id,
+    ) -> Result<document_type::DocumentType, Error> {
+        self.get(self.url_api(&routes::document_type(id))?).await
+    }
+
+    /// Get information about a document
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document from which you are trying to retrieve information
+    pub async fn document(&self, id: document::Id) -> Result<document::Document, Error> {
+        self.get(self.url_api(&routes::document(id))?).await
+    }
+
+    /// Get information about a storage path
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the storage path from which you are trying to retrieve information
+    pub async fn storage_path(
+        &self,
+        id: storage_path::Id,
+    ) -> Result<storage_path::StoragePath, Error> {
+        self.get(self.url_api(&routes::storage_path(id))?).await
+    }
+
+    /// Get information about a tag
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the tag from which you are trying to retrieve information
+    pub async fn tag(&self, id: tag::Id) -> Result<tag::Tag, Error> {
+        self.get(self.url_api(&routes::tag(id))?).await
+    }
+
+    /// Get information about a view
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the view from which you are trying to retrieve information
+    pub async fn saved_view(&self, id: saved_view::Id) -> Result<saved_view::SaveView, Error> {
+        self.get(self.url_api(&routes::saved_view(id))?).await
+    }
+
+    /// Get the thumbnail of a document, an image of its first page
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document
+    pub async fn document_thumbnail(&self, id: document::Id) -> Result<document::File, Error> {
+        self.file(self.url_api(&routes::document_thumbnail(id))?)
+            .await
+    }
+
+    /// Get the version of a document meant to be shown inline, the archived PDF when there is one
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document
+    pub async fn document_preview(&self, id: document::Id) -> Result<document::File, Error> {
+        self.file(self.url_api(&routes::document_preview(id))?)
+            .await
+    }
+
+    /// Get the size in bytes of a document, without downloading it
+    ///
+    /// The size is read with a HEAD request. If a proxy blocks them, the first byte of the file
+    /// or the metadata of the document are requested instead, and this method is remembered.
+    /// Encrypted documents (see [`document::Document::is_encrypted`]) can't be read partially,
+    /// their size is read from the metadata when the server sends the whole file instead of its
+    /// first byte.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document
+    /// * `version` - The version of the file
+    pub async fn document_size(
+        &self,
+        id: document::Id,
+        version: document::FileVersion,
+    ) -> Result<usize, Error> {
+        let url = self.url_download(id, version)?;
+        match self.size_probe.get() {
+            Some(size::Method::Head) => return self.size_head(url).await,
+            Some(size::Method::Range) => {
+                return match self.size_range(url).await? {
+                    Some(size) => Ok(size),
+                    None => self.size_metadata(id, version).await,
+                }
+            }
+            Some(size::Method::Metadata) => return self.size_metadata(id, version).await,
+            None => {}
+        }
+
+        let (method, result) = match self.size_head(url.clone()).await {
+            Err(e) if size::is_blocked(&e) => match self.size_range(url).await {
+                Err(e) if size::is_blocked(&e) => (
+                    size::Method::Metadata,
+                    self.size_metadata(id, version).await,
+                ),
+                // Nothing is learnt from an encrypted file, the next one may support ranges
+                Ok(None) => return self.size_metadata(id, version).await,
+                Ok(Some(size)) => (size::Method::Range, Ok(size)),
+                Err(e) => (size::Method::Range, Err(e)),
+            },
+            result => (size::Method::Head, result),
+        };
+        if result.is_ok() {
+            self.size_probe.set(method);
+        }
+        result
+    }
+
+    async fn size_head(&self, url: Url) -> Result<usize, Error> {
+        let response = self.send(self.request(Method::HEAD, url)).await?;
+        size::from_content_length(response.headers())
+    }
+
+    async fn size_range(&self, url: Url) -> Result<Option<usize>, Error> {
+        let mut request = self.request(Method::GET, url);
+        request
+            .headers_mut()
+            .insert(RANGE, HeaderValue::from_static(size::FIRST_BYTE));
+        let response = self.send(request).await?;
+        size::from_content_range(response.status(), response.headers())
+    }
+
+    async fn size_metadata(
+        &self,
+        id: document::Id,
+        version: document::FileVersion,
+    ) -> Result<usize, Error> {
+        size::from_metadata(self.document_metadata(id).await?.size(version))
+    }
+
+    /// Get the checksums, sizes and types of the files of a document
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document
+    pub async fn document_metadata(&self, id: document::Id) -> Result<document::Metadata, Error> {
+        self.get(self.url_api(&routes::document_metadata(id))?)
+            .await
+    }
+
+    /// Download a document
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document to download
+    /// * `version` - The version of the file, the original one or the archived PDF
+    pub async fn document_download(
+        &self,
+        id: document::Id,
+        version: document::FileVersion,
+    ) -> Result<Vec<u8>, Error> {
+        let request = self.request(Method::GET, self.url_download(id, version)?);
+        Ok(self.send(request).await?.bytes().await?.to_vec())
+    }
+
+    /// Download a document chunk by chunk, without loading it in memory
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document to download
+    /// * `version` - The version of the file, the original one or the archived PDF
+    pub async fn document_download_stream(
+        &self,
+        id: document::Id,
+        version: document::FileVersion,
+    ) -> Result<impl Stream<Item = Result<Bytes, Error>>, Error> {
+        let request = self.request(Method::GET, self.url_download(id, version)?);
+        Ok(self
+            .send(request)
+            .await?
+            .bytes_stream()
+            .map(|chunk| chunk.map_err(Error::from)))
+    }
+}
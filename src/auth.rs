@@ -0,0 +1,65 @@
+//! # Authentication
+//!
+//! How the clients authenticate to the server, see
+//! [`PaperlessBuilder::auth`](crate::PaperlessBuilder::auth).
+
+use crate::Error;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, COOKIE};
+
+#[derive(Debug, Clone)]
+pub enum Auth {
+    /// Token of a user, created in the profile of the user or with
+    /// [`Paperless::login`](crate::Paperless::login)
+    Token(String),
+    /// Username and password of a user, sent with every request
+    Basic { user: String, pass: String },
+    /// Session of the web interface, for deployments where the other methods are disabled
+    SessionCookie {
+        /// Value of the `sessionid` cookie
+        session_id: String,
+        /// Value of the `csrftoken` cookie, needed to modify objects
+        csrf_token: Option<String>,
+    },
+}
+
+impl Auth {
+    /// Headers authenticating every request
+    ///
+    /// Fails with [`Error::InvalidToken`] if the credentials contain characters which are not
+    /// allowed in a header.
+    pub(crate) fn headers(&self) -> Result<HeaderMap, Error> {
+        let mut headers = HeaderMap::new();
+        match self {
+            Auth::Token(token) => {
+                headers.insert(AUTHORIZATION, sensitive(&format!("Token {token}"))?);
+            }
+            Auth::Basic { user, pass } => {
+                let credentials = STANDARD.encode(format!("{user}:{pass}"));
+                headers.insert(AUTHORIZATION, sensitive(&format!("Basic {credentials}"))?);
+            }
+            Auth::SessionCookie {
+                session_id,
+                csrf_token,
+            } => match csrf_token {
+                Some(csrf_token) => {
+                    let cookie = format!("sessionid={session_id}; csrftoken={csrf_token}");
+                    headers.insert(COOKIE, sensitive(&cookie)?);
+                    headers.insert("X-CSRFToken", sensitive(csrf_token)?);
+                }
+                None => {
+                    headers.insert(COOKIE, sensitive(&format!("sessionid={session_id}"))?);
+                }
+            },
+        }
+        Ok(headers)
+    }
+}
+
+/// Header value which is never printed in debug output
+fn sensitive(value: &str) -> Result<HeaderValue, Error> {
+    let mut value = HeaderValue::from_str(value)?;
+    value.set_sensitive(true);
+    Ok(value)
+}
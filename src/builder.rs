@@ -0,0 +1,368 @@
+use crate::auth::Auth;
+use crate::rate_limit::RateLimit;
+use crate::retry::RetryPolicy;
+use crate::server::Server;
+use crate::{routes, Error, Paperless};
+#[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+use reqwest::Certificate;
+use reqwest::Proxy;
+use std::time::Duration;
+
+/// Apply the settings of a [`PaperlessBuilder`] to a `ClientBuilder`, the blocking and the
+/// asynchronous ones having the same methods
+macro_rules! configure_client {
+    ($settings:expr, $builder:expr) => {{
+        let settings = &$settings;
+        let mut builder = $builder;
+        if let Some(timeout) = settings.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = settings.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(proxy) = &settings.proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+        if let Some(user_agent) = &settings.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+        {
+            for certificate in &settings.root_certificates {
+                builder = builder.add_root_certificate(certificate.clone());
+            }
+            builder = builder.danger_accept_invalid_certs(settings.accept_invalid_certs);
+        }
+        builder
+    }};
+}
+
+/// Builder to configure a [`Paperless`] client
+///
+/// # Examples
+///
+/// ```rust
+/// use paperless::PaperlessBuilder;
+/// let paperless = PaperlessBuilder::new("https://example.com/paperless/api/", "thisIsAToken")
+///     .default_query("truncate_content", "true")
+///     .timeout(std::time::Duration::from_secs(30))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct PaperlessBuilder {
+    root: String,
+    auth: Auth,
+    headers: Vec<(String, String)>,
+    default_query: Vec<(String, String)>,
+    full_permissions: bool,
+    client: Option<reqwest::blocking::Client>,
+    #[cfg(feature = "tokio")]
+    async_client: Option<reqwest::Client>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxy: Option<Proxy>,
+    user_agent: Option<String>,
+    retry: RetryPolicy,
+    rate_limit: RateLimit,
+    http_cache: Option<usize>,
+    cache_ttl: Option<Duration>,
+    #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+    root_certificates: Vec<Certificate>,
+    #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+    accept_invalid_certs: bool,
+}
+
+impl PaperlessBuilder {
+    /// Create a new builder
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - Root URL of the api, for example `https://paperless.com/api/`, or of the server
+    /// * `token` - A token to access this instance
+    pub fn new(root: &str, token: &str) -> Self {
+        Self {
+            root: root.to_string(),
+            auth: Auth::Token(token.to_string()),
+            headers: Vec::new(),
+            default_query: Vec::new(),
+            full_permissions: false,
+            client: None,
+            #[cfg(feature = "tokio")]
+            async_client: None,
+            connect_timeout: None,
+            timeout: None,
+            proxy: None,
+            user_agent: None,
+            retry: RetryPolicy::never(),
+            rate_limit: RateLimit::default(),
+            http_cache: None,
+            cache_ttl: None,
+            #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+            root_certificates: Vec::new(),
+            #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+            accept_invalid_certs: false,
+        }
+    }
+
+    /// Authenticate with another method than the token given to [`PaperlessBuilder::new`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use paperless::auth::Auth;
+    /// use paperless::PaperlessBuilder;
+    /// let auth = Auth::Basic {
+    ///     user: "user".to_string(),
+    ///     pass: "password".to_string(),
+    /// };
+    /// let paperless = PaperlessBuilder::new("https://example.com/paperless/api/", "")
+    ///     .auth(auth)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn auth(mut self, auth: Auth) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Add a header to every request, for example the credentials expected by a reverse proxy
+    ///
+    /// Invalid names or values make [`PaperlessBuilder::build`] fail with
+    /// [`Error::InvalidArgument`].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the header, for example `CF-Access-Client-Id`
+    /// * `value` - Value of the header
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Add a query parameter to every listing request
+    ///
+    /// Parameters set by filters are added after this one, so the server uses them instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Name of the parameter, for example `ordering`
+    /// * `value` - Value of the parameter
+    pub fn default_query(mut self, key: &str, value: &str) -> Self {
+        self.default_query
+            .push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Ask the server for the owner and the permissions of every object, in the `permissions`
+    /// field of the models
+    pub fn full_permissions(mut self) -> Self {
+        self.full_permissions = true;
+        self
+    }
+
+    /// Use an already configured http client, for the blocking client
+    ///
+    /// The timeouts, the proxy, the user agent and the certificates set on this builder are not
+    /// applied to it.
+    pub fn client(mut self, client: reqwest::blocking::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Use an already configured http client, for the asynchronous client
+    ///
+    /// The timeouts, the proxy, the user agent and the certificates set on this builder are not
+    /// applied to it.
+    #[cfg(feature = "tokio")]
+    pub fn async_client(mut self, client: reqwest::Client) -> Self {
+        self.async_client = Some(client);
+        self
+    }
+
+    /// Maximum duration to establish the connection to the server
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Maximum duration of a request, from the connection to the end of the response
+    ///
+    /// Without it, the blocking client waits 30 seconds and the asynchronous one waits forever.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Send the requests through a proxy, instead of the ones of the environment
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Value of the `User-Agent` header sent with every request
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// Trust a certificate authority, in addition to the ones of the system, for example the one
+    /// which signed a self-signed certificate
+    ///
+    /// # Arguments
+    ///
+    /// * `certificate` - The certificate, read with [`Certificate::from_pem`] or
+    ///   [`Certificate::from_der`]
+    #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+    pub fn add_root_certificate(mut self, certificate: Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// Accept any certificate, even expired ones or ones for another host
+    ///
+    /// The connection is not authenticated anymore, so anyone able to intercept it can read the
+    /// token. Prefer [`PaperlessBuilder::add_root_certificate`] for self-signed certificates.
+    #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.accept_invalid_certs = accept;
+        self
+    }
+
+    /// Send idempotent requests again after transient failures, only for the blocking client
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use paperless::retry::RetryPolicy;
+    /// use paperless::PaperlessBuilder;
+    /// let paperless = PaperlessBuilder::new("https://example.com/paperless/api/", "thisIsAToken")
+    ///     .retry(RetryPolicy {
+    ///         max_attempts: 5,
+    ///         ..Default::default()
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Maximum number of requests sent per second by the blocking client, to avoid overloading
+    /// small servers during bulk operations
+    pub fn max_requests_per_second(mut self, requests: f64) -> Self {
+        self.rate_limit.per_second = Some(requests);
+        self
+    }
+
+    /// Longest delay waited when the server answers `429 Too Many Requests` or
+    /// `503 Service Unavailable` with a `Retry-After`, 60 seconds by default
+    ///
+    /// The blocking client sends such requests again after the delay asked by the server, up to 3
+    /// times. If the server asks for a longer delay, the request fails immediately.
+    pub fn max_retry_after(mut self, delay: Duration) -> Self {
+        self.rate_limit.max_retry_after = delay;
+        self
+    }
+
+    /// Keep the responses which have an `ETag` or a `Last-Modified` header, and send conditional
+    /// requests for them, so that the server doesn't send them again if they were not modified
+    ///
+    /// Only the blocking client uses it, for the requests returning json.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_entries` - Number of responses kept, the oldest ones are forgotten first
+    pub fn http_cache(mut self, max_entries: usize) -> Self {
+        self.http_cache = Some(max_entries);
+        self
+    }
+
+    /// How long the correspondents, document types, storage paths, tags and custom fields are
+    /// kept in cache, forever by default
+    ///
+    /// They are loaded again on first use after this delay, to see the modifications done by
+    /// other clients. See [`Paperless::cached_tags`] and the other `cached_*` methods.
+    pub fn cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = Some(ttl);
+        self
+    }
+
+    fn server(&self) -> Result<Server, Error> {
+        Server::new(
+            &self.root,
+            &self.auth,
+            &self.headers,
+            self.default_query.clone(),
+            self.full_permissions,
+        )
+    }
+
+    fn blocking_client(&self) -> Result<reqwest::blocking::Client, Error> {
+        match &self.client {
+            Some(client) => Ok(client.clone()),
+            None => Ok(configure_client!(self, reqwest::blocking::Client::builder()).build()?),
+        }
+    }
+
+    /// Create the client, with a token obtained from the credentials of a user
+    ///
+    /// The token is requested with the connection settings of the builder, and replaces the
+    /// authentication given to [`PaperlessBuilder::new`] or [`PaperlessBuilder::auth`].
+    ///
+    /// Fails with [`Error::Unauthorized`] if the server refuses the credentials.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - Name of the user
+    /// * `password` - Password of the user
+    pub fn login(mut self, username: &str, password: &str) -> Result<Paperless, Error> {
+        #[derive(serde::Deserialize)]
+        struct Token {
+            token: String,
+        }
+
+        let client = self.blocking_client()?;
+        let url = Server::parse_root(&self.root)?.join(routes::TOKEN)?;
+        let mut request = client
+            .post(url)
+            .json(&serde_json::json!({ "username": username, "password": password }));
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        let response = request.send()?;
+        // Invalid credentials are reported as a validation error
+        if response.status() == reqwest::StatusCode::BAD_REQUEST {
+            return Err(Error::Unauthorized);
+        }
+        Error::check_status(response.status())?;
+        let token: Token = serde_json::from_slice(&response.bytes()?)?;
+        self.auth = Auth::Token(token.token);
+        self.client = Some(client);
+        self.build()
+    }
+
+    /// Create the client
+    pub fn build(self) -> Result<Paperless, Error> {
+        let client = self.blocking_client()?;
+        Ok(Paperless::with_server(
+            self.server()?,
+            client,
+            self.retry,
+            self.rate_limit,
+            self.http_cache,
+            self.cache_ttl,
+        ))
+    }
+
+    /// Create the asynchronous client
+    #[cfg(feature = "tokio")]
+    pub fn build_async(self) -> Result<crate::AsyncPaperless, Error> {
+        let client = match &self.async_client {
+            Some(client) => client.clone(),
+            None => configure_client!(self, reqwest::Client::builder()).build()?,
+        };
+        Ok(crate::AsyncPaperless::with_server(self.server()?, client))
+    }
+}
@@ -0,0 +1,188 @@
+//! # Bulk edit
+//!
+//! Apply the same operation to many documents with a single request, see
+//! [`Paperless::bulk_edit`](crate::Paperless::bulk_edit).
+
+use crate::{correspondent, document, document_type, storage_path, tag, user, Error};
+use serde_json::json;
+use std::fmt;
+
+pub use crate::permissions::{Access, Permissions};
+
+/// Pages of a document, from `first` to `last` included, starting at 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageRange {
+    first: u32,
+    last: u32,
+}
+
+impl PageRange {
+    /// Fails with [`Error::InvalidArgument`] if `first` is 0 or after `last`
+    pub fn new(first: u32, last: u32) -> Result<Self, Error> {
+        if first == 0 || first > last {
+            return Err(Error::InvalidArgument(format!(
+                "invalid page range {first}-{last}"
+            )));
+        }
+        Ok(Self { first, last })
+    }
+
+    /// A single page, fails with [`Error::InvalidArgument`] if `page` is 0
+    pub fn single(page: u32) -> Result<Self, Error> {
+        Self::new(page, page)
+    }
+
+    pub fn first(&self) -> u32 {
+        self.first
+    }
+
+    pub fn last(&self) -> u32 {
+        self.last
+    }
+}
+
+impl fmt::Display for PageRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.first == self.last {
+            write!(f, "{}", self.first)
+        } else {
+            write!(f, "{}-{}", self.first, self.last)
+        }
+    }
+}
+
+/// Rotation of the pages, clockwise
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    Quarter,
+    Half,
+    ThreeQuarters,
+}
+
+impl Rotation {
+    fn degrees(self) -> u16 {
+        match self {
+            Rotation::Quarter => 90,
+            Rotation::Half => 180,
+            Rotation::ThreeQuarters => 270,
+        }
+    }
+}
+
+impl TryFrom<u16> for Rotation {
+    type Error = Error;
+
+    /// Fails with [`Error::InvalidArgument`] for other values than 90, 180 and 270
+    fn try_from(degrees: u16) -> Result<Self, Self::Error> {
+        match degrees {
+            90 => Ok(Rotation::Quarter),
+            180 => Ok(Rotation::Half),
+            270 => Ok(Rotation::ThreeQuarters),
+            _ => Err(Error::InvalidArgument(format!(
+                "invalid rotation of {degrees} degrees"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Operation {
+    /// Set the correspondent, `None` to remove it
+    SetCorrespondent(Option<correspondent::Id>),
+    /// Set the document type, `None` to remove it
+    SetDocumentType(Option<document_type::Id>),
+    /// Set the storage path, `None` to use the default one
+    SetStoragePath(Option<storage_path::Id>),
+    AddTag(tag::Id),
+    RemoveTag(tag::Id),
+    /// Add and remove several tags at once
+    ModifyTags {
+        add: Vec<tag::Id>,
+        remove: Vec<tag::Id>,
+    },
+    Delete,
+    /// Consume the files again, to update their content and archived version
+    Reprocess,
+    SetPermissions {
+        /// `None` to remove the owner
+        owner: Option<user::Id>,
+        permissions: Permissions,
+        /// Add to the current permissions instead of replacing them, the owner is only set on
+        /// documents without owner
+        merge: bool,
+    },
+    /// Merge the documents into a new one, in the given order
+    Merge {
+        /// Document from which the metadata are copied, none are set if `None`
+        metadata_document: Option<document::Id>,
+        delete_originals: bool,
+    },
+    /// Split a document, each range of pages becomes a new document
+    Split {
+        pages: Vec<PageRange>,
+        delete_originals: bool,
+    },
+    Rotate(Rotation),
+    /// Delete pages, starting at 1
+    DeletePages(Vec<u32>),
+}
+
+impl Operation {
+    /// Name of the operation expected by the server
+    pub(crate) fn method(&self) -> &'static str {
+        match self {
+            Operation::SetCorrespondent(_) => "set_correspondent",
+            Operation::SetDocumentType(_) => "set_document_type",
+            Operation::SetStoragePath(_) => "set_storage_path",
+            Operation::AddTag(_) => "add_tag",
+            Operation::RemoveTag(_) => "remove_tag",
+            Operation::ModifyTags { .. } => "modify_tags",
+            Operation::Delete => "delete",
+            Operation::Reprocess => "reprocess",
+            Operation::SetPermissions { .. } => "set_permissions",
+            Operation::Merge { .. } => "merge",
+            Operation::Split { .. } => "split",
+            Operation::Rotate(_) => "rotate",
+            Operation::DeletePages(_) => "delete_pages",
+        }
+    }
+
+    /// Parameters of the operation expected by the server
+    pub(crate) fn parameters(&self) -> serde_json::Value {
+        match self {
+            Operation::SetCorrespondent(correspondent) => json!({ "correspondent": correspondent }),
+            Operation::SetDocumentType(document_type) => json!({ "document_type": document_type }),
+            Operation::SetStoragePath(storage_path) => json!({ "storage_path": storage_path }),
+            Operation::AddTag(tag) | Operation::RemoveTag(tag) => json!({ "tag": tag }),
+            Operation::ModifyTags { add, remove } => {
+                json!({ "add_tags": add, "remove_tags": remove })
+            }
+            Operation::Delete | Operation::Reprocess => json!({}),
+            Operation::SetPermissions {
+                owner,
+                permissions,
+                merge,
+            } => json!({
+                "owner": owner,
+                "set_permissions": permissions,
+                "merge": merge,
+            }),
+            Operation::Merge {
+                metadata_document,
+                delete_originals,
+            } => json!({
+                "metadata_document_id": metadata_document,
+                "delete_originals": delete_originals,
+            }),
+            Operation::Split {
+                pages,
+                delete_originals,
+            } => {
+                let pages: Vec<_> = pages.iter().map(PageRange::to_string).collect();
+                json!({ "pages": pages.join(","), "delete_originals": delete_originals })
+            }
+            Operation::Rotate(rotation) => json!({ "degrees": rotation.degrees() }),
+            Operation::DeletePages(pages) => json!({ "pages": pages }),
+        }
+    }
+}
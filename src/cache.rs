@@ -0,0 +1,405 @@
+//! Cache of the entities referenced by documents, to avoid requesting them for every document
+
+use crate::{
+    correspondent, custom_field, document, document_type, slug, storage_path, tag, Error, Paperless,
+};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::{Duration, Instant};
+
+/// Entities of one kind, loaded all at once on first use, and again once they expire
+///
+/// Requests to the server are sent without holding the lock, so that other threads can still
+/// read the cache meanwhile. The generation is increased on every modification: what was loaded
+/// is only stored if the cache was not modified during the request, otherwise it may overwrite a
+/// newer version.
+pub(crate) struct EntityCache<I, T> {
+    entries: RwLock<Option<Loaded<I, T>>>,
+    generation: AtomicU64,
+    /// How long the entities are kept after being loaded, forever if `None`
+    ttl: Option<Duration>,
+}
+
+struct Loaded<I, T> {
+    entities: HashMap<I, T>,
+    at: Instant,
+}
+
+impl<I, T> Loaded<I, T> {
+    fn new(entities: HashMap<I, T>) -> Self {
+        Self {
+            entities,
+            at: Instant::now(),
+        }
+    }
+
+    fn is_fresh(&self, ttl: Option<Duration>) -> bool {
+        ttl.iter().all(|ttl| self.at.elapsed() < *ttl)
+    }
+}
+
+impl<I, T> EntityCache<I, T>
+where
+    I: Copy + Eq + Hash,
+    T: Clone,
+{
+    pub(crate) fn new(ttl: Option<Duration>) -> Self {
+        Self {
+            entries: RwLock::new(None),
+            generation: AtomicU64::new(0),
+            ttl,
+        }
+    }
+
+    /// Get an entity from the cache
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Id of the entity
+    /// * `load_all` - Load every entity, used the first time the cache is accessed
+    /// * `load_one` - Load a single entity, used when the entity was created after the cache was
+    ///   loaded
+    pub(crate) fn get(
+        &self,
+        id: I,
+        load_all: impl FnOnce() -> Result<Vec<(I, T)>, Error>,
+        load_one: impl FnOnce(I) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        if let Some(entity) = self.with_loaded(load_all, |entries| entries.get(&id).cloned())? {
+            return Ok(entity);
+        }
+        let generation = self.generation();
+        let entity = load_one(id)?;
+        let mut entries = self.write();
+        if let Some(loaded) = &mut *entries {
+            if generation == self.generation() {
+                loaded.entities.insert(id, entity.clone());
+            }
+        }
+        Ok(entity)
+    }
+
+    /// Every entity in the cache
+    ///
+    /// # Arguments
+    ///
+    /// * `load_all` - Load every entity, used when the cache is not loaded or expired
+    pub(crate) fn all(
+        &self,
+        load_all: impl FnOnce() -> Result<Vec<(I, T)>, Error>,
+    ) -> Result<Vec<T>, Error> {
+        self.with_loaded(load_all, |entries| entries.values().cloned().collect())
+    }
+
+    /// Find an entity in the cache
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate` - Function returning true for the searched entity
+    /// * `load_all` - Load every entity, used the first time the cache is accessed
+    pub(crate) fn find(
+        &self,
+        predicate: impl Fn(&T) -> bool,
+        load_all: impl FnOnce() -> Result<Vec<(I, T)>, Error>,
+    ) -> Result<Option<T>, Error> {
+        self.with_loaded(load_all, |entries| {
+            entries.values().find(|e| predicate(e)).cloned()
+        })
+    }
+
+    /// Run `f` on the entities, loading them first if needed
+    fn with_loaded<R>(
+        &self,
+        load_all: impl FnOnce() -> Result<Vec<(I, T)>, Error>,
+        f: impl FnOnce(&HashMap<I, T>) -> R,
+    ) -> Result<R, Error> {
+        if let Some(loaded) = self.fresh(&self.read()) {
+            return Ok(f(&loaded.entities));
+        }
+        let generation = self.generation();
+        let loaded: HashMap<I, T> = load_all()?.into_iter().collect();
+        let mut entries = self.write();
+        if self.fresh(&entries).is_none() && generation == self.generation() {
+            return Ok(f(&entries.insert(Loaded::new(loaded)).entities));
+        }
+        // The cache was modified meanwhile: prefer what another thread stored, and otherwise use
+        // what was loaded without storing it
+        match self.fresh(&entries) {
+            Some(stored) => Ok(f(&stored.entities)),
+            None => Ok(f(&loaded)),
+        }
+    }
+
+    /// The loaded entities, unless they expired
+    fn fresh<'e>(&self, entries: &'e Option<Loaded<I, T>>) -> Option<&'e Loaded<I, T>> {
+        entries.as_ref().filter(|loaded| loaded.is_fresh(self.ttl))
+    }
+
+    /// Replace the content of the cache
+    pub(crate) fn replace(&self, entities: Vec<(I, T)>) {
+        let mut entries = self.write();
+        *entries = Some(Loaded::new(entities.into_iter().collect()));
+        self.generation.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Forget every entity, they are loaded again on next use
+    pub(crate) fn clear(&self) {
+        let mut entries = self.write();
+        *entries = None;
+        self.generation.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Keep the cache up to date after a modification: `None` means the entity was deleted
+    ///
+    /// Nothing is stored if the cache is not loaded yet, it will be loaded with the new version.
+    pub(crate) fn update(&self, id: I, entity: Option<T>) {
+        let mut entries = self.write();
+        if let Some(loaded) = &mut *entries {
+            match entity {
+                Some(entity) => loaded.entities.insert(id, entity),
+                None => loaded.entities.remove(&id),
+            };
+        }
+        self.generation.fetch_add(1, Ordering::AcqRel);
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Option<Loaded<I, T>>> {
+        self.entries.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Option<Loaded<I, T>>> {
+        self.entries.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Every entity cache of a client
+pub(crate) struct Cache {
+    pub(crate) correspondents: EntityCache<correspondent::Id, correspondent::Correspondent>,
+    pub(crate) custom_fields: EntityCache<custom_field::Id, custom_field::CustomField>,
+    /// Version of the server when the custom fields were loaded
+    custom_fields_version: Mutex<Option<String>>,
+    pub(crate) document_types: EntityCache<document_type::Id, document_type::DocumentType>,
+    pub(crate) storage_paths: EntityCache<storage_path::Id, storage_path::StoragePath>,
+    pub(crate) tags: EntityCache<tag::Id, tag::Tag>,
+    /// Last version reported by the server
+    server_version: Mutex<Option<String>>,
+    /// Titles of the documents seen by this client, from the oldest to the most recent
+    titles: Mutex<HashMap<document::Id, Vec<String>>>,
+}
+
+impl Cache {
+    /// Create the caches
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - How long the entities are kept, forever if `None`
+    pub(crate) fn new(ttl: Option<Duration>) -> Self {
+        Self {
+            correspondents: EntityCache::new(ttl),
+            custom_fields: EntityCache::new(ttl),
+            custom_fields_version: Mutex::default(),
+            document_types: EntityCache::new(ttl),
+            storage_paths: EntityCache::new(ttl),
+            tags: EntityCache::new(ttl),
+            server_version: Mutex::default(),
+            titles: Mutex::default(),
+        }
+    }
+
+    pub(crate) fn correspondent(
+        &self,
+        paperless: &Paperless,
+        id: correspondent::Id,
+    ) -> Result<correspondent::Correspondent, Error> {
+        self.correspondents.get(
+            id,
+            || Self::all_correspondents(paperless),
+            |id| paperless.correspondent(id),
+        )
+    }
+
+    /// Find a correspondent by its name, ignoring the case like the server does
+    pub(crate) fn correspondent_by_name(
+        &self,
+        paperless: &Paperless,
+        name: &str,
+    ) -> Result<Option<correspondent::Correspondent>, Error> {
+        self.correspondents.find(
+            |c| slug::same_name(&c.name, name),
+            || Self::all_correspondents(paperless),
+        )
+    }
+
+    pub(crate) fn cached_correspondents(
+        &self,
+        paperless: &Paperless,
+    ) -> Result<Vec<correspondent::Correspondent>, Error> {
+        self.correspondents
+            .all(|| Self::all_correspondents(paperless))
+    }
+
+    fn all_correspondents(
+        paperless: &Paperless,
+    ) -> Result<Vec<(correspondent::Id, correspondent::Correspondent)>, Error> {
+        paperless
+            .correspondents(Default::default())?
+            .map(|c| c.map(|c| (c.id, c)))
+            .collect()
+    }
+
+    /// Forget every entity, they are loaded again on next use
+    pub(crate) fn invalidate_all(&self) {
+        self.correspondents.clear();
+        self.custom_fields.clear();
+        self.document_types.clear();
+        self.storage_paths.clear();
+        self.tags.clear();
+    }
+
+    /// Remember the title of a document, to find its previous titles if it is renamed
+    pub(crate) fn observe_title(&self, id: document::Id, title: &str) {
+        let mut titles = self.titles.lock().unwrap_or_else(|e| e.into_inner());
+        let titles = titles.entry(id).or_default();
+        if titles.last().map(String::as_str) != Some(title) {
+            titles.push(title.to_string());
+        }
+    }
+
+    /// Titles of a document seen by this client, except the current one
+    pub(crate) fn previous_titles(&self, id: document::Id) -> Vec<String> {
+        let titles = self.titles.lock().unwrap_or_else(|e| e.into_inner());
+        match titles.get(&id).map(Vec::as_slice) {
+            Some([previous @ .., _current]) => previous.to_vec(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Remember the version of the server, as sent in the headers of its responses
+    pub(crate) fn observe_version(&self, version: &str) {
+        let mut current = self
+            .server_version
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if current.as_deref() != Some(version) {
+            *current = Some(version.to_string());
+        }
+    }
+
+    /// Get a custom field, the cache is dropped when the version of the server changes as the
+    /// custom fields may have been migrated
+    pub(crate) fn custom_field(
+        &self,
+        paperless: &Paperless,
+        id: custom_field::Id,
+    ) -> Result<custom_field::CustomField, Error> {
+        let current = self
+            .server_version
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        if let Some(current) = current {
+            let mut loaded = self
+                .custom_fields_version
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            if loaded.as_ref().is_some_and(|loaded| *loaded != current) {
+                self.custom_fields.clear();
+            }
+            *loaded = Some(current);
+        }
+        self.custom_fields.get(
+            id,
+            || Self::all_custom_fields(paperless),
+            |id| paperless.custom_field(id),
+        )
+    }
+
+    pub(crate) fn all_custom_fields(
+        paperless: &Paperless,
+    ) -> Result<Vec<(custom_field::Id, custom_field::CustomField)>, Error> {
+        paperless
+            .custom_fields()?
+            .map(|f| f.map(|f| (f.id, f)))
+            .collect()
+    }
+
+    pub(crate) fn document_type(
+        &self,
+        paperless: &Paperless,
+        id: document_type::Id,
+    ) -> Result<document_type::DocumentType, Error> {
+        self.document_types.get(
+            id,
+            || Self::all_document_types(paperless),
+            |id| paperless.document_type(id),
+        )
+    }
+
+    pub(crate) fn cached_document_types(
+        &self,
+        paperless: &Paperless,
+    ) -> Result<Vec<document_type::DocumentType>, Error> {
+        self.document_types
+            .all(|| Self::all_document_types(paperless))
+    }
+
+    fn all_document_types(
+        paperless: &Paperless,
+    ) -> Result<Vec<(document_type::Id, document_type::DocumentType)>, Error> {
+        paperless
+            .document_types(Default::default())?
+            .map(|t| t.map(|t| (t.id, t)))
+            .collect()
+    }
+
+    pub(crate) fn storage_path(
+        &self,
+        paperless: &Paperless,
+        id: storage_path::Id,
+    ) -> Result<storage_path::StoragePath, Error> {
+        self.storage_paths.get(
+            id,
+            || Self::all_storage_paths(paperless),
+            |id| paperless.storage_path(id),
+        )
+    }
+
+    pub(crate) fn cached_storage_paths(
+        &self,
+        paperless: &Paperless,
+    ) -> Result<Vec<storage_path::StoragePath>, Error> {
+        self.storage_paths
+            .all(|| Self::all_storage_paths(paperless))
+    }
+
+    fn all_storage_paths(
+        paperless: &Paperless,
+    ) -> Result<Vec<(storage_path::Id, storage_path::StoragePath)>, Error> {
+        paperless
+            .storage_paths(Default::default())?
+            .map(|p| p.map(|p| (p.id, p)))
+            .collect()
+    }
+
+    pub(crate) fn tag(&self, paperless: &Paperless, id: tag::Id) -> Result<tag::Tag, Error> {
+        self.tags
+            .get(id, || Self::all_tags(paperless), |id| paperless.tag(id))
+    }
+
+    pub(crate) fn cached_tags(&self, paperless: &Paperless) -> Result<Vec<tag::Tag>, Error> {
+        self.tags.all(|| Self::all_tags(paperless))
+    }
+
+    fn all_tags(paperless: &Paperless) -> Result<Vec<(tag::Id, tag::Tag)>, Error> {
+        paperless
+            .tags(Default::default())?
+            .map(|t| t.map(|t| (t.id, t)))
+            .collect()
+    }
+}
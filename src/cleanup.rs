@@ -0,0 +1,174 @@
+//! # Cleanup
+//!
+//! Long-lived archives accumulate correspondents, document types, storage paths and tags which
+//! are not used by any document anymore. [`prune_unused`] finds and deletes them.
+
+use crate::rollback::{Rollback, Step};
+use crate::{correspondent, document, document_type, storage_path, tag, Error, Paperless};
+
+/// Kind of objects to prune
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Correspondents,
+    DocumentTypes,
+    StoragePaths,
+    Tags,
+}
+
+/// An object without documents
+#[derive(Debug, Clone)]
+pub enum Unused {
+    Correspondent(correspondent::Correspondent),
+    DocumentType(document_type::DocumentType),
+    StoragePath(storage_path::StoragePath),
+    Tag(tag::Tag),
+}
+
+impl Unused {
+    pub fn name(&self) -> &str {
+        match self {
+            Unused::Correspondent(c) => &c.name,
+            Unused::DocumentType(t) => &t.name,
+            Unused::StoragePath(p) => &p.name,
+            Unused::Tag(t) => &t.name,
+        }
+    }
+}
+
+/// Result of [`prune_unused`]
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    /// Objects deleted from the server
+    pub removed: Vec<Unused>,
+    /// Objects without documents which were not deleted, because of a dry run, because the
+    /// deletion was not confirmed, or because a document was assigned to them in the meantime
+    pub kept: Vec<Unused>,
+}
+
+/// Delete the objects which are not used by any document
+///
+/// Candidates are found with their `document_count`, then the documents using each of them are
+/// counted again just before deleting it, so that an object which was assigned in the meantime
+/// is left untouched. Inbox tags are never pruned, as an empty inbox is expected.
+///
+/// The counts of the server only include the documents the user is allowed to see, so an object
+/// used by the private documents of someone else would look unused. Only superusers can prune,
+/// otherwise this fails with [`Error::Forbidden`] before looking for candidates.
+///
+/// If a deletion fails, the error is [`Error::Interrupted`] with the objects already deleted.
+///
+/// # Arguments
+///
+/// * `paperless` - Client used to query the server
+/// * `entities` - Kinds of objects to prune
+/// * `dry_run` - Only report the unused objects, without deleting them
+/// * `confirm` - Called before deleting each object, which is kept if it returns `false`
+pub fn prune_unused(
+    paperless: &Paperless,
+    entities: &[Kind],
+    dry_run: bool,
+    mut confirm: impl FnMut(&Unused) -> bool,
+) -> Result<Report, Error> {
+    if !paperless.ui_settings()?.user.is_superuser {
+        return Err(Error::Forbidden);
+    }
+
+    let mut candidates = Vec::new();
+    for kind in entities {
+        candidates.extend(unused(paperless, *kind)?);
+    }
+
+    let mut report = Report::default();
+    if dry_run {
+        report.kept = candidates;
+        return Ok(report);
+    }
+
+    let mut rollback = Rollback::default();
+    for candidate in candidates {
+        if !confirm(&candidate) {
+            report.kept.push(candidate);
+            continue;
+        }
+        match remove(paperless, &candidate) {
+            Ok(true) => {
+                rollback.record(step(&candidate));
+                report.removed.push(candidate);
+            }
+            Ok(false) => report.kept.push(candidate),
+            Err(e) => return Err(e.interrupted(rollback)),
+        }
+    }
+    Ok(report)
+}
+
+/// Objects of a kind which have no documents according to the server
+fn unused(paperless: &Paperless, kind: Kind) -> Result<Vec<Unused>, Error> {
+    match kind {
+        Kind::Correspondents => paperless
+            .correspondents(Default::default())?
+            .filter(|c| c.as_ref().map_or(true, |c| c.document_count == 0))
+            .map(|c| c.map(Unused::Correspondent))
+            .collect(),
+        Kind::DocumentTypes => paperless
+            .document_types(Default::default())?
+            .filter(|t| t.as_ref().map_or(true, |t| t.document_count == 0))
+            .map(|t| t.map(Unused::DocumentType))
+            .collect(),
+        Kind::StoragePaths => paperless
+            .storage_paths(Default::default())?
+            .filter(|p| p.as_ref().map_or(true, |p| p.document_count == 0))
+            .map(|p| p.map(Unused::StoragePath))
+            .collect(),
+        Kind::Tags => paperless
+            .tags(Default::default())?
+            .filter(|t| {
+                t.as_ref()
+                    .map_or(true, |t| t.document_count == 0 && !t.is_inbox_tag)
+            })
+            .map(|t| t.map(Unused::Tag))
+            .collect(),
+    }
+}
+
+/// Delete an object if it still has no documents, and return whether it was deleted
+fn remove(paperless: &Paperless, unused: &Unused) -> Result<bool, Error> {
+    let filter = match unused {
+        Unused::Correspondent(c) => document::Filter {
+            correspondent_id: Some(c.id),
+            ..Default::default()
+        },
+        Unused::DocumentType(t) => document::Filter {
+            document_type_id: Some(t.id),
+            ..Default::default()
+        },
+        Unused::StoragePath(p) => document::Filter {
+            storage_path_id: Some(p.id),
+            ..Default::default()
+        },
+        Unused::Tag(t) => document::Filter {
+            tag_id: Some(t.id),
+            ..Default::default()
+        },
+    };
+    if paperless.document_count(filter)? > 0 {
+        return Ok(false);
+    }
+
+    match unused {
+        Unused::Correspondent(c) => paperless.delete_correspondent(c.id)?,
+        Unused::DocumentType(t) => paperless.delete_document_type(t.id)?,
+        Unused::StoragePath(p) => paperless.delete_storage_path(p.id)?,
+        Unused::Tag(t) => paperless.delete_tag(t.id)?,
+    }
+    Ok(true)
+}
+
+fn step(unused: &Unused) -> Step {
+    match unused.clone() {
+        Unused::Correspondent(c) => Step::CorrespondentDeleted(c),
+        Unused::DocumentType(t) => Step::DocumentTypeDeleted(t),
+        Unused::StoragePath(p) => Step::StoragePathDeleted(p),
+        Unused::Tag(t) => Step::TagDeleted(t),
+    }
+}
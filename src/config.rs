@@ -0,0 +1,130 @@
+//! # Configuration
+//!
+//! Configuration of the application stored in the database, which overrides the environment of
+//! the server. Fields set to `None` use the value from the environment. Only administrators can
+//! read and modify it.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Deserialize, Copy, Clone, Serialize, PartialEq, Eq, Hash)]
+pub struct Id(u64);
+
+impl From<u64> for Id {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+impl From<Id> for u64 {
+    fn from(value: Id) -> Self {
+        value.0
+    }
+}
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Format of the archived version of documents
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, PartialEq, Eq)]
+pub enum OutputType {
+    #[serde(rename = "pdf")]
+    Pdf,
+    #[serde(rename = "pdfa")]
+    PdfA,
+    #[serde(rename = "pdfa-1")]
+    PdfA1,
+    #[serde(rename = "pdfa-2")]
+    PdfA2,
+    #[serde(rename = "pdfa-3")]
+    PdfA3,
+}
+
+/// How the OCR handles documents which already contain text
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    /// Only run the OCR on pages without text
+    Skip,
+    /// Like `Skip`, but archived versions are only created for documents without text
+    SkipNoarchive,
+    /// Replace the text of the pages, only for text made by an OCR
+    Redo,
+    /// Rasterize every page and run the OCR on them
+    Force,
+}
+
+/// When the archived version of a document is not created
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipArchiveFile {
+    Never,
+    /// Only for documents which already contain text
+    WithText,
+    Always,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Config {
+    pub id: Id,
+    /// Additional arguments of OCRmyPDF, as a json object
+    pub user_args: Option<serde_json::Value>,
+    pub output_type: Option<OutputType>,
+    /// Number of pages on which the OCR is run, from the start of the document
+    pub pages: Option<u32>,
+    /// Languages of the OCR, like `eng+fra`
+    pub language: Option<String>,
+    pub mode: Option<Mode>,
+    pub skip_archive_file: Option<SkipArchiveFile>,
+    /// Resolution used for images without one
+    pub image_dpi: Option<u32>,
+    /// Cleaning done by unpaper, like `clean` or `clean-final`
+    pub unpaper_clean: Option<String>,
+    pub deskew: Option<bool>,
+    pub rotate_pages: Option<bool>,
+    pub rotate_pages_threshold: Option<f64>,
+    pub max_image_pixels: Option<f64>,
+    /// Color conversion of Ghostscript, like `RGB` or `LeaveColorUnchanged`
+    pub color_conversion_strategy: Option<String>,
+    #[serde(default)]
+    pub app_title: Option<String>,
+    /// Url of the logo, relative to the server
+    #[serde(default)]
+    pub app_logo: Option<String>,
+}
+
+/// Modification of the configuration, only the fields set to `Some` are modified
+///
+/// Set a field to `Some(None)` to use the value from the environment again.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct Update {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_args: Option<Option<serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_type: Option<Option<OutputType>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pages: Option<Option<u32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<Option<Mode>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_archive_file: Option<Option<SkipArchiveFile>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_dpi: Option<Option<u32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unpaper_clean: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deskew: Option<Option<bool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rotate_pages: Option<Option<bool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rotate_pages_threshold: Option<Option<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_image_pixels: Option<Option<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color_conversion_strategy: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_title: Option<Option<String>>,
+}
@@ -2,10 +2,12 @@
 //!
 //! Correspondent is the main entity related to the document. It can be your bank, a friend, a school, ...
 
+use crate::date::{self, DateTime};
+use crate::{matching, permissions, query, user};
 use reqwest::Url;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Copy, Clone)]
+#[derive(Debug, Deserialize, Copy, Clone, Serialize, PartialEq, Eq, Hash)]
 pub struct Id(u64);
 
 impl From<u64> for Id {
@@ -24,19 +26,66 @@ impl ToString for Id {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct Correspondent {
     pub id: Id,
     pub name: String,
     pub slug: String,
     #[serde(rename = "match")]
     pub match_: String,
-    pub matching_algorithm: u64,
+    pub matching_algorithm: matching::Algorithm,
     pub is_insensitive: bool,
+    /// Not sent by the server when the correspondent has just been created
+    #[serde(default)]
     pub document_count: u64,
-    pub last_correspondence: String,
+    #[serde(default, with = "date::datetime::option")]
+    pub last_correspondence: Option<DateTime>,
+    /// Owner of the object, `None` if everyone can see it
+    #[serde(default)]
+    pub owner: Option<user::Id>,
+    /// Only sent when the client asks for the full permissions
+    #[serde(default)]
+    pub permissions: Option<permissions::Permissions>,
+    /// Whether the current user can modify the object, not sent by old versions of paperless
+    #[serde(default, alias = "userCanChange")]
+    pub user_can_change: Option<bool>,
 }
-#[derive(Debug, Default)]
+
+/// A new correspondent
+#[derive(Debug, Clone, Serialize)]
+pub struct Create {
+    pub name: String,
+    #[serde(rename = "match")]
+    pub match_: String,
+    pub matching_algorithm: matching::Algorithm,
+    pub is_insensitive: bool,
+}
+
+impl Create {
+    /// A correspondent automatically assigned by the server, like when created from the interface
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            match_: String::new(),
+            matching_algorithm: matching::Algorithm::Auto,
+            is_insensitive: true,
+        }
+    }
+}
+
+/// Modification of a correspondent, only the fields set to `Some` are modified
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct Update {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(rename = "match", skip_serializing_if = "Option::is_none")]
+    pub match_: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matching_algorithm: Option<matching::Algorithm>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_insensitive: Option<bool>,
+}
+#[derive(Debug, Default, Clone)]
 pub struct Filter {
     name_starts_with: Option<String>,
     name_ends_with: Option<String>,
@@ -45,14 +94,20 @@ pub struct Filter {
 }
 
 impl Filter {
-    pub fn insert_query(self, url: &mut Url) {
-        url.query_pairs_mut()
-            .append_pair(
-                "name__istartswith",
-                &self.name_starts_with.unwrap_or_default(),
-            )
-            .append_pair("name__iendswith", &self.name_ends_with.unwrap_or_default())
-            .append_pair("name__icontains", &self.name_contains.unwrap_or_default())
-            .append_pair("name__iexact", &self.name_is.unwrap_or_default());
+    /// Merge two filters into a new one, every field set in `other` takes precedence over `self`
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            name_starts_with: other.name_starts_with.or(self.name_starts_with),
+            name_ends_with: other.name_ends_with.or(self.name_ends_with),
+            name_contains: other.name_contains.or(self.name_contains),
+            name_is: other.name_is.or(self.name_is),
+        }
+    }
+
+    pub fn insert_query(&self, url: &mut Url) {
+        query::append_some(url, "name__istartswith", self.name_starts_with.as_deref());
+        query::append_some(url, "name__iendswith", self.name_ends_with.as_deref());
+        query::append_some(url, "name__icontains", self.name_contains.as_deref());
+        query::append_some(url, "name__iexact", self.name_is.as_deref());
     }
 }
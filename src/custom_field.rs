@@ -0,0 +1,321 @@
+//! # Custom field
+//!
+//! Custom fields are additional typed fields which can be attached to documents. They are only
+//! supported by recent versions of paperless-ngx.
+
+use crate::date::{self, Date};
+use crate::{document, Error};
+use serde::{de, Deserialize, Serialize, Serializer};
+use std::fmt;
+
+#[derive(Debug, Deserialize, Copy, Clone, Serialize, PartialEq, Eq, Hash)]
+pub struct Id(u64);
+
+impl From<u64> for Id {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+impl From<Id> for u64 {
+    fn from(value: Id) -> Self {
+        value.0
+    }
+}
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Type of the values of a custom field
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DataType {
+    String,
+    Url,
+    Date,
+    Boolean,
+    Integer,
+    Float,
+    Monetary,
+    #[serde(alias = "documentLink", alias = "document_link")]
+    DocumentLink,
+    Select,
+    /// Type added by a newer version of paperless
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct CustomField {
+    pub id: Id,
+    pub name: String,
+    #[serde(alias = "dataType")]
+    pub data_type: DataType,
+}
+
+/// A new custom field
+#[derive(Debug, Clone, Serialize)]
+pub struct Create {
+    pub name: String,
+    pub data_type: DataType,
+}
+
+impl Create {
+    pub fn new(name: &str, data_type: DataType) -> Self {
+        Self {
+            name: name.to_string(),
+            data_type,
+        }
+    }
+}
+
+/// Value of a custom field on a document
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Instance {
+    pub field: Id,
+    /// Raw value, its type depends on the `data_type` of the field, see [`Instance::value`]
+    pub value: serde_json::Value,
+}
+
+impl Instance {
+    /// Value to set on a document, `None` to leave the field empty
+    ///
+    /// Fails with [`Error::InvalidArgument`] if the value is a float or an amount which is not
+    /// finite, as json can't represent it.
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - The custom field
+    /// * `value` - Its value
+    pub fn new(field: Id, value: Option<Value>) -> Result<Self, Error> {
+        let number = match &value {
+            Some(Value::Float(number)) | Some(Value::Monetary(Monetary { amount: number, .. })) => {
+                Some(*number)
+            }
+            _ => None,
+        };
+        if let Some(number) = number.filter(|n| !n.is_finite()) {
+            return Err(Error::InvalidArgument(format!(
+                "the value {number} of the custom field {field} is not finite"
+            )));
+        }
+        Ok(Self {
+            field,
+            value: serde_json::to_value(value)?,
+        })
+    }
+
+    /// Typed value of the field, `None` if the field has no value
+    ///
+    /// # Arguments
+    ///
+    /// * `data_type` - Type of the field, from the matching [`CustomField`]
+    pub fn value(&self, data_type: DataType) -> Result<Option<Value>, Error> {
+        Value::from_json(data_type, self.value.clone())
+    }
+}
+
+/// An amount of money, sent by the server as `EUR12.50`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Monetary {
+    /// ISO 4217 code, not set by old versions of paperless
+    pub currency: Option<String>,
+    pub amount: f64,
+}
+
+impl std::str::FromStr for Monetary {
+    type Err = std::num::ParseFloatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let split = s
+            .find(|c: char| c.is_ascii_digit() || c == '-' || c == '.')
+            .unwrap_or(s.len());
+        let (currency, amount) = s.split_at(split);
+        Ok(Self {
+            currency: (!currency.is_empty()).then(|| currency.to_string()),
+            amount: amount.parse()?,
+        })
+    }
+}
+
+impl std::fmt::Display for Monetary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{:.2}",
+            self.currency.as_deref().unwrap_or_default(),
+            self.amount
+        )
+    }
+}
+
+/// Option of a select field, an index in the list of options on old versions of paperless and
+/// an id on newer ones
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum SelectOption {
+    Index(u64),
+    Id(String),
+}
+
+/// Typed value of a custom field
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Url(String),
+    Date(Date),
+    Boolean(bool),
+    Integer(i64),
+    Float(f64),
+    Monetary(Monetary),
+    DocumentLink(Vec<document::Id>),
+    Select(SelectOption),
+    /// Value of a type unknown to this library
+    Unknown(serde_json::Value),
+}
+
+#[derive(Deserialize)]
+struct DateRepr(#[serde(with = "date::date")] Date);
+
+impl Value {
+    /// Convert a raw value sent by the server, `None` if the field has no value
+    ///
+    /// # Arguments
+    ///
+    /// * `data_type` - Type of the field
+    /// * `value` - Raw value
+    pub fn from_json(data_type: DataType, value: serde_json::Value) -> Result<Option<Self>, Error> {
+        if value.is_null() {
+            return Ok(None);
+        }
+        let value = match data_type {
+            DataType::String => Self::String(serde_json::from_value(value)?),
+            DataType::Url => Self::Url(serde_json::from_value(value)?),
+            DataType::Date => Self::Date(serde_json::from_value::<DateRepr>(value)?.0),
+            DataType::Boolean => Self::Boolean(serde_json::from_value(value)?),
+            DataType::Integer => Self::Integer(serde_json::from_value(value)?),
+            DataType::Float => Self::Float(serde_json::from_value(value)?),
+            DataType::Monetary => match value {
+                // Old versions of paperless send a bare number
+                serde_json::Value::Number(amount) => Self::Monetary(Monetary {
+                    currency: None,
+                    amount: amount.as_f64().unwrap_or_default(),
+                }),
+                value => {
+                    let raw: String = serde_json::from_value(value)?;
+                    Self::Monetary(raw.parse().map_err(|e| {
+                        <serde_json::Error as de::Error>::custom(format!(
+                            "invalid monetary value {raw:?}: {e}"
+                        ))
+                    })?)
+                }
+            },
+            DataType::DocumentLink => Self::DocumentLink(serde_json::from_value(value)?),
+            DataType::Select => Self::Select(serde_json::from_value(value)?),
+            DataType::Unknown => Self::Unknown(value),
+        };
+        Ok(Some(value))
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::String(value) | Self::Url(value) => serializer.serialize_str(value),
+            Self::Date(value) => serializer.serialize_str(&date::format_date(value)),
+            Self::Boolean(value) => serializer.serialize_bool(*value),
+            Self::Integer(value) => serializer.serialize_i64(*value),
+            Self::Float(value) => serializer.serialize_f64(*value),
+            Self::Monetary(value) => serializer.collect_str(value),
+            Self::DocumentLink(value) => value.serialize(serializer),
+            Self::Select(value) => value.serialize(serializer),
+            Self::Unknown(value) => value.serialize(serializer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn monetary(currency: Option<&str>, amount: f64) -> Monetary {
+        Monetary {
+            currency: currency.map(|c| c.to_string()),
+            amount,
+        }
+    }
+
+    #[test]
+    fn parse_monetary() {
+        assert_eq!("EUR12.50".parse(), Ok(monetary(Some("EUR"), 12.5)));
+        assert_eq!("12.50".parse(), Ok(monetary(None, 12.5)));
+        assert_eq!("USD-3.20".parse(), Ok(monetary(Some("USD"), -3.2)));
+        assert!("EUR".parse::<Monetary>().is_err());
+    }
+
+    #[test]
+    fn monetary_from_json() {
+        let value = |json| Value::from_json(DataType::Monetary, json).unwrap();
+        assert_eq!(
+            value(json!("EUR12.50")),
+            Some(Value::Monetary(monetary(Some("EUR"), 12.5)))
+        );
+        // Sent by old versions of paperless
+        assert_eq!(
+            value(json!(12.5)),
+            Some(Value::Monetary(monetary(None, 12.5)))
+        );
+        assert_eq!(value(json!(null)), None);
+        assert!(Value::from_json(DataType::Monetary, json!("twelve")).is_err());
+    }
+
+    #[test]
+    fn serialize_round_trip() {
+        let values = [
+            (
+                DataType::Monetary,
+                Value::Monetary(monetary(Some("EUR"), -12.5)),
+            ),
+            (DataType::Monetary, Value::Monetary(monetary(None, 3.0))),
+            (DataType::Float, Value::Float(0.25)),
+            (DataType::Integer, Value::Integer(-4)),
+            (
+                DataType::DocumentLink,
+                Value::DocumentLink(vec![1.into(), 2.into()]),
+            ),
+            (
+                DataType::Select,
+                Value::Select(SelectOption::Id("a1b2".to_string())),
+            ),
+        ];
+        for (data_type, value) in values {
+            let instance = Instance::new(1.into(), Some(value.clone())).unwrap();
+            assert_eq!(instance.value(data_type).unwrap(), Some(value));
+        }
+        assert_eq!(
+            Instance::new(
+                1.into(),
+                Some(Value::Monetary(monetary(Some("EUR"), -12.5)))
+            )
+            .unwrap()
+            .value,
+            json!("EUR-12.50")
+        );
+    }
+
+    #[test]
+    fn non_finite_values_are_rejected() {
+        for value in [
+            Value::Float(f64::NAN),
+            Value::Float(f64::INFINITY),
+            Value::Monetary(monetary(Some("EUR"), f64::NEG_INFINITY)),
+        ] {
+            assert!(matches!(
+                Instance::new(1.into(), Some(value)),
+                Err(Error::InvalidArgument(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! # Dashboard
+//!
+//! Everything needed to render the home dashboard of paperless, gathered at once with
+//! [`Paperless::dashboard_summary`](crate::Paperless::dashboard_summary).
+
+use crate::{saved_view, statistics, task};
+
+/// A saved view shown on the dashboard, with the number of documents it contains
+#[derive(Debug)]
+pub struct View {
+    pub view: saved_view::SaveView,
+    pub document_count: u64,
+    /// Rules of the view ignored when counting its documents, the count is too high if there are
+    /// some, see [`saved_view::FilterRule::unsupported`]
+    pub unsupported_rules: Vec<saved_view::FilterRule>,
+}
+
+#[derive(Debug)]
+pub struct Summary {
+    pub statistics: statistics::Statistics,
+    /// Number of documents in the inbox
+    pub inbox_count: u64,
+    /// Saved views with `show_on_dashboard` set
+    pub views: Vec<View>,
+    /// Failed tasks which have not been acknowledged yet, most recent first
+    pub failed_tasks: Vec<task::Task>,
+}
@@ -0,0 +1,143 @@
+//! # Date
+//!
+//! Dates used in models and filters. They are [`chrono`](https://docs.rs/chrono) types by default,
+//! and [`time`](https://docs.rs/time) types when the `time` feature is enabled instead of the
+//! default `chrono` one.
+//!
+//! Exactly one of the two features must be enabled: if both were allowed, enabling `time` in one
+//! crate of the dependency tree would change the types seen by the others.
+
+#[cfg(not(any(feature = "chrono", feature = "time")))]
+compile_error!("one of the `chrono` or `time` features must be enabled");
+
+#[cfg(all(feature = "chrono", feature = "time"))]
+compile_error!(
+    "the `chrono` and `time` features can't be enabled together, use `default-features = false` \
+     to enable `time`"
+);
+
+#[cfg(not(feature = "time"))]
+mod backend {
+    /// A date with a time, in UTC
+    pub type DateTime = chrono::DateTime<chrono::Utc>;
+    /// A date without time
+    pub type Date = chrono::NaiveDate;
+
+    pub(crate) fn format_datetime(datetime: &DateTime) -> String {
+        datetime.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+    }
+
+    pub(crate) fn format_date(date: &Date) -> String {
+        date.format("%Y-%m-%d").to_string()
+    }
+
+    pub(crate) fn previous_day(date: &Date) -> Option<Date> {
+        date.pred_opt()
+    }
+
+    pub(crate) fn next_day(date: &Date) -> Option<Date> {
+        date.succ_opt()
+    }
+
+    pub(crate) fn parse_date(value: &str) -> Option<Date> {
+        chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+    }
+
+    pub(crate) fn parse_datetime(value: &str) -> Option<DateTime> {
+        chrono::DateTime::parse_from_rfc3339(value)
+            .ok()
+            .map(|d| d.naive_utc().and_utc())
+    }
+
+    pub(crate) mod datetime {
+        use super::DateTime;
+        use serde::{Deserialize, Deserializer};
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<DateTime, D::Error> {
+            DateTime::deserialize(deserializer)
+        }
+
+        pub mod option {
+            use super::DateTime;
+            use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+            pub fn serialize<S: Serializer>(
+                value: &Option<DateTime>,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                value.serialize(serializer)
+            }
+
+            pub fn deserialize<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<Option<DateTime>, D::Error> {
+                Option::<DateTime>::deserialize(deserializer)
+            }
+        }
+    }
+
+    pub(crate) mod date {
+        use super::Date;
+        use serde::{Deserialize, Deserializer};
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Date, D::Error> {
+            Date::deserialize(deserializer)
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod backend {
+    use time::format_description::well_known::Rfc3339;
+    use time::macros::format_description;
+
+    /// A date with a time, in UTC
+    pub type DateTime = time::OffsetDateTime;
+    /// A date without time
+    pub type Date = time::Date;
+
+    pub(crate) fn format_datetime(datetime: &DateTime) -> String {
+        // Only fails for years which can't be represented with 4 digits
+        datetime
+            .to_offset(time::UtcOffset::UTC)
+            .format(format_description!(
+                "[year]-[month]-[day]T[hour]:[minute]:[second]Z"
+            ))
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn format_date(date: &Date) -> String {
+        date.format(format_description!("[year]-[month]-[day]"))
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn previous_day(date: &Date) -> Option<Date> {
+        date.previous_day()
+    }
+
+    pub(crate) fn next_day(date: &Date) -> Option<Date> {
+        date.next_day()
+    }
+
+    pub(crate) fn parse_date(value: &str) -> Option<Date> {
+        Date::parse(value, format_description!("[year]-[month]-[day]")).ok()
+    }
+
+    pub(crate) fn parse_datetime(value: &str) -> Option<DateTime> {
+        DateTime::parse(value, &Rfc3339)
+            .ok()
+            .map(|d| d.to_offset(time::UtcOffset::UTC))
+    }
+
+    pub(crate) use time::serde::rfc3339 as datetime;
+
+    time::serde::format_description!(pub(crate) date, Date, "[year]-[month]-[day]");
+}
+
+pub(crate) use backend::{
+    date, datetime, format_date, format_datetime, next_day, parse_date, parse_datetime,
+    previous_day,
+};
+pub use backend::{Date, DateTime};
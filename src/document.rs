@@ -2,12 +2,20 @@
 //!
 //! A document is stored on the server. There are a lot of way to filter documents
 
-use crate::{asn, correspondent, document_type, saved_view, storage_path, tag};
-use chrono::{DateTime, NaiveDate, Utc};
+use crate::date::{self, Date, DateTime};
+use crate::query;
+use crate::{
+    asn, correspondent, custom_field, document_type, note, permissions, saved_view, storage_path,
+    tag, user, Error,
+};
+use reqwest::blocking::multipart::{Form, Part};
+use reqwest::header::{HeaderMap, CONTENT_DISPOSITION, CONTENT_TYPE};
 use reqwest::Url;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
 
-#[derive(Debug, Deserialize, Copy, Clone)]
+#[derive(Debug, Deserialize, Copy, Clone, Serialize, PartialEq, Eq, Hash)]
 pub struct Id(u64);
 
 impl From<u64> for Id {
@@ -30,24 +38,543 @@ impl ToString for Id {
 pub struct Document {
     pub id: Id,
     pub correspondent: Option<correspondent::Id>,
-    pub document_type: Option<correspondent::Id>,
+    pub document_type: Option<document_type::Id>,
     pub storage_path: Option<storage_path::Id>,
     pub title: String,
     pub content: String,
     pub tags: Vec<tag::Id>,
-    pub created: DateTime<Utc>,
-    pub created_date: NaiveDate,
-    pub modified: DateTime<Utc>,
-    pub added: DateTime<Utc>,
+    #[serde(with = "date::datetime")]
+    pub created: DateTime,
+    #[serde(with = "date::date")]
+    pub created_date: Date,
+    #[serde(with = "date::datetime")]
+    pub modified: DateTime,
+    #[serde(with = "date::datetime")]
+    pub added: DateTime,
     pub archive_serial_number: Option<asn::ASN>,
     pub original_file_name: Option<String>,
     pub archived_file_name: Option<String>,
+    /// How the file is stored on the server, only sent by some versions of paperless
+    #[serde(default)]
+    pub storage_type: Option<StorageType>,
+    /// Notes written on the document, only sent by servers with notes enabled
+    #[serde(default)]
+    pub notes: Vec<note::Note>,
+    /// Values of the custom fields, only sent by servers supporting custom fields
+    #[serde(default)]
+    pub custom_fields: Vec<custom_field::Instance>,
+    /// Owner of the object, `None` if everyone can see it
+    #[serde(default)]
+    pub owner: Option<user::Id>,
+    /// Only sent when the client asks for the full permissions
+    #[serde(default)]
+    pub permissions: Option<permissions::Permissions>,
+    /// Whether the current user can modify the object, not sent by old versions of paperless
+    #[serde(default, alias = "userCanChange")]
+    pub user_can_change: Option<bool>,
+}
+
+impl Document {
+    /// Whether the file is encrypted on the server
+    ///
+    /// Encrypted files are decrypted by the server on download, which is slower and makes some
+    /// operations (like reading only a part of the file) impossible.
+    pub fn is_encrypted(&self) -> bool {
+        self.storage_type == Some(StorageType::Gpg)
+    }
+
+    /// Text of each page of the document
+    ///
+    /// The server doesn't send the text page by page, but the OCR and the text extraction
+    /// separate pages with a form feed. If the content has no form feed, it is returned as a
+    /// single page.
+    pub fn pages(&self) -> Vec<&str> {
+        self.content
+            .trim_end_matches('\u{c}')
+            .split('\u{c}')
+            .collect()
+    }
+}
+
+/// Version of the file of a document
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileVersion {
+    /// The file as uploaded
+    Original,
+    /// The archived PDF created by the server, or the original if there is none
+    #[default]
+    Archive,
+}
+
+impl FileVersion {
+    pub(crate) fn insert_query(&self, url: &mut Url) {
+        if *self == FileVersion::Original {
+            url.query_pairs_mut().append_pair("original", "true");
+        }
+    }
+}
+
+/// A file sent by the server, with its type
+#[derive(Debug, Clone)]
+pub struct File {
+    /// Mime type of the file, like `image/webp`, if sent by the server
+    pub content_type: Option<String>,
+    /// Name of the file, if sent by the server
+    pub file_name: Option<String>,
+    pub content: Vec<u8>,
+}
+
+impl File {
+    pub(crate) fn from_headers(headers: &HeaderMap, content: Vec<u8>) -> Self {
+        let header = |name| headers.get(name).and_then(|v| v.to_str().ok());
+        Self {
+            content_type: header(CONTENT_TYPE).map(|v| v.to_string()),
+            file_name: header(CONTENT_DISPOSITION).and_then(disposition_file_name),
+            content,
+        }
+    }
+
+    /// Extension to use when writing the file, without the leading dot
+    ///
+    /// See [`guess_extension`].
+    pub fn extension(&self) -> Option<String> {
+        guess_extension(
+            self.content_type.as_deref(),
+            self.file_name.as_deref(),
+            &self.content,
+        )
+    }
+}
+
+/// File of a document streamed from the server, see [`Paperless::document_download_stream`]
+///
+/// [`Paperless::document_download_stream`]: crate::Paperless::document_download_stream
+#[derive(Debug)]
+pub struct Download(pub(crate) reqwest::blocking::Response);
+
+impl std::io::Read for Download {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+/// Find the extension of a file, without the leading dot
+///
+/// The extension of the file name is used first, then the mime type, then the first bytes of the
+/// content for the formats paperless usually stores.
+///
+/// # Arguments
+///
+/// * `content_type` - The `Content-Type` header of the response
+/// * `file_name` - The name of the file, like in the `Content-Disposition` header
+/// * `content` - The beginning of the content, a few bytes are enough
+pub fn guess_extension(
+    content_type: Option<&str>,
+    file_name: Option<&str>,
+    content: &[u8],
+) -> Option<String> {
+    if let Some(extension) = file_name
+        .and_then(|f| Path::new(f).extension())
+        .and_then(|e| e.to_str())
+    {
+        return Some(extension.to_lowercase());
+    }
+
+    let mime = content_type
+        .and_then(|c| c.split(';').next())
+        .map(|c| c.trim().to_lowercase());
+    let from_mime = match mime.as_deref() {
+        Some("application/pdf") => Some("pdf"),
+        Some("image/png") => Some("png"),
+        Some("image/jpeg") => Some("jpg"),
+        Some("image/webp") => Some("webp"),
+        Some("image/tiff") => Some("tiff"),
+        Some("image/gif") => Some("gif"),
+        Some("text/plain") => Some("txt"),
+        Some("text/csv") => Some("csv"),
+        Some("text/html") => Some("html"),
+        Some("message/rfc822") => Some("eml"),
+        Some("application/zip") => Some("zip"),
+        Some("application/msword") => Some("doc"),
+        Some("application/vnd.openxmlformats-officedocument.wordprocessingml.document") => {
+            Some("docx")
+        }
+        Some("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet") => Some("xlsx"),
+        Some("application/vnd.oasis.opendocument.text") => Some("odt"),
+        _ => None,
+    };
+    if let Some(extension) = from_mime {
+        return Some(extension.to_string());
+    }
+
+    let from_magic = match content {
+        [b'%', b'P', b'D', b'F', ..] => Some("pdf"),
+        [0x89, b'P', b'N', b'G', ..] => Some("png"),
+        [0xFF, 0xD8, 0xFF, ..] => Some("jpg"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("webp"),
+        [b'I', b'I', 0x2A, 0x00, ..] | [b'M', b'M', 0x00, 0x2A, ..] => Some("tiff"),
+        [b'G', b'I', b'F', b'8', ..] => Some("gif"),
+        [b'P', b'K', 0x03, 0x04, ..] => Some("zip"),
+        _ => None,
+    };
+    from_magic.map(|e| e.to_string())
+}
+
+/// Name of the file in a `Content-Disposition` header
+fn disposition_file_name(disposition: &str) -> Option<String> {
+    let parameters = disposition
+        .split(';')
+        .skip(1)
+        .filter_map(|p| p.split_once('='));
+    let mut plain = None;
+    for (key, value) in parameters {
+        match key.trim().to_lowercase().as_str() {
+            // filename*=UTF-8''name%20with%20spaces.pdf, preferred when present
+            "filename*" => {
+                let (_, encoded) = value.trim().rsplit_once('\'')?;
+                return Some(percent_decode(encoded));
+            }
+            "filename" => plain = Some(value.trim().trim_matches('"').to_string()),
+            _ => {}
+        }
+    }
+    plain
+}
+
+fn percent_decode(value: &str) -> String {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let decoded = match tail {
+            [high, low, ..] if byte == b'%' => std::str::from_utf8(&[*high, *low])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+        match decoded {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// How a file is stored on the server
+#[derive(Debug, Deserialize, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageType {
+    Unencrypted,
+    /// Encrypted with GPG, only supported by old versions of paperless
+    Gpg,
+    /// A storage type unknown to this version of the crate
+    #[serde(other)]
+    Unknown,
+}
+
+/// Information about the files of a document
+#[derive(Debug, Deserialize, Clone)]
+pub struct Metadata {
+    #[serde(alias = "originalChecksum")]
+    pub original_checksum: Option<String>,
+    /// Size in bytes of the original file
+    #[serde(alias = "originalSize")]
+    pub original_size: Option<u64>,
+    #[serde(alias = "originalMimeType")]
+    pub original_mime_type: Option<String>,
+    #[serde(alias = "originalFilename")]
+    pub original_filename: Option<String>,
+    #[serde(default, alias = "hasArchiveVersion")]
+    pub has_archive_version: bool,
+    #[serde(alias = "archiveChecksum")]
+    pub archive_checksum: Option<String>,
+    /// Size in bytes of the archived file
+    #[serde(alias = "archiveSize")]
+    pub archive_size: Option<u64>,
+    /// Language detected in the document
+    pub lang: Option<String>,
+}
+
+impl Metadata {
+    /// Size in bytes of a version of the file
+    ///
+    /// Documents without archived version are downloaded in their original version.
+    pub fn size(&self, version: FileVersion) -> Option<u64> {
+        match version {
+            FileVersion::Archive if self.has_archive_version => self.archive_size,
+            _ => self.original_size,
+        }
+    }
+}
+
+/// A document with the names of its metadata
+#[derive(Debug)]
+pub struct DocumentDetailed {
+    pub document: Document,
+    pub correspondent: Option<String>,
+    pub document_type: Option<String>,
+    pub storage_path: Option<String>,
+    pub tags: Vec<String>,
+}
+
+/// Modification of a document
+///
+/// Only the fields set to `Some` are sent to the server, the others are left untouched. Setting
+/// an optional metadata to `Some(None)` removes it.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct Update {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correspondent: Option<Option<correspondent::Id>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub document_type: Option<Option<document_type::Id>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_path: Option<Option<storage_path::Id>>,
+    /// Replace all the tags of the document
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<tag::Id>>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "date::datetime::option"
+    )]
+    pub created: Option<DateTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive_serial_number: Option<Option<asn::ASN>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Replace all the custom fields of the document
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_fields: Option<Vec<custom_field::Instance>>,
+}
+
+/// A document to upload, with the metadata to set on it
+///
+/// Metadata left empty are guessed by the server from the content of the document.
+#[derive(Debug, Clone)]
+pub struct Upload {
+    pub file_name: String,
+    pub content: Vec<u8>,
+    pub title: Option<String>,
+    pub created: Option<DateTime>,
+    pub correspondent: Option<correspondent::Id>,
+    pub document_type: Option<document_type::Id>,
+    pub tags: Vec<tag::Id>,
+    pub archive_serial_number: Option<asn::ASN>,
+}
+
+impl Upload {
+    /// Upload a document from its content
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - Name of the file, the server uses its extension to detect the file type
+    /// * `content` - Content of the file
+    pub fn from_bytes(file_name: &str, content: Vec<u8>) -> Self {
+        Self {
+            file_name: file_name.to_string(),
+            content,
+            title: None,
+            created: None,
+            correspondent: None,
+            document_type: None,
+            tags: Vec::new(),
+            archive_serial_number: None,
+        }
+    }
+
+    /// Upload a local file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the file to read
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Ok(Self::from_bytes(&file_name, std::fs::read(path)?))
+    }
+
+    /// Build the multipart form expected by `post_document`
+    pub(crate) fn into_form(self) -> Form {
+        let mut form = Form::new().part(
+            "document",
+            Part::bytes(self.content).file_name(self.file_name),
+        );
+        if let Some(title) = self.title {
+            form = form.text("title", title);
+        }
+        if let Some(created) = self.created {
+            form = form.text("created", date::format_datetime(&created));
+        }
+        if let Some(correspondent) = self.correspondent {
+            form = form.text("correspondent", correspondent.to_string());
+        }
+        if let Some(document_type) = self.document_type {
+            form = form.text("document_type", document_type.to_string());
+        }
+        // Lists are sent by repeating the field in multipart forms
+        for tag in self.tags {
+            form = form.text("tags", tag.to_string());
+        }
+        if let Some(asn) = self.archive_serial_number {
+            form = form.text("archive_serial_number", asn.to_string());
+        }
+        form
+    }
+}
+
+/// Bound of a date filter
+///
+/// Comparing dates with a date and a time is error-prone: the document created on a day at
+/// midnight in the timezone of the server is created the previous day in UTC. The filters of this
+/// type use the comparison matching the type of the bound.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DateBound {
+    /// Compare with the day of the document
+    Date(Date),
+    /// Compare with the exact time of the document
+    DateTime(DateTime),
+}
+
+impl DateBound {
+    /// Parse a date, or a date with a time, as written in the rules of saved views
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        date::parse_date(value)
+            .map(Self::Date)
+            .or_else(|| date::parse_datetime(value).map(Self::DateTime))
+    }
+
+    /// Value of the bound in the rules of saved views
+    pub(crate) fn to_rule_value(self) -> String {
+        match self {
+            DateBound::Date(d) => date::format_date(&d),
+            DateBound::DateTime(d) => date::format_datetime(&d),
+        }
+    }
+
+    /// Set the field matching the type of the bound, and clear the other one
+    fn set(self, date: &mut Option<Date>, datetime: &mut Option<DateTime>) {
+        match self {
+            DateBound::Date(d) => {
+                *date = Some(d);
+                *datetime = None;
+            }
+            DateBound::DateTime(d) => {
+                *date = None;
+                *datetime = Some(d);
+            }
+        }
+    }
+}
+
+impl From<Date> for DateBound {
+    fn from(value: Date) -> Self {
+        Self::Date(value)
+    }
+}
+impl From<DateTime> for DateBound {
+    fn from(value: DateTime) -> Self {
+        Self::DateTime(value)
+    }
+}
+
+/// Field by which documents are sorted
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Ordering {
+    Id,
+    Title,
+    Created,
+    Added,
+    Modified,
+    /// By name of the correspondent
+    Correspondent,
+    /// By name of the document type
+    DocumentType,
+    /// By name of the storage path
+    StoragePath,
+    ArchiveSerialNumber,
+    NoteCount,
+    PageCount,
+    Owner,
+    /// Relevance, only for full text searches
+    Score,
+}
+
+impl Ordering {
+    /// Name of the field expected by the server
+    pub fn field(self) -> &'static str {
+        match self {
+            Ordering::Id => "id",
+            Ordering::Title => "title",
+            Ordering::Created => "created",
+            Ordering::Added => "added",
+            Ordering::Modified => "modified",
+            Ordering::Correspondent => "correspondent__name",
+            Ordering::DocumentType => "document_type__name",
+            Ordering::StoragePath => "storage_path__name",
+            Ordering::ArchiveSerialNumber => "archive_serial_number",
+            Ordering::NoteCount => "num_notes",
+            Ordering::PageCount => "page_count",
+            Ordering::Owner => "owner",
+            Ordering::Score => "score",
+        }
+    }
+
+    pub fn ascending(self) -> Sort {
+        Sort {
+            ordering: self,
+            descending: false,
+        }
+    }
+
+    pub fn descending(self) -> Sort {
+        Sort {
+            ordering: self,
+            descending: true,
+        }
+    }
+}
+
+/// Sort by a field, in ascending or descending order
+///
+/// # Examples
+///
+/// ```rust
+/// use paperless::document::{Filter, Ordering};
+/// let filter = Filter {
+///     ordering: vec![Ordering::Created.descending(), Ordering::Title.ascending()],
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Sort {
+    pub ordering: Ordering,
+    pub descending: bool,
+}
+
+impl fmt::Display for Sort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.descending {
+            write!(f, "-{}", self.ordering.field())
+        } else {
+            f.write_str(self.ordering.field())
+        }
+    }
 }
 
 /// Filter used when searching for a document
 ///
 /// Multiple values can be defined at the same time if needed
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct Filter {
     /// Query is equivalent to "advanced search" in the interface
     pub query: Option<String>,
@@ -70,26 +597,28 @@ pub struct Filter {
     pub created_year: Option<usize>,
     pub created_month: Option<usize>,
     pub created_day: Option<usize>,
-    pub created_date_gt: Option<NaiveDate>,
-    pub created_gt: Option<DateTime<Utc>>,
-    pub created_date_lt: Option<NaiveDate>,
-    pub created_lt: Option<DateTime<Utc>>,
+    pub created_date_gt: Option<Date>,
+    pub created_gt: Option<DateTime>,
+    pub created_date_lt: Option<Date>,
+    pub created_lt: Option<DateTime>,
     pub added_year: Option<usize>,
     pub added_month: Option<usize>,
     pub added_day: Option<usize>,
-    pub added_date_gt: Option<NaiveDate>,
-    pub added_gt: Option<DateTime<Utc>>,
-    pub added_date_lt: Option<NaiveDate>,
-    pub added_lt: Option<DateTime<Utc>>,
+    pub added_date_gt: Option<Date>,
+    pub added_gt: Option<DateTime>,
+    pub added_date_lt: Option<Date>,
+    pub added_lt: Option<DateTime>,
     pub modified_year: Option<usize>,
     pub modified_month: Option<usize>,
     pub modified_day: Option<usize>,
-    pub modified_date_gt: Option<NaiveDate>,
-    pub modified_gt: Option<DateTime<Utc>>,
-    pub modified_date_lt: Option<NaiveDate>,
-    pub modified_lt: Option<DateTime<Utc>>,
+    pub modified_date_gt: Option<Date>,
+    pub modified_gt: Option<DateTime>,
+    pub modified_date_lt: Option<Date>,
+    pub modified_lt: Option<DateTime>,
     pub correspondent_isnull: Option<bool>,
     pub correspondent_id_in: Option<Vec<correspondent::Id>>,
+    /// The document must have none of those correspondents
+    pub correspondent_id_none: Vec<correspondent::Id>,
     pub correspondent_id: Option<correspondent::Id>,
     pub correspondent_name_starts_with: Option<String>,
     pub correspondent_name_ends_with: Option<String>,
@@ -110,6 +639,8 @@ pub struct Filter {
     pub tag_name_is: Option<String>,
     pub document_type_isnull: Option<bool>,
     pub document_type_id_in: Vec<document_type::Id>,
+    /// The document must have none of those document types
+    pub document_type_id_none: Vec<document_type::Id>,
     pub document_type_id: Option<document_type::Id>,
     pub document_type_name_starts_with: Option<String>,
     pub document_type_name_ends_with: Option<String>,
@@ -117,200 +648,980 @@ pub struct Filter {
     pub document_type_name_is: Option<String>,
     pub storage_path_isnull: Option<bool>,
     pub storage_path_id_in: Vec<storage_path::Id>,
+    /// The document must have none of those storage paths
+    pub storage_path_id_none: Vec<storage_path::Id>,
     pub storage_path_id: Option<storage_path::Id>,
     pub storage_path_name_starts_with: Option<String>,
     pub storage_path_name_ends_with: Option<String>,
     pub storage_path_name_contains: Option<String>,
     pub storage_path_name_is: Option<String>,
+    pub owner_id: Option<user::Id>,
+    /// The document must be owned by one of those users
+    pub owner_id_in: Vec<user::Id>,
+    /// The document must be owned by none of those users
+    pub owner_id_none: Vec<user::Id>,
+    /// `Some(true)` for the documents without owner
+    pub owner_isnull: Option<bool>,
+    /// The document must be owned by this user and shared with others
+    pub shared_by_id: Option<user::Id>,
+    /// A value of a custom field of the document must contain this text
+    pub custom_fields_contains: Option<String>,
+    /// The document must have all those custom fields
+    pub custom_field_id_all: Vec<custom_field::Id>,
+    /// The document must have any of those custom fields
+    pub custom_field_id_in: Vec<custom_field::Id>,
+    /// The document must have none of those custom fields
+    pub custom_field_id_none: Vec<custom_field::Id>,
+    pub has_custom_fields: Option<bool>,
     pub more_like: Option<Id>,
+    /// The document must be one of those
+    pub id_in: Vec<Id>,
+    /// Order of the documents, by the first field then by the next ones for equal values
+    pub ordering: Vec<Sort>,
 }
 
 impl Filter {
     #[rustfmt::skip]
-    /// Insert query parameter in a url
-    pub(crate) fn insert_query(self, url: &mut Url) {
-        if let Some(more_like) = self.more_like {
-            url.query_pairs_mut().append_pair("more_like_id", &more_like.to_string());
-        }
-        if let Some(query) = self.query {
-            url.query_pairs_mut().append_pair("query", &query.to_string());
-        }
-        if let Some(is_tagged) = self.is_tagged {
-            url.query_pairs_mut().append_pair("is_tagged", &is_tagged.to_string());
-        }
-
-        url.query_pairs_mut()
-            .append_pair("title_content", &self.title_content_contains.unwrap_or_default())
-            .append_pair("is_in_inbox", &if let Some(is_in_inbox) = self.is_in_inbox { is_in_inbox.to_string() } else { String::default() })
-            .append_pair("title__istartswith", &self.title_starts_with.unwrap_or_default())
-            .append_pair("title__iendswith", &self.title_ends_with.unwrap_or_default())
-            .append_pair("title__icontains", &self.title_contains.unwrap_or_default())
-            .append_pair("title__iexact", &self.title_is.unwrap_or_default())
-            .append_pair("content__istartswith", &self.content_starts_with.unwrap_or_default())
-            .append_pair("content__iendswith", &self.content_ends_with.unwrap_or_default())
-            .append_pair("content__icontains", &self.content_contains.unwrap_or_default())
-            .append_pair("content__iexact", &self.content_is.unwrap_or_default())
-            .append_pair("archive_serial_number", &self.archive_serial_number_is.map(|asn| asn.to_string()).unwrap_or_default())
-            .append_pair("archive_serial_number__gt", &self.archive_serial_numer_gt.map(|asn| asn.to_string()).unwrap_or_default())
-            .append_pair("archive_serial_number__gte", &self.archive_serial_number_gte.map(|asn| asn.to_string()).unwrap_or_default())
-            .append_pair("archive_serial_number__lt", &self.archive_serial_numer_lt.map(|asn| asn.to_string()).unwrap_or_default())
-            .append_pair("archive_serial_number__lte", &self.archive_serial_number_lte.map(|asn| asn.to_string()).unwrap_or_default())
-            .append_pair("archive_serial_number__isnull", &if let Some(isnull) = self.archive_serial_number_isnull { isnull.to_string() } else { String::default() })
-            .append_pair("created__year", &self.created_year.map(|year| year.to_string()).unwrap_or_default())
-            .append_pair("created__month", &self.created_year.map(|month| month.to_string()).unwrap_or_default())
-            .append_pair("created__day", &self.created_year.map(|day| day.to_string()).unwrap_or_default())
-            .append_pair("created__date__gt", &self.created_date_gt.map(|d| d.format("%Y-%m-%dT%H:%M:%SZ").to_string()).unwrap_or_default())
-            .append_pair("created__gt", &self.created_gt.map(|d| d.format("%Y-%m-%dT%H:%M:%SZ").to_string()).unwrap_or_default())
-            .append_pair("created__date__lt", &self.created_date_lt.map(|d| d.format("%Y-%m-%dT%H:%M:%SZ").to_string()).unwrap_or_default())
-            .append_pair("created__lt", &self.created_lt.map(|d| d.format("%Y-%m-%dT%H:%M:%SZ").to_string()).unwrap_or_default())
-            .append_pair("added__year", &self.added_year.map(|year| year.to_string()).unwrap_or_default())
-            .append_pair("added__month", &self.added_year.map(|month| month.to_string()).unwrap_or_default())
-            .append_pair("added__day", &self.added_year.map(|day| day.to_string()).unwrap_or_default())
-            .append_pair("added__date__gt", &self.added_date_gt.map(|d| d.format("%Y-%m-%dT%H:%M:%SZ").to_string()).unwrap_or_default())
-            .append_pair("added__gt", &self.added_gt.map(|d| d.format("%Y-%m-%dT%H:%M:%SZ").to_string()).unwrap_or_default())
-            .append_pair("added__date__lt", &self.added_date_lt.map(|d| d.format("%Y-%m-%dT%H:%M:%SZ").to_string()).unwrap_or_default())
-            .append_pair("added__lt", &self.added_lt.map(|d| d.format("%Y-%m-%dT%H:%M:%SZ").to_string()).unwrap_or_default())
-            .append_pair("modified__year", &self.modified_year.map(|year| year.to_string()).unwrap_or_default())
-            .append_pair("modified__month", &self.modified_year.map(|month| month.to_string()).unwrap_or_default())
-            .append_pair("modified__day", &self.modified_year.map(|day| day.to_string()).unwrap_or_default())
-            .append_pair("modified__date__gt", &self.modified_date_gt.map(|d| d.format("%Y-%m-%dT%H:%M:%SZ").to_string()).unwrap_or_default())
-            .append_pair("modified__gt", &self.modified_gt.map(|d| d.format("%Y-%m-%dT%H:%M:%SZ").to_string()).unwrap_or_default())
-            .append_pair("modified__date__lt", &self.modified_date_lt.map(|d| d.format("%Y-%m-%dT%H:%M:%SZ").to_string()).unwrap_or_default())
-            .append_pair("modified__lt", &self.modified_lt.map(|d| d.format("%Y-%m-%dT%H:%M:%SZ").to_string()).unwrap_or_default())
-            .append_pair("correspondent__isnull",&if let Some(isnull) = self.correspondent_isnull { isnull.to_string() } else { String::default() })
-            .append_pair("correspondent__id__in", &self.correspondent_id_in.map(|ids| ids.iter().map(|id| id.to_string()).collect::<Vec<String>>().join(",")).unwrap_or_default())
-            .append_pair("correspondent__id", &self.correspondent_id.map(|id| id.to_string()).unwrap_or_default())
-            .append_pair("correspondent__name__istartswith", &self.correspondent_name_starts_with.unwrap_or_default())
-            .append_pair("correspondent__name__iendswith", &self.correspondent_name_ends_with.unwrap_or_default())
-            .append_pair("correspondent__name__icontains", &self.correspondent_name_contains.unwrap_or_default())
-            .append_pair("correspondent__name__iexact", &self.correspondent_name_is.unwrap_or_default())
-            .append_pair("tags__id__in", &self.tag_id_in.iter().map(|id| id.to_string()).collect::<Vec<String>>().join(","))
-            .append_pair("tags__id__all", &self.tag_id_all.iter().map(|id| id.to_string()).collect::<Vec<String>>().join(","))
-            .append_pair("tags__id__none", &self.tag_id_none.iter().map(|id| id.to_string()).collect::<Vec<String>>().join(","))
-            .append_pair("tags__id", &self.tag_id.map(|id| id.to_string()).unwrap_or_default())
-            .append_pair("tags__name__istartswith", &self.tag_name_starts_with.unwrap_or_default())
-            .append_pair("tags__name__iendswith", &self.tag_name_ends_with.unwrap_or_default())
-            .append_pair("tags__name__icontains", &self.tag_name_contains.unwrap_or_default())
-            .append_pair("tags__name__iexact", &self.tag_name_is.unwrap_or_default())
-            .append_pair("document_type__isnull",&if let Some(isnull) = self.document_type_isnull { isnull.to_string() } else { String::default() })
-            .append_pair("document_type__id__in", &self.document_type_id_in.iter().map(|id| id.to_string()).collect::<Vec<String>>().join(","))
-            .append_pair("document_type__id", &self.document_type_id.map(|id| id.to_string()).unwrap_or_default())
-            .append_pair("document_type__name__istartswith", &self.document_type_name_starts_with.unwrap_or_default())
-            .append_pair("document_type__name__iendswith", &self.document_type_name_ends_with.unwrap_or_default())
-            .append_pair("document_type__name__icontains", &self.document_type_name_contains.unwrap_or_default())
-            .append_pair("document_type__name__iexact", &self.document_type_name_is.unwrap_or_default())
-            .append_pair("storage_path__isnull",&if let Some(isnull) = self.storage_path_isnull { isnull.to_string() } else { String::default() })
-            .append_pair("storage_path__id__in", &self.storage_path_id_in.iter().map(|id| id.to_string()).collect::<Vec<String>>().join(","))
-            .append_pair("storage_path__id", &self.storage_path_id.map(|id| id.to_string()).unwrap_or_default())
-            .append_pair("storage_path__name__istartswith", &self.storage_path_name_starts_with.unwrap_or_default())
-            .append_pair("storage_path__name__iendswith", &self.storage_path_name_ends_with.unwrap_or_default())
-            .append_pair("storage_path__name__icontains", &self.storage_path_name_contains.unwrap_or_default())
-            .append_pair("storage_path__name__iexact", &self.storage_path_name_is.unwrap_or_default());
-    }
-
-    /// Create a filter from view rules
+    /// Insert query parameter in a url, only for the fields which are set
+    pub(crate) fn insert_query(&self, url: &mut Url) {
+        query::append_some(url, "more_like_id", self.more_like);
+        query::append_some(url, "query", self.query.as_deref());
+        query::append_some(url, "is_tagged", self.is_tagged);
+        query::append_some(url, "title_content", self.title_content_contains.as_deref());
+        query::append_some(url, "is_in_inbox", self.is_in_inbox);
+        query::append_some(url, "title__istartswith", self.title_starts_with.as_deref());
+        query::append_some(url, "title__iendswith", self.title_ends_with.as_deref());
+        query::append_some(url, "title__icontains", self.title_contains.as_deref());
+        query::append_some(url, "title__iexact", self.title_is.as_deref());
+        query::append_some(url, "content__istartswith", self.content_starts_with.as_deref());
+        query::append_some(url, "content__iendswith", self.content_ends_with.as_deref());
+        query::append_some(url, "content__icontains", self.content_contains.as_deref());
+        query::append_some(url, "content__iexact", self.content_is.as_deref());
+        query::append_some(url, "archive_serial_number", self.archive_serial_number_is);
+        query::append_some(url, "archive_serial_number__gt", self.archive_serial_numer_gt);
+        query::append_some(url, "archive_serial_number__gte", self.archive_serial_number_gte);
+        query::append_some(url, "archive_serial_number__lt", self.archive_serial_numer_lt);
+        query::append_some(url, "archive_serial_number__lte", self.archive_serial_number_lte);
+        query::append_some(url, "archive_serial_number__isnull", self.archive_serial_number_isnull);
+        query::append_some(url, "created__year", self.created_year);
+        query::append_some(url, "created__month", self.created_month);
+        query::append_some(url, "created__day", self.created_day);
+        query::append_some(url, "created__date__gt", self.created_date_gt.as_ref().map(date::format_date));
+        query::append_some(url, "created__gt", self.created_gt.as_ref().map(date::format_datetime));
+        query::append_some(url, "created__date__lt", self.created_date_lt.as_ref().map(date::format_date));
+        query::append_some(url, "created__lt", self.created_lt.as_ref().map(date::format_datetime));
+        query::append_some(url, "added__year", self.added_year);
+        query::append_some(url, "added__month", self.added_month);
+        query::append_some(url, "added__day", self.added_day);
+        query::append_some(url, "added__date__gt", self.added_date_gt.as_ref().map(date::format_date));
+        query::append_some(url, "added__gt", self.added_gt.as_ref().map(date::format_datetime));
+        query::append_some(url, "added__date__lt", self.added_date_lt.as_ref().map(date::format_date));
+        query::append_some(url, "added__lt", self.added_lt.as_ref().map(date::format_datetime));
+        query::append_some(url, "modified__year", self.modified_year);
+        query::append_some(url, "modified__month", self.modified_month);
+        query::append_some(url, "modified__day", self.modified_day);
+        query::append_some(url, "modified__date__gt", self.modified_date_gt.as_ref().map(date::format_date));
+        query::append_some(url, "modified__gt", self.modified_gt.as_ref().map(date::format_datetime));
+        query::append_some(url, "modified__date__lt", self.modified_date_lt.as_ref().map(date::format_date));
+        query::append_some(url, "modified__lt", self.modified_lt.as_ref().map(date::format_datetime));
+        query::append_some(url, "correspondent__isnull", self.correspondent_isnull);
+        query::append_some(url, "correspondent__id", self.correspondent_id);
+        query::append_some(url, "correspondent__name__istartswith", self.correspondent_name_starts_with.as_deref());
+        query::append_some(url, "correspondent__name__iendswith", self.correspondent_name_ends_with.as_deref());
+        query::append_some(url, "correspondent__name__icontains", self.correspondent_name_contains.as_deref());
+        query::append_some(url, "correspondent__name__iexact", self.correspondent_name_is.as_deref());
+        query::append_some(url, "tags__id", self.tag_id);
+        query::append_some(url, "tags__name__istartswith", self.tag_name_starts_with.as_deref());
+        query::append_some(url, "tags__name__iendswith", self.tag_name_ends_with.as_deref());
+        query::append_some(url, "tags__name__icontains", self.tag_name_contains.as_deref());
+        query::append_some(url, "tags__name__iexact", self.tag_name_is.as_deref());
+        query::append_some(url, "document_type__isnull", self.document_type_isnull);
+        query::append_some(url, "document_type__id", self.document_type_id);
+        query::append_some(url, "document_type__name__istartswith", self.document_type_name_starts_with.as_deref());
+        query::append_some(url, "document_type__name__iendswith", self.document_type_name_ends_with.as_deref());
+        query::append_some(url, "document_type__name__icontains", self.document_type_name_contains.as_deref());
+        query::append_some(url, "document_type__name__iexact", self.document_type_name_is.as_deref());
+        query::append_some(url, "storage_path__isnull", self.storage_path_isnull);
+        query::append_some(url, "storage_path__id", self.storage_path_id);
+        query::append_some(url, "storage_path__name__istartswith", self.storage_path_name_starts_with.as_deref());
+        query::append_some(url, "storage_path__name__iendswith", self.storage_path_name_ends_with.as_deref());
+        query::append_some(url, "storage_path__name__icontains", self.storage_path_name_contains.as_deref());
+        query::append_some(url, "storage_path__name__iexact", self.storage_path_name_is.as_deref());
+        query::append_some(url, "owner__id", self.owner_id);
+        query::append_some(url, "owner__isnull", self.owner_isnull);
+        query::append_some(url, "shared_by__id", self.shared_by_id);
+        query::append_some(url, "custom_fields__icontains", self.custom_fields_contains.as_deref());
+        query::append_some(url, "has_custom_fields", self.has_custom_fields);
+
+        // `BaseInFilter`s, `ObjectFilter`s and the ordering, all comma separated
+        query::append_list(url, "id__in", &self.id_in);
+        query::append_list(url, "correspondent__id__in", self.correspondent_id_in.as_deref().unwrap_or_default());
+        query::append_list(url, "tags__id__in", &self.tag_id_in);
+        query::append_list(url, "tags__id__all", &self.tag_id_all);
+        query::append_list(url, "tags__id__none", &self.tag_id_none);
+        query::append_list(url, "correspondent__id__none", &self.correspondent_id_none);
+        query::append_list(url, "document_type__id__in", &self.document_type_id_in);
+        query::append_list(url, "document_type__id__none", &self.document_type_id_none);
+        query::append_list(url, "storage_path__id__in", &self.storage_path_id_in);
+        query::append_list(url, "storage_path__id__none", &self.storage_path_id_none);
+        query::append_list(url, "owner__id__in", &self.owner_id_in);
+        query::append_list(url, "owner__id__none", &self.owner_id_none);
+        query::append_list(url, "custom_fields__id__all", &self.custom_field_id_all);
+        query::append_list(url, "custom_fields__id__in", &self.custom_field_id_in);
+        query::append_list(url, "custom_fields__id__none", &self.custom_field_id_none);
+        query::append_list(url, "ordering", &self.ordering);
+    }
+
+    /// Merge two filters into a new one
+    ///
+    /// Every field set in `other` takes precedence over the same field in `self`, a list is set if
+    /// it is not empty. The `_all` and `_none` lists are the exception: every id of both filters
+    /// must match, so their ids are concatenated, the ones of `self` first, without duplicates.
+    /// The `_in` lists are not, a document matching any id of either list would match more
+    /// documents than each filter.
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            query: other.query.or(self.query),
+            title_content_contains: other.title_content_contains.or(self.title_content_contains),
+            is_in_inbox: other.is_in_inbox.or(self.is_in_inbox),
+            title_starts_with: other.title_starts_with.or(self.title_starts_with),
+            title_ends_with: other.title_ends_with.or(self.title_ends_with),
+            title_contains: other.title_contains.or(self.title_contains),
+            title_is: other.title_is.or(self.title_is),
+            content_starts_with: other.content_starts_with.or(self.content_starts_with),
+            content_ends_with: other.content_ends_with.or(self.content_ends_with),
+            content_contains: other.content_contains.or(self.content_contains),
+            content_is: other.content_is.or(self.content_is),
+            archive_serial_number_is: other
+                .archive_serial_number_is
+                .or(self.archive_serial_number_is),
+            archive_serial_numer_gt: other
+                .archive_serial_numer_gt
+                .or(self.archive_serial_numer_gt),
+            archive_serial_number_gte: other
+                .archive_serial_number_gte
+                .or(self.archive_serial_number_gte),
+            archive_serial_numer_lt: other
+                .archive_serial_numer_lt
+                .or(self.archive_serial_numer_lt),
+            archive_serial_number_lte: other
+                .archive_serial_number_lte
+                .or(self.archive_serial_number_lte),
+            archive_serial_number_isnull: other
+                .archive_serial_number_isnull
+                .or(self.archive_serial_number_isnull),
+            created_year: other.created_year.or(self.created_year),
+            created_month: other.created_month.or(self.created_month),
+            created_day: other.created_day.or(self.created_day),
+            created_date_gt: other.created_date_gt.or(self.created_date_gt),
+            created_gt: other.created_gt.or(self.created_gt),
+            created_date_lt: other.created_date_lt.or(self.created_date_lt),
+            created_lt: other.created_lt.or(self.created_lt),
+            added_year: other.added_year.or(self.added_year),
+            added_month: other.added_month.or(self.added_month),
+            added_day: other.added_day.or(self.added_day),
+            added_date_gt: other.added_date_gt.or(self.added_date_gt),
+            added_gt: other.added_gt.or(self.added_gt),
+            added_date_lt: other.added_date_lt.or(self.added_date_lt),
+            added_lt: other.added_lt.or(self.added_lt),
+            modified_year: other.modified_year.or(self.modified_year),
+            modified_month: other.modified_month.or(self.modified_month),
+            modified_day: other.modified_day.or(self.modified_day),
+            modified_date_gt: other.modified_date_gt.or(self.modified_date_gt),
+            modified_gt: other.modified_gt.or(self.modified_gt),
+            modified_date_lt: other.modified_date_lt.or(self.modified_date_lt),
+            modified_lt: other.modified_lt.or(self.modified_lt),
+            correspondent_isnull: other.correspondent_isnull.or(self.correspondent_isnull),
+            correspondent_id_in: other.correspondent_id_in.or(self.correspondent_id_in),
+            correspondent_id_none: union(self.correspondent_id_none, other.correspondent_id_none),
+            correspondent_id: other.correspondent_id.or(self.correspondent_id),
+            correspondent_name_starts_with: other
+                .correspondent_name_starts_with
+                .or(self.correspondent_name_starts_with),
+            correspondent_name_ends_with: other
+                .correspondent_name_ends_with
+                .or(self.correspondent_name_ends_with),
+            correspondent_name_contains: other
+                .correspondent_name_contains
+                .or(self.correspondent_name_contains),
+            correspondent_name_is: other.correspondent_name_is.or(self.correspondent_name_is),
+            is_tagged: other.is_tagged.or(self.is_tagged),
+            tag_id_all: union(self.tag_id_all, other.tag_id_all),
+            tag_id_none: union(self.tag_id_none, other.tag_id_none),
+            tag_id_in: replaced(self.tag_id_in, other.tag_id_in),
+            tag_id: other.tag_id.or(self.tag_id),
+            tag_name_starts_with: other.tag_name_starts_with.or(self.tag_name_starts_with),
+            tag_name_ends_with: other.tag_name_ends_with.or(self.tag_name_ends_with),
+            tag_name_contains: other.tag_name_contains.or(self.tag_name_contains),
+            tag_name_is: other.tag_name_is.or(self.tag_name_is),
+            document_type_isnull: other.document_type_isnull.or(self.document_type_isnull),
+            document_type_id_in: replaced(self.document_type_id_in, other.document_type_id_in),
+            document_type_id_none: union(self.document_type_id_none, other.document_type_id_none),
+            document_type_id: other.document_type_id.or(self.document_type_id),
+            document_type_name_starts_with: other
+                .document_type_name_starts_with
+                .or(self.document_type_name_starts_with),
+            document_type_name_ends_with: other
+                .document_type_name_ends_with
+                .or(self.document_type_name_ends_with),
+            document_type_name_contains: other
+                .document_type_name_contains
+                .or(self.document_type_name_contains),
+            document_type_name_is: other.document_type_name_is.or(self.document_type_name_is),
+            storage_path_isnull: other.storage_path_isnull.or(self.storage_path_isnull),
+            storage_path_id_in: replaced(self.storage_path_id_in, other.storage_path_id_in),
+            storage_path_id_none: union(self.storage_path_id_none, other.storage_path_id_none),
+            storage_path_id: other.storage_path_id.or(self.storage_path_id),
+            storage_path_name_starts_with: other
+                .storage_path_name_starts_with
+                .or(self.storage_path_name_starts_with),
+            storage_path_name_ends_with: other
+                .storage_path_name_ends_with
+                .or(self.storage_path_name_ends_with),
+            storage_path_name_contains: other
+                .storage_path_name_contains
+                .or(self.storage_path_name_contains),
+            storage_path_name_is: other.storage_path_name_is.or(self.storage_path_name_is),
+            owner_id: other.owner_id.or(self.owner_id),
+            owner_id_in: replaced(self.owner_id_in, other.owner_id_in),
+            owner_id_none: union(self.owner_id_none, other.owner_id_none),
+            owner_isnull: other.owner_isnull.or(self.owner_isnull),
+            shared_by_id: other.shared_by_id.or(self.shared_by_id),
+            custom_fields_contains: other.custom_fields_contains.or(self.custom_fields_contains),
+            custom_field_id_all: union(self.custom_field_id_all, other.custom_field_id_all),
+            custom_field_id_in: replaced(self.custom_field_id_in, other.custom_field_id_in),
+            custom_field_id_none: union(self.custom_field_id_none, other.custom_field_id_none),
+            has_custom_fields: other.has_custom_fields.or(self.has_custom_fields),
+            more_like: other.more_like.or(self.more_like),
+            id_in: replaced(self.id_in, other.id_in),
+            ordering: replaced(self.ordering, other.ordering),
+        }
+    }
+
+    /// Keep only the documents created strictly after a date, or a date with a time
+    ///
+    /// A date is compared with the day of the document, a date with a time with its exact time.
+    ///
+    /// # Arguments
+    ///
+    /// * `bound` - The excluded bound
+    pub fn created_after(mut self, bound: impl Into<DateBound>) -> Self {
+        bound
+            .into()
+            .set(&mut self.created_date_gt, &mut self.created_gt);
+        self
+    }
+
+    /// Keep only the documents created strictly before a date, or a date with a time
+    ///
+    /// A date is compared with the day of the document, a date with a time with its exact time.
+    ///
+    /// # Arguments
+    ///
+    /// * `bound` - The excluded bound
+    pub fn created_before(mut self, bound: impl Into<DateBound>) -> Self {
+        bound
+            .into()
+            .set(&mut self.created_date_lt, &mut self.created_lt);
+        self
+    }
+
+    /// Keep only the documents added strictly after a date, or a date with a time
+    ///
+    /// A date is compared with the day of the document, a date with a time with its exact time.
+    ///
+    /// # Arguments
+    ///
+    /// * `bound` - The excluded bound
+    pub fn added_after(mut self, bound: impl Into<DateBound>) -> Self {
+        bound
+            .into()
+            .set(&mut self.added_date_gt, &mut self.added_gt);
+        self
+    }
+
+    /// Keep only the documents added strictly before a date, or a date with a time
+    ///
+    /// A date is compared with the day of the document, a date with a time with its exact time.
+    ///
+    /// # Arguments
+    ///
+    /// * `bound` - The excluded bound
+    pub fn added_before(mut self, bound: impl Into<DateBound>) -> Self {
+        bound
+            .into()
+            .set(&mut self.added_date_lt, &mut self.added_lt);
+        self
+    }
+
+    /// Keep only the documents modified strictly after a date, or a date with a time
+    ///
+    /// A date is compared with the day of the document, a date with a time with its exact time.
+    ///
+    /// # Arguments
+    ///
+    /// * `bound` - The excluded bound
+    pub fn modified_after(mut self, bound: impl Into<DateBound>) -> Self {
+        bound
+            .into()
+            .set(&mut self.modified_date_gt, &mut self.modified_gt);
+        self
+    }
+
+    /// Keep only the documents modified strictly before a date, or a date with a time
+    ///
+    /// A date is compared with the day of the document, a date with a time with its exact time.
+    ///
+    /// # Arguments
+    ///
+    /// * `bound` - The excluded bound
+    pub fn modified_before(mut self, bound: impl Into<DateBound>) -> Self {
+        bound
+            .into()
+            .set(&mut self.modified_date_lt, &mut self.modified_lt);
+        self
+    }
+
+    /// Keep only the documents created between `from` and `to`, both included
+    ///
+    /// The server only supports strict comparisons, so the bounds are moved by one day.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - First day of the range
+    /// * `to` - Last day of the range
+    pub fn created_between(mut self, from: Date, to: Date) -> Self {
+        self.created_date_gt = date::previous_day(&from);
+        self.created_date_lt = date::next_day(&to);
+        self.created_gt = None;
+        self.created_lt = None;
+        self
+    }
+
+    /// Keep only the documents added between `from` and `to`, both included
+    ///
+    /// The server only supports strict comparisons, so the bounds are moved by one day.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - First day of the range
+    /// * `to` - Last day of the range
+    pub fn added_between(mut self, from: Date, to: Date) -> Self {
+        self.added_date_gt = date::previous_day(&from);
+        self.added_date_lt = date::next_day(&to);
+        self.added_gt = None;
+        self.added_lt = None;
+        self
+    }
+
+    /// Keep only the documents modified between `from` and `to`, both included
+    ///
+    /// The server only supports strict comparisons, so the bounds are moved by one day.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - First day of the range
+    /// * `to` - Last day of the range
+    pub fn modified_between(mut self, from: Date, to: Date) -> Self {
+        self.modified_date_gt = date::previous_day(&from);
+        self.modified_date_lt = date::next_day(&to);
+        self.modified_gt = None;
+        self.modified_lt = None;
+        self
+    }
+
+    /// Create a filter from the rules of a saved view
+    ///
+    /// Rules unknown to this version of the crate are ignored, they are listed by
+    /// [`saved_view::FilterRule::unsupported`].
     pub fn from_filter_rules(filter_rules: &[saved_view::FilterRule]) -> Self {
+        use saved_view::FilterRule;
+
         let mut filter = Self::default();
         for rule in filter_rules {
-            match rule {
-                saved_view::FilterRule::TitleContains(v) => {
-                    filter.title_contains = v.clone();
-                }
-                saved_view::FilterRule::ContentContains(v) => {
-                    filter.content_contains = v.clone();
-                }
-                saved_view::FilterRule::ASNIs(Some(v)) => {
-                    filter.archive_serial_number_is = Some(v.clone());
-                }
-                saved_view::FilterRule::ASNIs(None) => {
-                    filter.archive_serial_number_isnull = Some(true);
-                }
-                saved_view::FilterRule::CorrespondentIs(Some(v)) => {
-                    filter.correspondent_id = Some(v.clone());
-                }
-                saved_view::FilterRule::CorrespondentIs(None) => {
-                    filter.correspondent_isnull = Some(true);
-                }
-                saved_view::FilterRule::DocumentTypeIs(Some(v)) => {
-                    filter.document_type_id = Some(v.clone());
-                }
-                saved_view::FilterRule::DocumentTypeIs(None) => {
-                    filter.document_type_isnull = Some(true);
-                }
-                saved_view::FilterRule::IsInInbox(v) => {
-                    filter.is_in_inbox = v.clone();
-                }
-                saved_view::FilterRule::HasTag(Some(tag)) => {
-                    filter.tag_id_all.push(tag.clone());
-                }
-                saved_view::FilterRule::HasAnyTag(v) => {
-                    filter.is_tagged = v.clone();
-                }
-                saved_view::FilterRule::CreatedBefore(v) => {
-                    filter.created_lt = v.clone();
-                }
-                saved_view::FilterRule::CreatedAfter(v) => {
-                    filter.created_gt = v.clone();
-                }
-                saved_view::FilterRule::CreatedYearIs(v) => {
-                    filter.created_year = v.clone();
-                }
-                saved_view::FilterRule::CreatedMountIs(v) => {
-                    filter.created_month = v.clone();
-                }
-                saved_view::FilterRule::CreatedDayIs(v) => {
-                    filter.created_day = v.clone();
-                }
-                saved_view::FilterRule::AddedBefore(v) => {
-                    filter.added_lt = v.clone();
-                }
-                saved_view::FilterRule::AddedAfter(v) => {
-                    filter.added_gt = v.clone();
-                }
-                saved_view::FilterRule::ModifiedBefore(v) => {
-                    filter.modified_lt = v.clone();
-                }
-                saved_view::FilterRule::ModifiedAfter(v) => {
-                    filter.modified_gt = v.clone();
-                }
-                saved_view::FilterRule::DontHaveTag(Some(tag)) => {
-                    filter.tag_id_none.push(tag.clone());
+            match rule.clone() {
+                FilterRule::TitleContains(v) => filter.title_contains = v,
+                FilterRule::ContentContains(v) => filter.content_contains = v,
+                FilterRule::ASNIs(Some(v)) => filter.archive_serial_number_is = Some(v),
+                FilterRule::ASNIs(None) => filter.archive_serial_number_isnull = Some(true),
+                FilterRule::CorrespondentIs(Some(v)) => filter.correspondent_id = Some(v),
+                FilterRule::CorrespondentIs(None) | FilterRule::HasCorrespondentIn(None) => {
+                    filter.correspondent_isnull = Some(true)
                 }
-                saved_view::FilterRule::DontHaveASN(v) => {
-                    filter.archive_serial_number_isnull = v.clone();
+                FilterRule::HasCorrespondentIn(Some(v)) => filter
+                    .correspondent_id_in
+                    .get_or_insert_with(Vec::new)
+                    .push(v),
+                FilterRule::DontHaveCorrespondent(Some(v)) => filter.correspondent_id_none.push(v),
+                FilterRule::DontHaveCorrespondent(None) => {
+                    filter.correspondent_isnull = Some(false)
                 }
-                saved_view::FilterRule::TitleOrContentContains(v) => {
-                    filter.title_content_contains = v.clone();
+                FilterRule::DocumentTypeIs(Some(v)) => filter.document_type_id = Some(v),
+                FilterRule::DocumentTypeIs(None) | FilterRule::HasDocumentTypeIn(None) => {
+                    filter.document_type_isnull = Some(true)
                 }
-                saved_view::FilterRule::FullTextQuery(v) => {
-                    filter.query = v.clone();
+                FilterRule::HasDocumentTypeIn(Some(v)) => filter.document_type_id_in.push(v),
+                FilterRule::DontHaveDocumentType(Some(v)) => filter.document_type_id_none.push(v),
+                FilterRule::DontHaveDocumentType(None) => filter.document_type_isnull = Some(false),
+                FilterRule::StoragePathIs(Some(v)) => filter.storage_path_id = Some(v),
+                FilterRule::StoragePathIs(None) | FilterRule::HasStoragePathIn(None) => {
+                    filter.storage_path_isnull = Some(true)
                 }
-                saved_view::FilterRule::MoreLikeThis(v) => {
-                    filter.more_like = v.clone();
+                FilterRule::HasStoragePathIn(Some(v)) => filter.storage_path_id_in.push(v),
+                FilterRule::DontHaveStoragePath(Some(v)) => filter.storage_path_id_none.push(v),
+                FilterRule::DontHaveStoragePath(None) => filter.storage_path_isnull = Some(false),
+                FilterRule::IsInInbox(v) => filter.is_in_inbox = v,
+                FilterRule::HasTag(Some(v)) => filter.tag_id_all.push(v),
+                FilterRule::DontHaveTag(Some(v)) => filter.tag_id_none.push(v),
+                FilterRule::HasTagIn(Some(v)) => filter.tag_id_in.push(v),
+                // Documents without tags are selected with `HasAnyTag`
+                FilterRule::HasTag(None)
+                | FilterRule::DontHaveTag(None)
+                | FilterRule::HasTagIn(None) => {}
+                FilterRule::HasAnyTag(v) => filter.is_tagged = v,
+                FilterRule::CreatedBefore(v) => {
+                    filter = v.into_iter().fold(filter, Self::created_before)
                 }
-                saved_view::FilterRule::HasTagIn(Some(tag)) => {
-                    filter.tag_id_in.push(tag.clone());
+                FilterRule::CreatedAfter(v) => {
+                    filter = v.into_iter().fold(filter, Self::created_after)
                 }
-                saved_view::FilterRule::ASNGreaterThan(v) => {
-                    filter.archive_serial_numer_gt = v.clone();
+                FilterRule::CreatedYearIs(v) => filter.created_year = v,
+                FilterRule::CreatedMountIs(v) => filter.created_month = v,
+                FilterRule::CreatedDayIs(v) => filter.created_day = v,
+                FilterRule::AddedBefore(v) => {
+                    filter = v.into_iter().fold(filter, Self::added_before)
                 }
-                saved_view::FilterRule::ASNLessThan(v) => {
-                    filter.archive_serial_numer_lt = v.clone();
+                FilterRule::AddedAfter(v) => filter = v.into_iter().fold(filter, Self::added_after),
+                FilterRule::ModifiedBefore(v) => {
+                    filter = v.into_iter().fold(filter, Self::modified_before)
                 }
-                saved_view::FilterRule::StoragePathIs(Some(v)) => {
-                    filter.storage_path_id = Some(v.clone());
-                }
-                saved_view::FilterRule::StoragePathIs(None) => {
-                    filter.storage_path_isnull = Some(true);
-                }
-                r => {
-                    println!("Ignore {:?}", r)
+                FilterRule::ModifiedAfter(v) => {
+                    filter = v.into_iter().fold(filter, Self::modified_after)
                 }
+                FilterRule::DontHaveASN(v) => filter.archive_serial_number_isnull = v,
+                FilterRule::TitleOrContentContains(v) => filter.title_content_contains = v,
+                FilterRule::FullTextQuery(v) => filter.query = v,
+                FilterRule::MoreLikeThis(v) => filter.more_like = v,
+                FilterRule::ASNGreaterThan(v) => filter.archive_serial_numer_gt = v,
+                FilterRule::ASNLessThan(v) => filter.archive_serial_numer_lt = v,
+                FilterRule::OwnerIs(v) => filter.owner_id = v,
+                FilterRule::OwnerIn(Some(v)) => filter.owner_id_in.push(v),
+                FilterRule::DontHaveOwner(Some(v)) => filter.owner_id_none.push(v),
+                FilterRule::OwnerIn(None) | FilterRule::DontHaveOwner(None) => {}
+                FilterRule::OwnerIsNull(v) => filter.owner_isnull = v,
+                FilterRule::SharedBy(v) => filter.shared_by_id = v,
+                FilterRule::CustomFieldsContains(v) => filter.custom_fields_contains = v,
+                FilterRule::HasCustomFieldsAll(Some(v)) => filter.custom_field_id_all.push(v),
+                FilterRule::HasCustomFieldsIn(Some(v)) => filter.custom_field_id_in.push(v),
+                FilterRule::DontHaveCustomFields(Some(v)) => filter.custom_field_id_none.push(v),
+                FilterRule::HasCustomFieldsAll(None)
+                | FilterRule::HasCustomFieldsIn(None)
+                | FilterRule::DontHaveCustomFields(None) => {}
+                FilterRule::HasAnyCustomFields(v) => filter.has_custom_fields = v,
+                FilterRule::Unknown { .. } => {}
             }
         }
         filter
     }
+
+    /// Rules of a saved view selecting the same documents as this filter, the reverse of
+    /// [`Filter::from_filter_rules`]
+    ///
+    /// Fields which can't be expressed with rules, like the names or `id_in`, are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use paperless::document::Filter;
+    /// use paperless::saved_view::FilterRule;
+    ///
+    /// let rules = vec![
+    ///     FilterRule::TitleContains(Some("invoice".to_string())),
+    ///     FilterRule::HasTag(Some(3.into())),
+    ///     FilterRule::HasTag(Some(5.into())),
+    ///     FilterRule::DontHaveCorrespondent(Some(2.into())),
+    /// ];
+    /// assert_eq!(Filter::from_filter_rules(&rules).to_filter_rules(), rules);
+    /// ```
+    pub fn to_filter_rules(&self) -> Vec<saved_view::FilterRule> {
+        use saved_view::FilterRule;
+
+        let mut rules = Vec::new();
+        let mut push = |rule: Option<FilterRule>| rules.extend(rule);
+        let isnull = |isnull: Option<bool>, is: FilterRule, is_not: FilterRule| match isnull {
+            Some(true) => Some(is),
+            Some(false) => Some(is_not),
+            None => None,
+        };
+
+        push(
+            self.title_contains
+                .clone()
+                .map(|v| FilterRule::TitleContains(Some(v))),
+        );
+        push(
+            self.content_contains
+                .clone()
+                .map(|v| FilterRule::ContentContains(Some(v))),
+        );
+        push(
+            self.archive_serial_number_is
+                .map(|v| FilterRule::ASNIs(Some(v))),
+        );
+        push(
+            self.correspondent_id
+                .map(|v| FilterRule::CorrespondentIs(Some(v))),
+        );
+        push(
+            self.document_type_id
+                .map(|v| FilterRule::DocumentTypeIs(Some(v))),
+        );
+        push(self.is_in_inbox.map(|v| FilterRule::IsInInbox(Some(v))));
+        for tag in &self.tag_id_all {
+            push(Some(FilterRule::HasTag(Some(*tag))));
+        }
+        push(self.is_tagged.map(|v| FilterRule::HasAnyTag(Some(v))));
+        push(
+            self.created_date_lt
+                .map(|v| FilterRule::CreatedBefore(Some(v.into()))),
+        );
+        push(
+            self.created_lt
+                .map(|v| FilterRule::CreatedBefore(Some(v.into()))),
+        );
+        push(
+            self.created_date_gt
+                .map(|v| FilterRule::CreatedAfter(Some(v.into()))),
+        );
+        push(
+            self.created_gt
+                .map(|v| FilterRule::CreatedAfter(Some(v.into()))),
+        );
+        push(
+            self.created_year
+                .map(|v| FilterRule::CreatedYearIs(Some(v))),
+        );
+        push(
+            self.created_month
+                .map(|v| FilterRule::CreatedMountIs(Some(v))),
+        );
+        push(self.created_day.map(|v| FilterRule::CreatedDayIs(Some(v))));
+        push(
+            self.added_date_lt
+                .map(|v| FilterRule::AddedBefore(Some(v.into()))),
+        );
+        push(
+            self.added_lt
+                .map(|v| FilterRule::AddedBefore(Some(v.into()))),
+        );
+        push(
+            self.added_date_gt
+                .map(|v| FilterRule::AddedAfter(Some(v.into()))),
+        );
+        push(
+            self.added_gt
+                .map(|v| FilterRule::AddedAfter(Some(v.into()))),
+        );
+        push(
+            self.modified_date_lt
+                .map(|v| FilterRule::ModifiedBefore(Some(v.into()))),
+        );
+        push(
+            self.modified_lt
+                .map(|v| FilterRule::ModifiedBefore(Some(v.into()))),
+        );
+        push(
+            self.modified_date_gt
+                .map(|v| FilterRule::ModifiedAfter(Some(v.into()))),
+        );
+        push(
+            self.modified_gt
+                .map(|v| FilterRule::ModifiedAfter(Some(v.into()))),
+        );
+        for tag in &self.tag_id_none {
+            push(Some(FilterRule::DontHaveTag(Some(*tag))));
+        }
+        push(
+            self.archive_serial_number_isnull
+                .map(|v| FilterRule::DontHaveASN(Some(v))),
+        );
+        push(
+            self.title_content_contains
+                .clone()
+                .map(|v| FilterRule::TitleOrContentContains(Some(v))),
+        );
+        push(
+            self.query
+                .clone()
+                .map(|v| FilterRule::FullTextQuery(Some(v))),
+        );
+        push(self.more_like.map(|v| FilterRule::MoreLikeThis(Some(v))));
+        for tag in &self.tag_id_in {
+            push(Some(FilterRule::HasTagIn(Some(*tag))));
+        }
+        push(
+            self.archive_serial_numer_gt
+                .map(|v| FilterRule::ASNGreaterThan(Some(v))),
+        );
+        push(
+            self.archive_serial_numer_lt
+                .map(|v| FilterRule::ASNLessThan(Some(v))),
+        );
+        push(
+            self.storage_path_id
+                .map(|v| FilterRule::StoragePathIs(Some(v))),
+        );
+        push(isnull(
+            self.correspondent_isnull,
+            FilterRule::CorrespondentIs(None),
+            FilterRule::DontHaveCorrespondent(None),
+        ));
+        for id in self.correspondent_id_in.iter().flatten() {
+            push(Some(FilterRule::HasCorrespondentIn(Some(*id))));
+        }
+        for id in &self.correspondent_id_none {
+            push(Some(FilterRule::DontHaveCorrespondent(Some(*id))));
+        }
+        push(isnull(
+            self.document_type_isnull,
+            FilterRule::DocumentTypeIs(None),
+            FilterRule::DontHaveDocumentType(None),
+        ));
+        for id in &self.document_type_id_in {
+            push(Some(FilterRule::HasDocumentTypeIn(Some(*id))));
+        }
+        for id in &self.document_type_id_none {
+            push(Some(FilterRule::DontHaveDocumentType(Some(*id))));
+        }
+        push(isnull(
+            self.storage_path_isnull,
+            FilterRule::StoragePathIs(None),
+            FilterRule::DontHaveStoragePath(None),
+        ));
+        for id in &self.storage_path_id_in {
+            push(Some(FilterRule::HasStoragePathIn(Some(*id))));
+        }
+        for id in &self.storage_path_id_none {
+            push(Some(FilterRule::DontHaveStoragePath(Some(*id))));
+        }
+        push(self.owner_id.map(|v| FilterRule::OwnerIs(Some(v))));
+        for id in &self.owner_id_in {
+            push(Some(FilterRule::OwnerIn(Some(*id))));
+        }
+        push(self.owner_isnull.map(|v| FilterRule::OwnerIsNull(Some(v))));
+        for id in &self.owner_id_none {
+            push(Some(FilterRule::DontHaveOwner(Some(*id))));
+        }
+        push(
+            self.custom_fields_contains
+                .clone()
+                .map(|v| FilterRule::CustomFieldsContains(Some(v))),
+        );
+        push(self.shared_by_id.map(|v| FilterRule::SharedBy(Some(v))));
+        for id in &self.custom_field_id_all {
+            push(Some(FilterRule::HasCustomFieldsAll(Some(*id))));
+        }
+        for id in &self.custom_field_id_in {
+            push(Some(FilterRule::HasCustomFieldsIn(Some(*id))));
+        }
+        for id in &self.custom_field_id_none {
+            push(Some(FilterRule::DontHaveCustomFields(Some(*id))));
+        }
+        push(
+            self.has_custom_fields
+                .map(|v| FilterRule::HasAnyCustomFields(Some(v))),
+        );
+        rules
+    }
+}
+
+/// Ids of `a` then the ones of `b` which are not in `a`, each only once
+fn union<T: PartialEq>(a: Vec<T>, b: Vec<T>) -> Vec<T> {
+    let mut ids = Vec::with_capacity(a.len() + b.len());
+    for id in a.into_iter().chain(b) {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    ids
+}
+
+/// `b` if it is not empty, `a` otherwise
+fn replaced<T>(a: Vec<T>, b: Vec<T>) -> Vec<T> {
+    if b.is_empty() {
+        a
+    } else {
+        b
+    }
 }
 
-//https://paperless.joel.rs/api/documents/?more_like_id=&query=&title_content=&is_in_inbox=&title__istartswith=&title__iendswith=&title__icontains=&title__iexact=&content__istartswith=&content__iendswith=&content__icontains=&content__iexact=&archive_serial_number=&archive_serial_number__gt=&archive_serial_number__gte=&archive_serial_number__lt=&archive_serial_number__lte=&archive_serial_number__isnull=&correspondent__isnull=&correspondent__id__in=&correspondent__id=5&correspondent__name__istartswith=&correspondent__name__iendswith=&correspondent__name__icontains=&correspondent__name__iexact=&is_tagged=&tags__id__in=&tags__id__all=&tags__id__none=&tags__id=&tags__name__istartswith=&tags__name__iendswith=&tags__name__icontains=&tags__name__iexact=&document_type__isnull=&document_type__id__in=&document_type__id=&document_type__name__istartswith=&document_type__name__iendswith=&document_type__name__icontains=&document_type__name__iexact=&storage_path__isnull=&storage_path__id__in=&storage_path__id=&storage_path__name__istartswith=&storage_path__name__iendswith=&storage_path__name__icontains=&storage_path__name__iexact=
-//https://paperless.joel.rs/api/documents/?title__istartswith=&title__iendswith=&title__icontains=&title__iexact=&content__istartswith=&content__iendswith=&content__icontains=&content__iexact=&archive_serial_number=&archive_serial_number__gt=&archive_serial_number__gte=&archive_serial_number__lt=&archive_serial_number__lte=&archive_serial_number__isnull=&created__year=&created__month=&created__day=&created__date__gt=&created__gt=&created__date__lt=&created__lt=&added__year=&added__month=&added__day=&added__date__gt=&added__gt=&added__date__lt=&added__lt=&modified__year=&modified__month=&modified__day=&modified__date__gt=&modified__gt=&modified__date__lt=&modified__lt=&correspondent__isnull=&correspondent__id__in=&correspondent__id=5&correspondent__name__istartswith=&correspondent__name__iendswith=&correspondent__name__icontains=&correspondent__name__iexact=&tags__id__in=&tags__id=&tags__name__istartswith=&tags__name__iendswith=&tags__name__icontains=&tags__name__iexact=&document_type__isnull=&document_type__id__in=&document_type__id=&document_type__name__istartswith=&document_type__name__iendswith=&document_type__name__icontains=&document_type__name__iexact=&storage_path__isnull=&storage_path__id__in=&storage_path__id=&storage_path__name__istartswith=&storage_path__name__iendswith=&storage_path__name__icontains=&storage_path__name__iexact=&is_tagged=&tags__id__all=&tags__id__none=&is_in_inbox=&title_content=
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(filter: &Filter) -> String {
+        let mut url = Url::parse("https://paperless.example/api/documents/").unwrap();
+        filter.insert_query(&mut url);
+        url.query().unwrap_or_default().to_string()
+    }
+
+    #[test]
+    fn lists_are_comma_separated() {
+        fn ids<T: From<u64>>() -> Vec<T> {
+            vec![1.into(), 2.into()]
+        }
+        let cases: Vec<(Filter, &str)> = vec![
+            (
+                Filter {
+                    id_in: ids(),
+                    ..Default::default()
+                },
+                "id__in=1%2C2",
+            ),
+            (
+                Filter {
+                    correspondent_id_in: Some(ids()),
+                    ..Default::default()
+                },
+                "correspondent__id__in=1%2C2",
+            ),
+            (
+                Filter {
+                    correspondent_id_none: ids(),
+                    ..Default::default()
+                },
+                "correspondent__id__none=1%2C2",
+            ),
+            (
+                Filter {
+                    tag_id_in: ids(),
+                    ..Default::default()
+                },
+                "tags__id__in=1%2C2",
+            ),
+            (
+                Filter {
+                    tag_id_all: ids(),
+                    ..Default::default()
+                },
+                "tags__id__all=1%2C2",
+            ),
+            (
+                Filter {
+                    tag_id_none: ids(),
+                    ..Default::default()
+                },
+                "tags__id__none=1%2C2",
+            ),
+            (
+                Filter {
+                    document_type_id_in: ids(),
+                    ..Default::default()
+                },
+                "document_type__id__in=1%2C2",
+            ),
+            (
+                Filter {
+                    document_type_id_none: ids(),
+                    ..Default::default()
+                },
+                "document_type__id__none=1%2C2",
+            ),
+            (
+                Filter {
+                    storage_path_id_in: ids(),
+                    ..Default::default()
+                },
+                "storage_path__id__in=1%2C2",
+            ),
+            (
+                Filter {
+                    storage_path_id_none: ids(),
+                    ..Default::default()
+                },
+                "storage_path__id__none=1%2C2",
+            ),
+            (
+                Filter {
+                    ordering: vec![Ordering::Created.descending(), Ordering::Title.ascending()],
+                    ..Default::default()
+                },
+                "ordering=-created%2Ctitle",
+            ),
+        ];
+        for (filter, expected) in cases {
+            assert_eq!(query(&filter), expected);
+        }
+    }
+
+    /// Documents recorded from a server, filtered by their tags like paperless does
+    fn documents_server() -> String {
+        use crate::test_server::{ids, page, serve};
+
+        serve(|url| {
+            let documents: Vec<serde_json::Value> =
+                serde_json::from_str(include_str!("testdata/documents.json")).unwrap();
+            let tags = |d: &serde_json::Value| -> Vec<u64> {
+                d["tags"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|t| t.as_u64().unwrap())
+                    .collect()
+            };
+            let all = ids(url, "tags__id__all");
+            let any = ids(url, "tags__id__in");
+            let none = ids(url, "tags__id__none");
+            let results = documents
+                .into_iter()
+                .filter(|d| {
+                    let tags = tags(d);
+                    all.iter().flatten().all(|t| tags.contains(t))
+                        && any.iter().all(|any| any.iter().any(|t| tags.contains(t)))
+                        && !none.iter().flatten().any(|t| tags.contains(t))
+                })
+                .collect();
+            (200, page(results))
+        })
+    }
+
+    fn listed(paperless: &crate::Paperless, filter: Filter) -> Vec<u64> {
+        paperless
+            .documents(filter)
+            .unwrap()
+            .map(|d| u64::from(d.unwrap().id))
+            .collect()
+    }
+
+    #[test]
+    fn lists_narrow_the_documents() {
+        let paperless = crate::Paperless::new(&documents_server(), "token").unwrap();
+        let tags = |ids: &[u64]| ids.iter().map(|&id| id.into()).collect::<Vec<tag::Id>>();
+
+        assert_eq!(listed(&paperless, Filter::default()), vec![1, 2, 3]);
+        let filter = Filter {
+            tag_id_all: tags(&[1, 2]),
+            ..Default::default()
+        };
+        assert_eq!(listed(&paperless, filter), vec![1]);
+        let filter = Filter {
+            tag_id_in: tags(&[1, 3]),
+            ..Default::default()
+        };
+        assert_eq!(listed(&paperless, filter), vec![1, 2, 3]);
+        let filter = Filter {
+            tag_id_none: tags(&[2, 4]),
+            ..Default::default()
+        };
+        assert_eq!(listed(&paperless, filter), vec![2]);
+    }
+
+    #[test]
+    fn repeated_keys_dont_narrow_the_documents() {
+        let root = documents_server();
+        let url = format!("{root}documents/?tags__id__all=1&tags__id__all=2");
+        let body = reqwest::blocking::get(url).unwrap().text().unwrap();
+        let page: serde_json::Value = serde_json::from_str(&body).unwrap();
+        // Only the last value is used: the documents with the tag 2, not with both tags
+        assert_eq!(page["count"], 2);
+    }
+
+    #[test]
+    fn empty_lists_are_not_sent() {
+        let filter = Filter {
+            correspondent_id_in: Some(vec![]),
+            ..Default::default()
+        };
+        assert_eq!(query(&filter), "");
+    }
+
+    #[test]
+    fn merge_lists() {
+        let a = Filter {
+            tag_id_all: vec![1.into(), 2.into()],
+            tag_id_in: vec![5.into(), 6.into()],
+            correspondent_id_in: Some(vec![1.into()]),
+            ..Default::default()
+        };
+        let b = Filter {
+            tag_id_all: vec![2.into(), 3.into()],
+            tag_id_in: vec![5.into()],
+            correspondent_id_in: Some(vec![2.into(), 1.into()]),
+            document_type_id_in: vec![4.into()],
+            ..Default::default()
+        };
+        let merged = a.clone().merge(b);
+        assert_eq!(merged.tag_id_all, vec![1.into(), 2.into(), 3.into()]);
+        assert_eq!(merged.tag_id_in, vec![5.into()]);
+        assert_eq!(merged.correspondent_id_in, Some(vec![2.into(), 1.into()]));
+        assert_eq!(merged.document_type_id_in, vec![4.into()]);
+        let merged = a.merge(Filter::default());
+        assert_eq!(merged.tag_id_in, vec![5.into(), 6.into()]);
+        assert_eq!(merged.correspondent_id_in, Some(vec![1.into()]));
+    }
+
+    #[test]
+    fn filter_rules_round_trip() {
+        use saved_view::FilterRule;
+
+        let day = || DateBound::parse("2024-01-31");
+        let time = || DateBound::parse("2024-01-31T10:00:00Z");
+        let rules = vec![
+            FilterRule::TitleContains(Some("invoice".to_string())),
+            FilterRule::ContentContains(Some("total".to_string())),
+            FilterRule::ASNIs(Some(12.into())),
+            FilterRule::CorrespondentIs(Some(1.into())),
+            FilterRule::DocumentTypeIs(Some(2.into())),
+            FilterRule::IsInInbox(Some(true)),
+            FilterRule::HasTag(Some(3.into())),
+            FilterRule::HasAnyTag(Some(false)),
+            FilterRule::CreatedBefore(day()),
+            FilterRule::CreatedAfter(time()),
+            FilterRule::CreatedYearIs(Some(2024)),
+            FilterRule::CreatedMountIs(Some(1)),
+            FilterRule::CreatedDayIs(Some(31)),
+            FilterRule::AddedBefore(time()),
+            FilterRule::AddedAfter(day()),
+            FilterRule::ModifiedBefore(day()),
+            FilterRule::ModifiedAfter(time()),
+            FilterRule::DontHaveTag(Some(4.into())),
+            FilterRule::DontHaveASN(Some(true)),
+            FilterRule::TitleOrContentContains(Some("bank".to_string())),
+            FilterRule::FullTextQuery(Some("bank AND 2024".to_string())),
+            FilterRule::MoreLikeThis(Some(5.into())),
+            FilterRule::HasTagIn(Some(6.into())),
+            FilterRule::ASNGreaterThan(Some(7.into())),
+            FilterRule::ASNLessThan(Some(8.into())),
+            FilterRule::StoragePathIs(Some(9.into())),
+            FilterRule::HasCorrespondentIn(Some(10.into())),
+            FilterRule::DontHaveCorrespondent(Some(11.into())),
+            FilterRule::HasDocumentTypeIn(Some(12.into())),
+            FilterRule::DontHaveDocumentType(Some(13.into())),
+            FilterRule::HasStoragePathIn(Some(14.into())),
+            FilterRule::DontHaveStoragePath(Some(15.into())),
+            FilterRule::OwnerIs(Some(16.into())),
+            FilterRule::OwnerIn(Some(17.into())),
+            FilterRule::OwnerIsNull(Some(false)),
+            FilterRule::DontHaveOwner(Some(18.into())),
+            FilterRule::CustomFieldsContains(Some("paid".to_string())),
+            FilterRule::SharedBy(Some(19.into())),
+            FilterRule::HasCustomFieldsAll(Some(20.into())),
+            FilterRule::HasCustomFieldsIn(Some(21.into())),
+            FilterRule::DontHaveCustomFields(Some(22.into())),
+            FilterRule::HasAnyCustomFields(Some(true)),
+        ];
+        let rule_types: Vec<u64> = rules.iter().map(FilterRule::rule_type).collect();
+        assert_eq!(rule_types, (0..42).collect::<Vec<u64>>());
+        assert!(FilterRule::unsupported(&rules).is_empty());
+
+        for rule in rules {
+            let filter = Filter::from_filter_rules(std::slice::from_ref(&rule));
+            assert_ne!(filter, Filter::default(), "{rule:?} was ignored");
+            assert_eq!(
+                Filter::from_filter_rules(&filter.to_filter_rules()),
+                filter,
+                "{rule:?} didn't round trip"
+            );
+        }
+    }
+}
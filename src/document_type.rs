@@ -2,10 +2,11 @@
 //!
 //! A document type is a category of document, like invoice, receipt, bank statement, ...
 
+use crate::{matching, permissions, query, user};
 use reqwest::Url;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Copy, Clone)]
+#[derive(Debug, Deserialize, Copy, Clone, Serialize, PartialEq, Eq, Hash)]
 pub struct Id(u64);
 
 impl From<u64> for Id {
@@ -24,19 +25,65 @@ impl ToString for Id {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct DocumentType {
     pub id: Id,
     pub slug: String,
     pub name: String,
     #[serde(rename = "match")]
     pub match_: String,
-    pub matching_algorithm: u64,
+    pub matching_algorithm: matching::Algorithm,
     pub is_insensitive: bool,
+    /// Not sent by the server when the document type has just been created
+    #[serde(default)]
     pub document_count: u64,
+    /// Owner of the object, `None` if everyone can see it
+    #[serde(default)]
+    pub owner: Option<user::Id>,
+    /// Only sent when the client asks for the full permissions
+    #[serde(default)]
+    pub permissions: Option<permissions::Permissions>,
+    /// Whether the current user can modify the object, not sent by old versions of paperless
+    #[serde(default, alias = "userCanChange")]
+    pub user_can_change: Option<bool>,
 }
 
-#[derive(Debug, Default)]
+/// A new document type
+#[derive(Debug, Clone, Serialize)]
+pub struct Create {
+    pub name: String,
+    #[serde(rename = "match")]
+    pub match_: String,
+    pub matching_algorithm: matching::Algorithm,
+    pub is_insensitive: bool,
+}
+
+impl Create {
+    /// A document type automatically assigned by the server, like when created from the interface
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            match_: String::new(),
+            matching_algorithm: matching::Algorithm::Auto,
+            is_insensitive: true,
+        }
+    }
+}
+
+/// Modification of a document type, only the fields set to `Some` are modified
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct Update {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(rename = "match", skip_serializing_if = "Option::is_none")]
+    pub match_: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matching_algorithm: Option<matching::Algorithm>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_insensitive: Option<bool>,
+}
+
+#[derive(Debug, Default, Clone)]
 pub struct Filter {
     name_starts_with: Option<String>,
     name_ends_with: Option<String>,
@@ -45,14 +92,20 @@ pub struct Filter {
 }
 
 impl Filter {
-    pub fn insert_query(self, url: &mut Url) {
-        url.query_pairs_mut()
-            .append_pair(
-                "name__istartswith",
-                &self.name_starts_with.unwrap_or_default(),
-            )
-            .append_pair("name__iendswith", &self.name_ends_with.unwrap_or_default())
-            .append_pair("name__icontains", &self.name_contains.unwrap_or_default())
-            .append_pair("name__iexact", &self.name_is.unwrap_or_default());
+    /// Merge two filters into a new one, every field set in `other` takes precedence over `self`
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            name_starts_with: other.name_starts_with.or(self.name_starts_with),
+            name_ends_with: other.name_ends_with.or(self.name_ends_with),
+            name_contains: other.name_contains.or(self.name_contains),
+            name_is: other.name_is.or(self.name_is),
+        }
+    }
+
+    pub fn insert_query(&self, url: &mut Url) {
+        query::append_some(url, "name__istartswith", self.name_starts_with.as_deref());
+        query::append_some(url, "name__iendswith", self.name_ends_with.as_deref());
+        query::append_some(url, "name__icontains", self.name_contains.as_deref());
+        query::append_some(url, "name__iexact", self.name_is.as_deref());
     }
 }
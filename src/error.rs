@@ -0,0 +1,371 @@
+//! # Error
+//!
+//! Every fallible function of this crate returns this error type.
+
+use crate::rollback::Rollback;
+use reqwest::header::InvalidHeaderValue;
+use reqwest::StatusCode;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The request could not be sent, or the response could not be read
+    Http(reqwest::Error),
+    /// The server answered with an unexpected status code
+    Status(StatusCode),
+    /// The server refused the credentials
+    Unauthorized,
+    /// The credentials are valid, but not allowed to do this operation
+    Forbidden,
+    /// The requested object doesn't exist, the server answered `404 Not Found`
+    NotFound,
+    /// An object with the same name already exists
+    Conflict(String),
+    /// The response doesn't have the expected format
+    Deserialize(serde_json::Error),
+    /// An url is invalid
+    Url(url::ParseError),
+    /// The credentials contain characters which are not allowed in a header
+    InvalidToken(InvalidHeaderValue),
+    /// A header needed to understand the response is missing or invalid
+    MissingHeader(&'static str),
+    /// A local file could not be read or written
+    Io(std::io::Error),
+    /// The operation did not finish in time
+    Timeout,
+    /// A background task of the server failed, with its error message
+    TaskFailed(String),
+    /// An argument is invalid, nothing was sent to the server
+    InvalidArgument(String),
+    /// The server rejected the values sent, with its messages by field
+    ///
+    /// Messages which are not about a field are under `non_field_errors`, and the fields of
+    /// nested objects are joined with dots, like `custom_fields.0.value`.
+    Validation(HashMap<String, Vec<String>>),
+    /// An operation made of several steps failed after modifying the server
+    Interrupted(Box<Error>, Rollback),
+    /// The websocket of the server failed
+    #[cfg(feature = "events")]
+    WebSocket(Box<tungstenite::Error>),
+}
+
+impl Error {
+    /// Turn a non-successful status code into an error
+    pub(crate) fn check_status(status: StatusCode) -> Result<(), Error> {
+        match status {
+            s if s.is_success() => Ok(()),
+            StatusCode::UNAUTHORIZED => Err(Error::Unauthorized),
+            StatusCode::FORBIDDEN => Err(Error::Forbidden),
+            StatusCode::NOT_FOUND => Err(Error::NotFound),
+            s => Err(Error::Status(s)),
+        }
+    }
+
+    /// Error of a `400 Bad Request` response, from the messages in its body
+    ///
+    /// Bodies which are not validation errors, for example sent by a proxy, give
+    /// [`Error::Status`].
+    pub(crate) fn bad_request(body: &[u8]) -> Error {
+        let mut messages = HashMap::new();
+        match serde_json::from_slice(body) {
+            Ok(value @ Value::Object(_)) => collect_messages("", &value, &mut messages),
+            Ok(value) => collect_messages("non_field_errors", &value, &mut messages),
+            Err(_) => {}
+        }
+        if messages.is_empty() {
+            Error::Status(StatusCode::BAD_REQUEST)
+        } else {
+            Error::Validation(messages)
+        }
+    }
+
+    /// Error code of the kernel matching this error, for file systems built on this crate
+    ///
+    /// Temporary failures, after which retrying may succeed, are reported as `EAGAIN`.
+    #[cfg(unix)]
+    pub fn as_errno(&self) -> i32 {
+        match self {
+            Error::NotFound => libc::ENOENT,
+            Error::Unauthorized => libc::EACCES,
+            Error::Forbidden => libc::EPERM,
+            Error::Conflict(_) => libc::EEXIST,
+            Error::Timeout => libc::EAGAIN,
+            Error::Http(e) if e.is_timeout() || e.is_connect() => libc::EAGAIN,
+            Error::Status(s) if *s == StatusCode::TOO_MANY_REQUESTS || s.is_server_error() => {
+                libc::EAGAIN
+            }
+            Error::Status(s) if s.is_client_error() => libc::EINVAL,
+            Error::Url(_)
+            | Error::InvalidToken(_)
+            | Error::InvalidArgument(_)
+            | Error::Validation(_) => libc::EINVAL,
+            Error::Io(e) => e.raw_os_error().unwrap_or(libc::EIO),
+            Error::Interrupted(e, _) => e.as_errno(),
+            #[cfg(feature = "events")]
+            Error::WebSocket(_) => libc::EIO,
+            Error::Http(_)
+            | Error::Status(_)
+            | Error::Deserialize(_)
+            | Error::MissingHeader(_)
+            | Error::TaskFailed(_) => libc::EIO,
+        }
+    }
+
+    /// Attach the steps already done to an error, if there are any
+    pub(crate) fn interrupted(self, rollback: Rollback) -> Error {
+        if rollback.steps.is_empty() {
+            self
+        } else {
+            Error::Interrupted(Box::new(self), rollback)
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Http(e) => write!(f, "http error: {e}"),
+            Error::Status(s) => write!(f, "unexpected status code: {s}"),
+            Error::Unauthorized => write!(f, "the server refused the credentials"),
+            Error::Forbidden => write!(f, "operation not allowed"),
+            Error::NotFound => write!(f, "not found"),
+            Error::Conflict(name) => write!(f, "{name} already exists"),
+            Error::Deserialize(e) => write!(f, "invalid response: {e}"),
+            Error::Url(e) => write!(f, "invalid url: {e}"),
+            Error::InvalidToken(e) => write!(f, "invalid token: {e}"),
+            Error::MissingHeader(h) => write!(f, "missing or invalid header: {h}"),
+            Error::Io(e) => write!(f, "io error: {e}"),
+            Error::Timeout => write!(f, "the operation timed out"),
+            Error::TaskFailed(message) => write!(f, "task failed: {message}"),
+            Error::InvalidArgument(message) => write!(f, "invalid argument: {message}"),
+            Error::Validation(messages) => {
+                let mut fields: Vec<_> = messages.iter().collect();
+                fields.sort();
+                write!(f, "rejected by the server:")?;
+                for (field, messages) in fields {
+                    write!(f, " {field}: {}.", messages.join(", "))?;
+                }
+                Ok(())
+            }
+            Error::Interrupted(e, rollback) => {
+                write!(f, "interrupted after {} steps: {e}", rollback.steps.len())
+            }
+            #[cfg(feature = "events")]
+            Error::WebSocket(e) => write!(f, "websocket error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Http(e) => Some(e),
+            Error::Deserialize(e) => Some(e),
+            Error::Url(e) => Some(e),
+            Error::InvalidToken(e) => Some(e),
+            Error::Io(e) => Some(e),
+            Error::Interrupted(e, _) => Some(e),
+            #[cfg(feature = "events")]
+            Error::WebSocket(e) => Some(e),
+            Error::Status(_)
+            | Error::Unauthorized
+            | Error::Forbidden
+            | Error::NotFound
+            | Error::Conflict(_)
+            | Error::MissingHeader(_)
+            | Error::Timeout
+            | Error::TaskFailed(_)
+            | Error::InvalidArgument(_)
+            | Error::Validation(_) => None,
+        }
+    }
+}
+
+/// Gather the messages of a validation error, which are strings in lists or nested objects
+fn collect_messages(field: &str, value: &Value, messages: &mut HashMap<String, Vec<String>>) {
+    match value {
+        Value::Object(fields) => {
+            for (name, value) in fields {
+                let name = if field.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{field}.{name}")
+                };
+                collect_messages(&name, value, messages);
+            }
+        }
+        Value::Array(values) => {
+            for (i, value) in values.iter().enumerate() {
+                // Lists of objects have the messages of each element, like the custom fields
+                if value.is_object() {
+                    collect_messages(&format!("{field}.{i}"), value, messages);
+                } else {
+                    collect_messages(field, value, messages);
+                }
+            }
+        }
+        Value::String(message) => messages
+            .entry(field.to_string())
+            .or_default()
+            .push(message.clone()),
+        Value::Null => {}
+        other => messages
+            .entry(field.to_string())
+            .or_default()
+            .push(other.to_string()),
+    }
+}
+
+/// Turn [`Error::NotFound`] into `None`, for the methods getting a single object
+///
+/// # Examples
+///
+/// ```rust
+/// use paperless::{document, Error, OptionalExt, Paperless};
+///
+/// fn title(paperless: &Paperless, id: document::Id) -> Result<Option<String>, Error> {
+///     Ok(paperless.document(id).optional()?.map(|d| d.title))
+/// }
+/// ```
+pub trait OptionalExt<T> {
+    fn optional(self) -> Result<Option<T>, Error>;
+}
+
+impl<T> OptionalExt<T> for Result<T, Error> {
+    fn optional(self) -> Result<Option<T>, Error> {
+        match self {
+            Ok(value) => Ok(Some(value)),
+            Err(Error::NotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(value: reqwest::Error) -> Self {
+        Self::Http(value)
+    }
+}
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Deserialize(value)
+    }
+}
+impl From<url::ParseError> for Error {
+    fn from(value: url::ParseError) -> Self {
+        Self::Url(value)
+    }
+}
+impl From<InvalidHeaderValue> for Error {
+    fn from(value: InvalidHeaderValue) -> Self {
+        Self::InvalidToken(value)
+    }
+}
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+#[cfg(feature = "events")]
+impl From<tungstenite::Error> for Error {
+    fn from(value: tungstenite::Error) -> Self {
+        match value {
+            tungstenite::Error::Io(e) => Self::Io(e),
+            // The server refused to open the websocket
+            tungstenite::Error::Http(response) => {
+                match StatusCode::from_u16(response.status().as_u16()) {
+                    Ok(status) => Self::check_status(status)
+                        .err()
+                        .unwrap_or(Self::Status(status)),
+                    Err(_) => Self::WebSocket(Box::new(tungstenite::Error::Http(response))),
+                }
+            }
+            e => Self::WebSocket(Box::new(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(body: &str) -> HashMap<String, Vec<String>> {
+        match Error::bad_request(body.as_bytes()) {
+            Error::Validation(messages) => messages,
+            e => panic!("not a validation error: {e:?}"),
+        }
+    }
+
+    fn field(message: &str) -> Vec<String> {
+        vec![message.to_string()]
+    }
+
+    #[test]
+    fn flat_fields() {
+        let messages = messages(
+            r#"{
+                "title": ["This field may not be blank."],
+                "tags": ["Invalid pk \"9\" - object does not exist."]
+            }"#,
+        );
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages["title"], field("This field may not be blank."));
+        assert_eq!(
+            messages["tags"],
+            field("Invalid pk \"9\" - object does not exist.")
+        );
+    }
+
+    #[test]
+    fn nested_fields() {
+        let messages = messages(
+            r#"{
+                "custom_fields": [{"value": ["Enter a valid date."]}, {}],
+                "owner": {"id": ["Required."]}
+            }"#,
+        );
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages["custom_fields.0.value"],
+            field("Enter a valid date.")
+        );
+        assert_eq!(messages["owner.id"], field("Required."));
+    }
+
+    #[test]
+    fn list_of_objects() {
+        let messages = messages(r#"[{},{"name":["This field is required."]}]"#);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(
+            messages["non_field_errors.1.name"],
+            field("This field is required.")
+        );
+    }
+
+    #[test]
+    fn non_field_errors() {
+        let body = r#"{"non_field_errors":["The fields name, owner must make a unique set."]}"#;
+        assert_eq!(
+            messages(body)["non_field_errors"],
+            field("The fields name, owner must make a unique set.")
+        );
+        assert_eq!(
+            messages(r#"["Unknown operation."]"#)["non_field_errors"],
+            field("Unknown operation.")
+        );
+    }
+
+    #[test]
+    fn not_json() {
+        let body = "<html><head><title>400 Bad Request</title></head>\
+                    <body><h1>400 Bad Request</h1></body></html>";
+        for body in [body, "", "{}"] {
+            assert!(matches!(
+                Error::bad_request(body.as_bytes()),
+                Error::Status(StatusCode::BAD_REQUEST)
+            ));
+        }
+    }
+}
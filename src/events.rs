@@ -0,0 +1,175 @@
+//! # Events
+//!
+//! The server pushes the status of the documents being consumed over a websocket, which is what
+//! the web interface uses to show the progress of the uploads, see [`connect`].
+
+use crate::{document, routes, Error, Paperless};
+use serde::Deserialize;
+use std::net::TcpStream;
+use tungstenite::client::IntoClientRequest;
+use tungstenite::http::{HeaderName, HeaderValue};
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+/// Progress of the consumption of a file
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct Status {
+    /// Id of the task consuming the file, as returned by
+    /// [`Paperless::upload_document`](crate::Paperless::upload_document)
+    #[serde(default)]
+    pub task_id: Option<String>,
+    /// Name of the consumed file
+    #[serde(default)]
+    pub filename: Option<String>,
+    #[serde(default)]
+    pub current_progress: u64,
+    #[serde(default)]
+    pub max_progress: u64,
+    /// Step of the consumption, or error message for failures
+    #[serde(default)]
+    pub message: Option<String>,
+    /// Document created from the file, only known once the consumption succeeded
+    #[serde(default)]
+    pub document_id: Option<document::Id>,
+}
+
+/// An update of the consumer
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "UPPERCASE")]
+pub enum ConsumerEvent {
+    Started(Status),
+    #[serde(rename = "WORKING")]
+    Progress(Status),
+    Success(Status),
+    #[serde(rename = "FAILED")]
+    Failure(Status),
+    /// Status added by a newer version of paperless
+    #[serde(other)]
+    Unknown,
+}
+
+impl ConsumerEvent {
+    pub fn status(&self) -> Option<&Status> {
+        match self {
+            ConsumerEvent::Started(status)
+            | ConsumerEvent::Progress(status)
+            | ConsumerEvent::Success(status)
+            | ConsumerEvent::Failure(status) => Some(status),
+            ConsumerEvent::Unknown => None,
+        }
+    }
+}
+
+/// Events received from the server, until the websocket is closed
+///
+/// Reading blocks until the next event is pushed by the server.
+pub struct Events {
+    socket: WebSocket<MaybeTlsStream<TcpStream>>,
+    /// A message was received, the server accepted the credentials
+    received: bool,
+}
+
+/// Open the websocket of the server, authenticated like the other requests of the client
+///
+/// The websocket is not part of the api, the server only accepts the session of the web
+/// interface: the client must use [`Auth::SessionCookie`](crate::auth::Auth::SessionCookie).
+/// With other credentials, the server closes the websocket before sending anything, which is
+/// reported as [`Error::Unauthorized`] by the first read of [`Events`].
+///
+/// The proxy, timeouts and certificates set on [`PaperlessBuilder`](crate::PaperlessBuilder) are
+/// not used for the websocket.
+///
+/// # Arguments
+///
+/// * `paperless` - Client whose server and credentials are used
+pub fn connect(paperless: &Paperless) -> Result<Events, Error> {
+    let mut url = paperless.server.url_ui(routes::WS_STATUS)?;
+    let scheme = if url.scheme() == "https" { "wss" } else { "ws" };
+    url.set_scheme(scheme)
+        .map_err(|()| Error::InvalidArgument(format!("{url} can't be a websocket url")))?;
+
+    let mut request = url.as_str().into_client_request()?;
+    let headers = request.headers_mut();
+    for (name, value) in paperless.server.headers().iter() {
+        let name = HeaderName::from_bytes(name.as_str().as_bytes())
+            .map_err(|e| Error::InvalidArgument(e.to_string()))?;
+        let mut value = HeaderValue::from_bytes(value.as_bytes())
+            .map_err(|e| Error::InvalidArgument(e.to_string()))?;
+        value.set_sensitive(true);
+        headers.append(name, value);
+    }
+    // The server only accepts websockets opened from its own pages
+    let origin = paperless.server.url_ui("")?.origin().ascii_serialization();
+    headers.insert(
+        "Origin",
+        HeaderValue::from_str(&origin).map_err(|e| Error::InvalidArgument(e.to_string()))?,
+    );
+
+    let (socket, _) = tungstenite::connect(request)?;
+    Ok(Events {
+        socket,
+        received: false,
+    })
+}
+
+impl Events {
+    /// Close the websocket, the next events are not received anymore
+    pub fn close(mut self) -> Result<(), Error> {
+        self.socket.close(None)?;
+        // Wait for the server to acknowledge the closing
+        loop {
+            match self.socket.read() {
+                Ok(_) => continue,
+                Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => {
+                    return Ok(())
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+impl Iterator for Events {
+    type Item = Result<ConsumerEvent, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let text = match self.socket.read() {
+                Ok(Message::Text(text)) => text,
+                // The session is checked once the websocket is open, it's closed if it's not valid
+                Ok(Message::Close(_)) | Err(tungstenite::Error::ConnectionClosed)
+                    if !self.received =>
+                {
+                    self.received = true;
+                    return Some(Err(Error::Unauthorized));
+                }
+                Ok(Message::Close(_))
+                | Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => {
+                    return None
+                }
+                // Pings are answered by the next read
+                Ok(_) => continue,
+                Err(e) => return Some(Err(e.into())),
+            };
+            self.received = true;
+            match decode(&text) {
+                Ok(Some(event)) => return Some(Ok(event)),
+                Ok(None) => continue,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Decode a message of the server, `None` for the messages which are not about the consumer
+fn decode(text: &str) -> Result<Option<ConsumerEvent>, Error> {
+    let message: serde_json::Value = serde_json::from_str(text)?;
+    // Since paperless-ngx 2.0, the status is wrapped with the type of the message, next to other
+    // messages such as deleted documents
+    let status = match message.get("type").and_then(|t| t.as_str()) {
+        Some("status_update") => message.get("data").cloned().unwrap_or_default(),
+        Some(_) => return Ok(None),
+        None => message,
+    };
+    Ok(Some(serde_json::from_value(status)?))
+}
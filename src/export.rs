@@ -0,0 +1,215 @@
+//! # Export
+//!
+//! Gather all the documents of a year in a directory, for example to hand them to an accountant.
+//!
+//! The documents are sorted in `<correspondent>/<document type>/` directories, with an
+//! `index.csv` describing them and a `SHA256SUMS` manifest to check that nothing was altered.
+
+use crate::{date, document, Error, Paperless};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Name of the directory of documents without correspondent or without document type
+const NONE: &str = "None";
+
+/// How to build a package
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// Directory in which the documents are written, created if needed
+    pub directory: PathBuf,
+    /// Version of the files to export
+    pub version: document::FileVersion,
+    /// Also compress the directory into `<directory>.zip`
+    pub zip: bool,
+}
+
+impl Options {
+    /// Export the archived version of the documents, without compressing them
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            version: document::FileVersion::Archive,
+            zip: false,
+        }
+    }
+}
+
+/// Result of an export
+#[derive(Debug, Clone)]
+pub struct Package {
+    pub directory: PathBuf,
+    /// Paths of the exported files, relative to `directory`
+    pub files: Vec<PathBuf>,
+    /// Path of the zip archive, if it was requested
+    pub zip: Option<PathBuf>,
+}
+
+/// Export all the documents created during a year
+///
+/// # Arguments
+///
+/// * `paperless` - Client used to query the server
+/// * `year` - Year in which the documents were created
+/// * `options` - Where and how to write the package
+pub fn year_package(
+    paperless: &Paperless,
+    year: usize,
+    options: &Options,
+) -> Result<Package, Error> {
+    fs::create_dir_all(&options.directory)?;
+    let documents = paperless.documents_detailed(document::Filter {
+        created_year: Some(year),
+        ..Default::default()
+    })?;
+
+    let mut index = BufWriter::new(File::create(options.directory.join("index.csv"))?);
+    writeln!(
+        index,
+        "id,title,created,correspondent,document_type,tags,archive_serial_number,file"
+    )?;
+    let mut manifest = BufWriter::new(File::create(options.directory.join("SHA256SUMS"))?);
+    let mut files = Vec::new();
+
+    for document in documents {
+        let document = document?;
+        let path = file_path(&document, options.version);
+        let full_path = options.directory.join(&path);
+        if let Some(parent) = full_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut content =
+            paperless.document_download_stream(document.document.id, options.version)?;
+        let hash = copy_hashed(&mut content, &mut File::create(&full_path)?)?;
+        writeln!(manifest, "{hash}  {}", to_slash(&path))?;
+
+        let d = &document.document;
+        let fields = [
+            d.id.to_string(),
+            d.title.clone(),
+            date::format_date(&d.created_date),
+            document.correspondent.clone().unwrap_or_default(),
+            document.document_type.clone().unwrap_or_default(),
+            document.tags.join(";"),
+            d.archive_serial_number
+                .map(|asn| asn.to_string())
+                .unwrap_or_default(),
+            to_slash(&path),
+        ];
+        let fields: Vec<_> = fields.iter().map(|f| csv_field(f)).collect();
+        writeln!(index, "{}", fields.join(","))?;
+        files.push(path);
+    }
+    index.flush()?;
+    manifest.flush()?;
+
+    let zip = if options.zip {
+        let mut zip_path = options.directory.clone().into_os_string();
+        zip_path.push(".zip");
+        let zip_path = PathBuf::from(zip_path);
+        write_zip(&options.directory, &files, &zip_path)?;
+        Some(zip_path)
+    } else {
+        None
+    };
+
+    Ok(Package {
+        directory: options.directory.clone(),
+        files,
+        zip,
+    })
+}
+
+/// Path of a document in the package, the id avoids collisions between identical titles
+fn file_path(document: &document::DocumentDetailed, version: document::FileVersion) -> PathBuf {
+    let d = &document.document;
+    let file_name = match version {
+        document::FileVersion::Archive => d.archived_file_name.as_ref(),
+        document::FileVersion::Original => None,
+    }
+    .or(d.original_file_name.as_ref());
+    let extension = file_name
+        .and_then(|f| Path::new(f).extension())
+        .and_then(|e| e.to_str())
+        .unwrap_or("pdf");
+
+    [
+        sanitize(document.correspondent.as_deref().unwrap_or(NONE)),
+        sanitize(document.document_type.as_deref().unwrap_or(NONE)),
+        format!(
+            "{} {} [{}].{extension}",
+            date::format_date(&d.created_date),
+            sanitize(&d.title),
+            d.id.to_string()
+        ),
+    ]
+    .iter()
+    .collect()
+}
+
+/// Replace the characters which are not allowed in file names on common file systems
+fn sanitize(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    match name.trim() {
+        "" | "." | ".." => "_".to_string(),
+        name => name.to_string(),
+    }
+}
+
+/// Copy everything and return the SHA-256 of what was copied, in hexadecimal
+fn copy_hashed(reader: &mut impl Read, writer: &mut impl Write) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        writer.write_all(&buffer[..read])?;
+    }
+    writer.flush()?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect())
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Path with `/` separators, as expected in csv files, manifests and zip archives
+fn to_slash(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn write_zip(directory: &Path, files: &[PathBuf], zip_path: &Path) -> Result<(), Error> {
+    let to_io = |e: zip::result::ZipError| io::Error::other(e);
+    let mut zip = zip::ZipWriter::new(File::create(zip_path)?);
+    let options = zip::write::FileOptions::default();
+    let metadata = [PathBuf::from("index.csv"), PathBuf::from("SHA256SUMS")];
+    for path in metadata.iter().chain(files) {
+        zip.start_file(to_slash(path), options).map_err(to_io)?;
+        io::copy(&mut File::open(directory.join(path))?, &mut zip)?;
+    }
+    zip.finish().map_err(to_io)?;
+    Ok(())
+}
@@ -0,0 +1,195 @@
+//! # Exporter
+//!
+//! Collect the state of the archive in a [`Snapshot`], to feed a monitoring system. Snapshots
+//! can be rendered in the Prometheus text format with [`Snapshot::to_prometheus`].
+
+use crate::paperless::join;
+use crate::{priority, statistics, tag, task, Error, Paperless};
+use std::fmt::Write;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// Number of documents with a tag
+#[derive(Debug, Clone)]
+pub struct TagCount {
+    pub id: tag::Id,
+    pub name: String,
+    pub document_count: u64,
+}
+
+/// Number of tasks known by the server, by status
+#[derive(Debug, Clone, Default)]
+pub struct TaskCounts {
+    pub pending: u64,
+    pub started: u64,
+    pub success: u64,
+    pub failure: u64,
+    pub retry: u64,
+    pub revoked: u64,
+    /// Failed tasks which have not been acknowledged yet
+    pub unacknowledged_failures: u64,
+}
+
+impl TaskCounts {
+    fn from_tasks(tasks: &[task::Task]) -> Self {
+        let mut counts = Self::default();
+        for task in tasks {
+            match task.status {
+                task::Status::Pending => counts.pending += 1,
+                task::Status::Started => counts.started += 1,
+                task::Status::Success => counts.success += 1,
+                task::Status::Failure => counts.failure += 1,
+                task::Status::Retry => counts.retry += 1,
+                task::Status::Revoked => counts.revoked += 1,
+            }
+            if task.status == task::Status::Failure && !task.acknowledged {
+                counts.unacknowledged_failures += 1;
+            }
+        }
+        counts
+    }
+}
+
+/// State of the archive at a given time
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub collected_at: SystemTime,
+    pub statistics: statistics::Statistics,
+    pub tasks: TaskCounts,
+    pub tags: Vec<TagCount>,
+}
+
+impl Snapshot {
+    /// Collect a new snapshot, statistics, tasks and tags are requested concurrently
+    ///
+    /// The requests keep the priority of the calling thread, see
+    /// [`Paperless::with_priority`](crate::Paperless::with_priority).
+    ///
+    /// # Arguments
+    ///
+    /// * `paperless` - Client used to query the server
+    pub fn collect(paperless: &Paperless) -> Result<Self, Error> {
+        let priority = priority::current();
+        let (statistics, tasks, tags) = thread::scope(|s| {
+            let statistics = s.spawn(|| priority::scoped(priority, || paperless.statistics()));
+            let tasks =
+                s.spawn(|| priority::scoped(priority, || paperless.tasks(Default::default())));
+            let tags = s.spawn(|| {
+                priority::scoped(priority, || {
+                    paperless
+                        .tags(Default::default())?
+                        .map(|t| {
+                            t.map(|t| TagCount {
+                                id: t.id,
+                                name: t.name,
+                                document_count: t.document_count,
+                            })
+                        })
+                        .collect::<Result<Vec<_>, Error>>()
+                })
+            });
+            (join(statistics), join(tasks), join(tags))
+        });
+        Ok(Self {
+            collected_at: SystemTime::now(),
+            statistics: statistics?,
+            tasks: TaskCounts::from_tasks(&tasks?),
+            tags: tags?,
+        })
+    }
+
+    /// Render the snapshot in the Prometheus text exposition format
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        let mut gauge = |name: &str, help: &str, values: &[(String, u64)]| {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} gauge");
+            for (labels, value) in values {
+                let _ = writeln!(out, "{name}{labels} {value}");
+            }
+        };
+
+        gauge(
+            "paperless_documents_total",
+            "Number of documents",
+            &[(String::new(), self.statistics.documents_total)],
+        );
+        if let Some(inbox) = self.statistics.documents_inbox {
+            gauge(
+                "paperless_documents_inbox",
+                "Number of documents in the inbox",
+                &[(String::new(), inbox)],
+            );
+        }
+        gauge(
+            "paperless_characters_total",
+            "Number of characters in the content of all documents",
+            &[(String::new(), self.statistics.character_count)],
+        );
+        let tasks = &self.tasks;
+        gauge(
+            "paperless_tasks",
+            "Number of tasks by status",
+            &[
+                ("pending", tasks.pending),
+                ("started", tasks.started),
+                ("success", tasks.success),
+                ("failure", tasks.failure),
+                ("retry", tasks.retry),
+                ("revoked", tasks.revoked),
+            ]
+            .map(|(status, count)| (format!("{{status=\"{status}\"}}"), count)),
+        );
+        gauge(
+            "paperless_tasks_unacknowledged_failures",
+            "Number of failed tasks not acknowledged yet",
+            &[(String::new(), tasks.unacknowledged_failures)],
+        );
+        let tags: Vec<_> = self
+            .tags
+            .iter()
+            .map(|t| {
+                (
+                    format!(
+                        "{{tag=\"{}\",tag_id=\"{}\"}}",
+                        escape_label(&t.name),
+                        t.id.to_string()
+                    ),
+                    t.document_count,
+                )
+            })
+            .collect();
+        gauge(
+            "paperless_tag_documents",
+            "Number of documents with a tag",
+            &tags,
+        );
+        out
+    }
+}
+
+/// Collect a snapshot every `interval` and hand it to `on_snapshot`, until it returns `false`
+///
+/// Errors are handed to `on_snapshot` too, so that a temporary network failure doesn't stop the
+/// collection.
+///
+/// # Arguments
+///
+/// * `paperless` - Client used to query the server
+/// * `interval` - Time to wait between two collections
+/// * `on_snapshot` - Called with every collected snapshot
+pub fn watch<F>(paperless: &Paperless, interval: Duration, mut on_snapshot: F)
+where
+    F: FnMut(Result<Snapshot, Error>) -> bool,
+{
+    while on_snapshot(Snapshot::collect(paperless)) {
+        thread::sleep(interval);
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
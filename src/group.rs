@@ -0,0 +1,63 @@
+//! # Group
+//!
+//! Groups of users, sharing the same permissions.
+
+use crate::query;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Deserialize, Copy, Clone, Serialize, PartialEq, Eq, Hash)]
+pub struct Id(u64);
+
+impl From<u64> for Id {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+impl From<Id> for u64 {
+    fn from(value: Id) -> Self {
+        value.0
+    }
+}
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Group {
+    pub id: Id,
+    pub name: String,
+    /// Permissions given to the users of the group, like `view_document`
+    #[serde(default)]
+    pub permissions: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Filter {
+    pub name_starts_with: Option<String>,
+    pub name_ends_with: Option<String>,
+    pub name_contains: Option<String>,
+    pub name_is: Option<String>,
+}
+
+impl Filter {
+    /// Merge two filters into a new one, every field set in `other` takes precedence over `self`
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            name_starts_with: other.name_starts_with.or(self.name_starts_with),
+            name_ends_with: other.name_ends_with.or(self.name_ends_with),
+            name_contains: other.name_contains.or(self.name_contains),
+            name_is: other.name_is.or(self.name_is),
+        }
+    }
+
+    pub fn insert_query(&self, url: &mut Url) {
+        query::append_some(url, "name__istartswith", self.name_starts_with.as_deref());
+        query::append_some(url, "name__iendswith", self.name_ends_with.as_deref());
+        query::append_some(url, "name__icontains", self.name_contains.as_deref());
+        query::append_some(url, "name__iexact", self.name_is.as_deref());
+    }
+}
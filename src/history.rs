@@ -0,0 +1,51 @@
+//! # History
+//!
+//! The audit log of a document, when it is enabled on the server, see
+//! [`Paperless::document_history`](crate::Paperless::document_history).
+
+use crate::date::{self, DateTime};
+use crate::note::ResolvedUser;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Create,
+    Update,
+    Delete,
+    /// Action added by a newer version of paperless
+    #[serde(other)]
+    Unknown,
+}
+
+/// Modification of a field
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Change {
+    /// The value was replaced, `null` values mean that the field was not set
+    Replaced(serde_json::Value, serde_json::Value),
+    /// Other modifications, like tags added or removed, as sent by the server
+    Other(serde_json::Value),
+}
+
+/// A modification of a document
+#[derive(Debug, Deserialize, Clone)]
+pub struct Entry {
+    pub id: u64,
+    #[serde(with = "date::datetime")]
+    pub timestamp: DateTime,
+    pub action: Action,
+    /// Modified fields, by name
+    #[serde(default)]
+    pub changes: Option<HashMap<String, Change>>,
+    /// User who made the modification, `None` for modifications made by the server itself
+    pub actor: Option<ResolvedUser>,
+}
+
+impl Entry {
+    /// Modification of a field in this entry, if it was modified
+    pub fn change(&self, field: &str) -> Option<&Change> {
+        self.changes.as_ref()?.get(field)
+    }
+}
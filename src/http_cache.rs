@@ -0,0 +1,86 @@
+//! Conditional requests: responses are kept with their `ETag` and `Last-Modified` headers, which
+//! are sent back to the server so that it answers `304 Not Modified` instead of the same body
+
+use reqwest::header::{
+    HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+struct Entry {
+    etag: Option<HeaderValue>,
+    last_modified: Option<HeaderValue>,
+    body: Arc<[u8]>,
+}
+
+#[derive(Default)]
+struct Entries {
+    by_url: HashMap<String, Entry>,
+    /// Urls from the oldest to the newest, to forget the oldest ones first
+    order: VecDeque<String>,
+}
+
+/// Bodies of the responses to GET requests, by url
+pub(crate) struct HttpCache {
+    entries: Mutex<Entries>,
+    max_entries: usize,
+}
+
+impl HttpCache {
+    pub(crate) fn new(max_entries: usize) -> Self {
+        Self {
+            entries: Mutex::default(),
+            max_entries,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Entries> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Headers making the request conditional, if a response is known for this url
+    pub(crate) fn conditions(&self, url: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(entry) = self.lock().by_url.get(url) {
+            if let Some(etag) = &entry.etag {
+                headers.insert(IF_NONE_MATCH, etag.clone());
+            }
+            if let Some(last_modified) = &entry.last_modified {
+                headers.insert(IF_MODIFIED_SINCE, last_modified.clone());
+            }
+        }
+        headers
+    }
+
+    /// Body of the known response, when the server answered that it was not modified
+    pub(crate) fn body(&self, url: &str) -> Option<Arc<[u8]>> {
+        self.lock().by_url.get(url).map(|e| Arc::clone(&e.body))
+    }
+
+    /// Keep a response, if the server sent something to make the next request conditional
+    pub(crate) fn store(&self, url: &str, headers: &HeaderMap, body: &[u8]) {
+        let etag = headers.get(ETAG).cloned();
+        let last_modified = headers.get(LAST_MODIFIED).cloned();
+        let mut entries = self.lock();
+        if etag.is_none() && last_modified.is_none() {
+            if entries.by_url.remove(url).is_some() {
+                entries.order.retain(|u| u != url);
+            }
+            return;
+        }
+        let entry = Entry {
+            etag,
+            last_modified,
+            body: body.into(),
+        };
+        if entries.by_url.insert(url.to_string(), entry).is_none() {
+            entries.order.push_back(url.to_string());
+        }
+        while entries.by_url.len() > self.max_entries {
+            let Some(oldest) = entries.order.pop_front() else {
+                break;
+            };
+            entries.by_url.remove(&oldest);
+        }
+    }
+}
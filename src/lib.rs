@@ -2,15 +2,74 @@
 //!
 //! This create was created for a fuse driver for paperless, so some functions and endpoints are not present
 
+mod api;
 pub mod asn;
+#[cfg(feature = "tokio")]
+mod async_paginated;
+#[cfg(feature = "tokio")]
+mod async_paperless;
+pub mod auth;
+mod builder;
+pub mod bulk_edit;
+mod cache;
+pub mod cleanup;
+pub mod config;
 pub mod correspondent;
+pub mod custom_field;
+pub mod dashboard;
+pub mod date;
 pub mod document;
 pub mod document_type;
+mod error;
+#[cfg(feature = "events")]
+pub mod events;
+#[cfg(feature = "export")]
+pub mod export;
+#[cfg(feature = "exporter")]
+pub mod exporter;
+pub mod group;
+pub mod history;
+mod http_cache;
+pub mod mail_account;
+pub mod mail_rule;
+pub mod matching;
+pub mod note;
 mod paginated;
 mod paperless;
+pub mod permissions;
+pub mod priority;
+mod query;
+mod rate_limit;
+pub mod retry;
+pub mod rollback;
+mod routes;
 pub mod saved_view;
+pub mod search;
+mod server;
+pub mod share_link;
+mod size;
+pub mod slug;
+pub mod statistics;
 pub mod storage_path;
+pub mod sync;
 pub mod tag;
+pub mod task;
+#[cfg(test)]
+mod test_server;
+mod trace;
+pub mod trash;
+pub mod ui_settings;
+pub mod user;
+pub mod version;
+pub mod workflow;
 
-pub use paginated::Paginated;
+pub use api::PaperlessApi;
+#[cfg(feature = "tokio")]
+pub use async_paginated::AsyncPaginated;
+#[cfg(feature = "tokio")]
+pub use async_paperless::AsyncPaperless;
+pub use builder::PaperlessBuilder;
+pub use error::{Error, OptionalExt};
+pub use paginated::{ExactSize, OwnedPaginated, Paginated};
 pub use paperless::Paperless;
+pub use sync::State as SyncState;
@@ -0,0 +1,196 @@
+//! # Mail account
+//!
+//! Mailboxes from which paperless fetches documents, according to the mail rules.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+#[derive(Debug, Deserialize, Copy, Clone, Serialize, PartialEq, Eq, Hash)]
+pub struct Id(u64);
+
+impl From<u64> for Id {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+impl From<Id> for u64 {
+    fn from(value: Id) -> Self {
+        value.0
+    }
+}
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Encryption of the connection to the IMAP server
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Security {
+    None,
+    Ssl,
+    StartTls,
+    /// A security unknown to this version of the crate
+    Unknown(u64),
+}
+
+impl From<u64> for Security {
+    fn from(value: u64) -> Self {
+        match value {
+            1 => Security::None,
+            2 => Security::Ssl,
+            3 => Security::StartTls,
+            v => Security::Unknown(v),
+        }
+    }
+}
+impl From<Security> for u64 {
+    fn from(value: Security) -> Self {
+        match value {
+            Security::None => 1,
+            Security::Ssl => 2,
+            Security::StartTls => 3,
+            Security::Unknown(v) => v,
+        }
+    }
+}
+
+impl Serialize for Security {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64((*self).into())
+    }
+}
+impl<'de> Deserialize<'de> for Security {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(u64::deserialize(deserializer)?.into())
+    }
+}
+
+/// Kind of mailbox, which defines how the user is authenticated
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AccountType {
+    /// Authenticated with the password, or a token if `is_token` is set
+    Imap,
+    /// Authenticated with OAuth
+    Gmail,
+    /// Authenticated with OAuth
+    Outlook,
+    /// A type unknown to this version of the crate
+    Unknown(u64),
+}
+
+impl From<u64> for AccountType {
+    fn from(value: u64) -> Self {
+        match value {
+            1 => AccountType::Imap,
+            2 => AccountType::Gmail,
+            3 => AccountType::Outlook,
+            v => AccountType::Unknown(v),
+        }
+    }
+}
+impl From<AccountType> for u64 {
+    fn from(value: AccountType) -> Self {
+        match value {
+            AccountType::Imap => 1,
+            AccountType::Gmail => 2,
+            AccountType::Outlook => 3,
+            AccountType::Unknown(v) => v,
+        }
+    }
+}
+
+impl Serialize for AccountType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64((*self).into())
+    }
+}
+impl<'de> Deserialize<'de> for AccountType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(u64::deserialize(deserializer)?.into())
+    }
+}
+
+fn imap() -> AccountType {
+    AccountType::Imap
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct MailAccount {
+    pub id: Id,
+    pub name: String,
+    #[serde(alias = "imapServer")]
+    pub imap_server: String,
+    /// `None` to use the default port of the security
+    #[serde(alias = "imapPort")]
+    pub imap_port: Option<u16>,
+    #[serde(alias = "imapSecurity")]
+    pub imap_security: Security,
+    pub username: String,
+    /// Hidden by the server
+    #[serde(default)]
+    pub password: String,
+    /// Character set used to talk with the server, like `UTF-8`
+    #[serde(alias = "characterSet")]
+    pub character_set: String,
+    /// The password is an authentication token
+    #[serde(default, alias = "isToken")]
+    pub is_token: bool,
+    #[serde(default = "imap", alias = "accountType")]
+    pub account_type: AccountType,
+}
+
+/// A new mail account
+#[derive(Debug, Clone, Serialize)]
+pub struct Create {
+    pub name: String,
+    pub imap_server: String,
+    pub imap_port: Option<u16>,
+    pub imap_security: Security,
+    pub username: String,
+    pub password: String,
+    pub character_set: String,
+    pub is_token: bool,
+    pub account_type: AccountType,
+}
+
+impl Create {
+    /// An IMAP account using SSL on the default port, authenticated with a password
+    pub fn new(name: &str, imap_server: &str, username: &str, password: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            imap_server: imap_server.to_string(),
+            imap_port: None,
+            imap_security: Security::Ssl,
+            username: username.to_string(),
+            password: password.to_string(),
+            character_set: "UTF-8".to_string(),
+            is_token: false,
+            account_type: AccountType::Imap,
+        }
+    }
+}
+
+/// Modification of a mail account, only the fields set to `Some` are modified
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct Update {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub imap_server: Option<String>,
+    /// `Some(None)` to use the default port of the security
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub imap_port: Option<Option<u16>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub imap_security: Option<Security>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub character_set: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_token: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_type: Option<AccountType>,
+}
@@ -0,0 +1,419 @@
+//! # Mail rule
+//!
+//! Mail rules select the mails fetched from a [mail account](crate::mail_account), and define
+//! what is consumed from them and what is done with the mails afterwards.
+
+use crate::{correspondent, document_type, mail_account, tag};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+#[derive(Debug, Deserialize, Copy, Clone, Serialize, PartialEq, Eq, Hash)]
+pub struct Id(u64);
+
+impl From<u64> for Id {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+impl From<Id> for u64 {
+    fn from(value: Id) -> Self {
+        value.0
+    }
+}
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// What is done with a mail once processed
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Action {
+    Delete,
+    /// Move to the folder in `action_parameter`
+    Move,
+    MarkRead,
+    Flag,
+    /// Add the tag in `action_parameter`
+    Tag,
+    /// A value unknown to this version of the crate
+    Unknown(u64),
+}
+
+impl From<u64> for Action {
+    fn from(value: u64) -> Self {
+        match value {
+            1 => Action::Delete,
+            2 => Action::Move,
+            3 => Action::MarkRead,
+            4 => Action::Flag,
+            5 => Action::Tag,
+            v => Action::Unknown(v),
+        }
+    }
+}
+impl From<Action> for u64 {
+    fn from(value: Action) -> Self {
+        match value {
+            Action::Delete => 1,
+            Action::Move => 2,
+            Action::MarkRead => 3,
+            Action::Flag => 4,
+            Action::Tag => 5,
+            Action::Unknown(v) => v,
+        }
+    }
+}
+
+impl Serialize for Action {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64((*self).into())
+    }
+}
+impl<'de> Deserialize<'de> for Action {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(u64::deserialize(deserializer)?.into())
+    }
+}
+
+/// Source of the title of the documents
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TitleFrom {
+    Subject,
+    /// Name of the attachment
+    FileName,
+    /// Let the consumer choose the title
+    None,
+    /// A value unknown to this version of the crate
+    Unknown(u64),
+}
+
+impl From<u64> for TitleFrom {
+    fn from(value: u64) -> Self {
+        match value {
+            1 => TitleFrom::Subject,
+            2 => TitleFrom::FileName,
+            3 => TitleFrom::None,
+            v => TitleFrom::Unknown(v),
+        }
+    }
+}
+impl From<TitleFrom> for u64 {
+    fn from(value: TitleFrom) -> Self {
+        match value {
+            TitleFrom::Subject => 1,
+            TitleFrom::FileName => 2,
+            TitleFrom::None => 3,
+            TitleFrom::Unknown(v) => v,
+        }
+    }
+}
+
+impl Serialize for TitleFrom {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64((*self).into())
+    }
+}
+impl<'de> Deserialize<'de> for TitleFrom {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(u64::deserialize(deserializer)?.into())
+    }
+}
+
+/// How the correspondent of the documents is chosen
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CorrespondentFrom {
+    /// Let the consumer choose the correspondent
+    None,
+    /// Address of the sender
+    Email,
+    /// Name of the sender
+    Name,
+    /// The correspondent in `assign_correspondent`
+    Custom,
+    /// A value unknown to this version of the crate
+    Unknown(u64),
+}
+
+impl From<u64> for CorrespondentFrom {
+    fn from(value: u64) -> Self {
+        match value {
+            1 => CorrespondentFrom::None,
+            2 => CorrespondentFrom::Email,
+            3 => CorrespondentFrom::Name,
+            4 => CorrespondentFrom::Custom,
+            v => CorrespondentFrom::Unknown(v),
+        }
+    }
+}
+impl From<CorrespondentFrom> for u64 {
+    fn from(value: CorrespondentFrom) -> Self {
+        match value {
+            CorrespondentFrom::None => 1,
+            CorrespondentFrom::Email => 2,
+            CorrespondentFrom::Name => 3,
+            CorrespondentFrom::Custom => 4,
+            CorrespondentFrom::Unknown(v) => v,
+        }
+    }
+}
+
+impl Serialize for CorrespondentFrom {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64((*self).into())
+    }
+}
+impl<'de> Deserialize<'de> for CorrespondentFrom {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(u64::deserialize(deserializer)?.into())
+    }
+}
+
+/// Attachments which are consumed
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AttachmentType {
+    /// Only the attachments which are not inline
+    Attachments,
+    /// Inline files too
+    Everything,
+    /// A value unknown to this version of the crate
+    Unknown(u64),
+}
+
+impl From<u64> for AttachmentType {
+    fn from(value: u64) -> Self {
+        match value {
+            1 => AttachmentType::Attachments,
+            2 => AttachmentType::Everything,
+            v => AttachmentType::Unknown(v),
+        }
+    }
+}
+impl From<AttachmentType> for u64 {
+    fn from(value: AttachmentType) -> Self {
+        match value {
+            AttachmentType::Attachments => 1,
+            AttachmentType::Everything => 2,
+            AttachmentType::Unknown(v) => v,
+        }
+    }
+}
+
+impl Serialize for AttachmentType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64((*self).into())
+    }
+}
+impl<'de> Deserialize<'de> for AttachmentType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(u64::deserialize(deserializer)?.into())
+    }
+}
+
+/// Parts of the mails which are consumed
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConsumptionScope {
+    Attachments,
+    /// The mail itself, as a document
+    Email,
+    /// The mail and its attachments
+    Everything,
+    /// A value unknown to this version of the crate
+    Unknown(u64),
+}
+
+impl From<u64> for ConsumptionScope {
+    fn from(value: u64) -> Self {
+        match value {
+            1 => ConsumptionScope::Attachments,
+            2 => ConsumptionScope::Email,
+            3 => ConsumptionScope::Everything,
+            v => ConsumptionScope::Unknown(v),
+        }
+    }
+}
+impl From<ConsumptionScope> for u64 {
+    fn from(value: ConsumptionScope) -> Self {
+        match value {
+            ConsumptionScope::Attachments => 1,
+            ConsumptionScope::Email => 2,
+            ConsumptionScope::Everything => 3,
+            ConsumptionScope::Unknown(v) => v,
+        }
+    }
+}
+
+impl Serialize for ConsumptionScope {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64((*self).into())
+    }
+}
+impl<'de> Deserialize<'de> for ConsumptionScope {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(u64::deserialize(deserializer)?.into())
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct MailRule {
+    pub id: Id,
+    pub name: String,
+    pub account: mail_account::Id,
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+    /// Folder of the mailbox, subfolders are separated with a dot or a slash depending on the
+    /// server
+    pub folder: String,
+    #[serde(default, alias = "filterFrom")]
+    pub filter_from: Option<String>,
+    #[serde(default, alias = "filterTo")]
+    pub filter_to: Option<String>,
+    #[serde(default, alias = "filterSubject")]
+    pub filter_subject: Option<String>,
+    #[serde(default, alias = "filterBody")]
+    pub filter_body: Option<String>,
+    /// Pattern of the names of the attachments to consume, with `*` wildcards
+    #[serde(default, alias = "filterAttachmentFilenameInclude")]
+    pub filter_attachment_filename_include: Option<String>,
+    /// Pattern of the names of the attachments to ignore, with `*` wildcards
+    #[serde(default, alias = "filterAttachmentFilenameExclude")]
+    pub filter_attachment_filename_exclude: Option<String>,
+    /// Mails older than this number of days are ignored
+    #[serde(alias = "maximumAge")]
+    pub maximum_age: u32,
+    pub action: Action,
+    #[serde(default, alias = "actionParameter")]
+    pub action_parameter: Option<String>,
+    #[serde(alias = "assignTitleFrom")]
+    pub assign_title_from: TitleFrom,
+    #[serde(default, alias = "assignTags")]
+    pub assign_tags: Vec<tag::Id>,
+    #[serde(alias = "assignCorrespondentFrom")]
+    pub assign_correspondent_from: CorrespondentFrom,
+    #[serde(default, alias = "assignCorrespondent")]
+    pub assign_correspondent: Option<correspondent::Id>,
+    #[serde(default, alias = "assignDocumentType")]
+    pub assign_document_type: Option<document_type::Id>,
+    /// The documents belong to the owner of the rule
+    #[serde(default, alias = "assignOwnerFromRule")]
+    pub assign_owner_from_rule: bool,
+    /// Rules are applied by increasing order
+    pub order: i64,
+    #[serde(alias = "attachmentType")]
+    pub attachment_type: AttachmentType,
+    #[serde(alias = "consumptionScope")]
+    pub consumption_scope: ConsumptionScope,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+/// A new mail rule
+#[derive(Debug, Clone, Serialize)]
+pub struct Create {
+    pub name: String,
+    pub account: mail_account::Id,
+    pub enabled: bool,
+    pub folder: String,
+    pub filter_from: Option<String>,
+    pub filter_to: Option<String>,
+    pub filter_subject: Option<String>,
+    pub filter_body: Option<String>,
+    pub filter_attachment_filename_include: Option<String>,
+    pub filter_attachment_filename_exclude: Option<String>,
+    pub maximum_age: u32,
+    pub action: Action,
+    pub action_parameter: Option<String>,
+    pub assign_title_from: TitleFrom,
+    pub assign_tags: Vec<tag::Id>,
+    pub assign_correspondent_from: CorrespondentFrom,
+    pub assign_correspondent: Option<correspondent::Id>,
+    pub assign_document_type: Option<document_type::Id>,
+    pub assign_owner_from_rule: bool,
+    pub order: i64,
+    pub attachment_type: AttachmentType,
+    pub consumption_scope: ConsumptionScope,
+}
+
+impl Create {
+    /// A rule consuming the attachments of the mails of the last 30 days in `INBOX`, which are
+    /// then marked as read, like when created from the interface
+    pub fn new(name: &str, account: mail_account::Id) -> Self {
+        Self {
+            name: name.to_string(),
+            account,
+            enabled: true,
+            folder: "INBOX".to_string(),
+            filter_from: None,
+            filter_to: None,
+            filter_subject: None,
+            filter_body: None,
+            filter_attachment_filename_include: None,
+            filter_attachment_filename_exclude: None,
+            maximum_age: 30,
+            action: Action::MarkRead,
+            action_parameter: None,
+            assign_title_from: TitleFrom::Subject,
+            assign_tags: Vec::new(),
+            assign_correspondent_from: CorrespondentFrom::None,
+            assign_correspondent: None,
+            assign_document_type: None,
+            assign_owner_from_rule: true,
+            order: 0,
+            attachment_type: AttachmentType::Attachments,
+            consumption_scope: ConsumptionScope::Attachments,
+        }
+    }
+}
+
+/// Modification of a mail rule, only the fields set to `Some` are modified
+///
+/// Optional fields are removed with `Some(None)`.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct Update {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account: Option<mail_account::Id>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter_from: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter_to: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter_subject: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter_body: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter_attachment_filename_include: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter_attachment_filename_exclude: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maximum_age: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<Action>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action_parameter: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assign_title_from: Option<TitleFrom>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assign_tags: Option<Vec<tag::Id>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assign_correspondent_from: Option<CorrespondentFrom>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assign_correspondent: Option<Option<correspondent::Id>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assign_document_type: Option<Option<document_type::Id>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assign_owner_from_rule: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachment_type: Option<AttachmentType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consumption_scope: Option<ConsumptionScope>,
+}
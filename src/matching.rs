@@ -0,0 +1,67 @@
+//! # Matching
+//!
+//! Correspondents, document types, tags and storage paths can be automatically assigned to new
+//! documents when their content matches a pattern.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// How the `match` pattern is used to assign an entity to a document
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Algorithm {
+    /// Never assigned automatically
+    None,
+    /// The document contains any of the words of the pattern
+    Any,
+    /// The document contains all the words of the pattern
+    All,
+    /// The document contains the exact pattern
+    Literal,
+    /// The document matches the pattern as a regular expression
+    Regex,
+    /// The document contains a word close to the pattern
+    Fuzzy,
+    /// Learnt from the documents which are already assigned
+    Auto,
+    /// An algorithm unknown to this version of the crate
+    Unknown(u64),
+}
+
+impl From<u64> for Algorithm {
+    fn from(value: u64) -> Self {
+        match value {
+            0 => Algorithm::None,
+            1 => Algorithm::Any,
+            2 => Algorithm::All,
+            3 => Algorithm::Literal,
+            4 => Algorithm::Regex,
+            5 => Algorithm::Fuzzy,
+            6 => Algorithm::Auto,
+            v => Algorithm::Unknown(v),
+        }
+    }
+}
+impl From<Algorithm> for u64 {
+    fn from(value: Algorithm) -> Self {
+        match value {
+            Algorithm::None => 0,
+            Algorithm::Any => 1,
+            Algorithm::All => 2,
+            Algorithm::Literal => 3,
+            Algorithm::Regex => 4,
+            Algorithm::Fuzzy => 5,
+            Algorithm::Auto => 6,
+            Algorithm::Unknown(v) => v,
+        }
+    }
+}
+
+impl Serialize for Algorithm {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64((*self).into())
+    }
+}
+impl<'de> Deserialize<'de> for Algorithm {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(u64::deserialize(deserializer)?.into())
+    }
+}
@@ -0,0 +1,108 @@
+//! # Note
+//!
+//! Notes are comments written by users on a document.
+
+use crate::date::{self, DateTime};
+use crate::user;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Deserialize, Copy, Clone, Serialize, PartialEq, Eq, Hash)]
+pub struct Id(u64);
+
+impl From<u64> for Id {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+impl From<Id> for u64 {
+    fn from(value: Id) -> Self {
+        value.0
+    }
+}
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Note {
+    pub id: Id,
+    pub note: String,
+    #[serde(with = "date::datetime")]
+    pub created: DateTime,
+    /// Author of the note
+    pub user: ResolvedUser,
+}
+
+/// A user with its names
+///
+/// Recent servers send the names with the note, older ones only send the id of the user: the
+/// names are `None` in this case.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "UserRepr")]
+pub struct ResolvedUser {
+    pub id: user::Id,
+    pub username: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+}
+
+impl ResolvedUser {
+    /// Name to show for this user: its full name, its username, or its id as a last resort
+    pub fn display_name(&self) -> String {
+        let full_name = [&self.first_name, &self.last_name]
+            .into_iter()
+            .flatten()
+            .filter(|n| !n.is_empty())
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !full_name.is_empty() {
+            full_name
+        } else if let Some(username) = &self.username {
+            username.clone()
+        } else {
+            format!("#{}", self.id)
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum UserRepr {
+    Id(user::Id),
+    User {
+        id: user::Id,
+        username: Option<String>,
+        #[serde(alias = "firstName")]
+        first_name: Option<String>,
+        #[serde(alias = "lastName")]
+        last_name: Option<String>,
+    },
+}
+
+impl From<UserRepr> for ResolvedUser {
+    fn from(value: UserRepr) -> Self {
+        match value {
+            UserRepr::Id(id) => Self {
+                id,
+                username: None,
+                first_name: None,
+                last_name: None,
+            },
+            UserRepr::User {
+                id,
+                username,
+                first_name,
+                last_name,
+            } => Self {
+                id,
+                username,
+                first_name,
+                last_name,
+            },
+        }
+    }
+}
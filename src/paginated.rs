@@ -1,32 +1,163 @@
-use crate::paperless::Paperless;
-use reqwest::{Method, Url};
+use crate::paperless::{Background, Paperless};
+use crate::query;
+use crate::server::Server;
+use crate::Error;
+use reqwest::Url;
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
+use std::ops::Deref;
+use std::sync::Arc;
 
 #[derive(Deserialize)]
-struct PaginatedResult<T> {
-    #[serde(rename = "count")]
-    _count: u64,
+pub(crate) struct PaginatedResult<T> {
+    pub(crate) count: u64,
     next: Option<String>,
     #[serde(rename = "previous")]
     _previous: Option<String>,
-    results: Vec<T>,
+    pub(crate) results: Vec<T>,
+    /// Only sent for full text searches
+    #[serde(default)]
+    pub(crate) corrected_query: Option<String>,
 }
 
+impl<T> PaginatedResult<T> {
+    /// Url of the next page, if any, relative to the root of the client
+    ///
+    /// The server builds the url from the request it received, which behind a reverse proxy may
+    /// have another scheme, host or path than the ones used by the client.
+    pub(crate) fn next_url(&self, server: &Server) -> Result<Option<Url>, Error> {
+        match &self.next {
+            None => Ok(None),
+            Some(s) => Ok(Some(server.rebase(&Url::parse(s)?)?)),
+        }
+    }
+}
+
+/// Url of a page of a listing, fails for the page 0
+pub(crate) fn page_url(url: &Url, n: u64) -> Result<Url, Error> {
+    if n == 0 {
+        return Err(Error::InvalidArgument("pages start at 1".to_string()));
+    }
+    let mut url = url.clone();
+    query::replace_pair(&mut url, "page", &n.to_string());
+    Ok(url)
+}
+
+/// Client used by a listing
+enum Client<'p> {
+    Borrowed(&'p Paperless),
+    Shared(Arc<Paperless>),
+}
+
+impl Deref for Client<'_> {
+    type Target = Paperless;
+
+    fn deref(&self) -> &Paperless {
+        match self {
+            Client::Borrowed(paperless) => paperless,
+            Client::Shared(paperless) => paperless,
+        }
+    }
+}
+
+/// A [`Paginated`] which doesn't borrow the client, see [`Paginated::into_owned`]
+pub type OwnedPaginated<T> = Paginated<'static, T>;
+
 pub struct Paginated<'p, T> {
-    paperless: &'p Paperless,
+    paperless: Client<'p>,
     url: Url,
     last_result: Option<PaginatedResult<T>>,
     current_index: usize,
+    /// Number of items already returned
+    yielded: u64,
+    pages_fetched: u64,
+    /// Request the next page while the current one is consumed
+    prefetch: bool,
+    /// Next page, requested in background
+    pending: Option<Background>,
 }
 
 impl<'p, T> Paginated<'p, T> {
     pub fn new(paperless: &'p Paperless, url: Url) -> Self {
         Self {
-            paperless,
+            paperless: Client::Borrowed(paperless),
             url,
             last_result: None,
             current_index: 0,
+            yielded: 0,
+            pages_fetched: 0,
+            prefetch: false,
+            pending: None,
+        }
+    }
+
+    /// Number of items requested per page, the server sends 25 by default
+    ///
+    /// Bigger pages need fewer requests to list many items. It has no effect once the first page
+    /// was requested, as the next pages keep the size of the first one.
+    ///
+    /// # Arguments
+    ///
+    /// * `page_size` - Number of items per page, at most 100000
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        query::replace_pair(&mut self.url, "page_size", &page_size.to_string());
+        self
+    }
+
+    /// Keep the client in the listing, so that it can be stored next to the client, for example
+    /// in an open directory of a file system
+    ///
+    /// Fails with [`Error::InvalidArgument`] if `paperless` is not the client which created the
+    /// listing, the next pages would otherwise be requested with other credentials.
+    ///
+    /// # Arguments
+    ///
+    /// * `paperless` - The client which created the listing
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use paperless::{OwnedPaginated, Paperless};
+    /// use paperless::tag::Tag;
+    /// use std::sync::Arc;
+    ///
+    /// fn open(paperless: &Arc<Paperless>) -> Result<OwnedPaginated<Tag>, paperless::Error> {
+    ///     paperless.tags(Default::default())?.into_owned(Arc::clone(paperless))
+    /// }
+    /// ```
+    pub fn into_owned(self, paperless: Arc<Paperless>) -> Result<OwnedPaginated<T>, Error> {
+        if !std::ptr::eq(&*self.paperless, &*paperless) {
+            return Err(Error::InvalidArgument(
+                "the listing was created by another client".to_string(),
+            ));
+        }
+        Ok(Paginated {
+            paperless: Client::Shared(paperless),
+            url: self.url,
+            last_result: self.last_result,
+            current_index: self.current_index,
+            yielded: self.yielded,
+            pages_fetched: self.pages_fetched,
+            prefetch: self.prefetch,
+            pending: self.pending,
+        })
+    }
+
+    /// Continue a listing of which the first page was already requested
+    pub(crate) fn with_first_page(
+        paperless: &'p Paperless,
+        url: Url,
+        first_page: PaginatedResult<T>,
+    ) -> Self {
+        Self {
+            paperless: Client::Borrowed(paperless),
+            url,
+            last_result: Some(first_page),
+            current_index: 0,
+            yielded: 0,
+            pages_fetched: 1,
+            prefetch: false,
+            pending: None,
         }
     }
 }
@@ -34,42 +165,125 @@ impl<'p, T> Paginated<'p, T>
 where
     T: DeserializeOwned,
 {
-    fn fetch_next(&mut self) -> Result<(), reqwest::Error> {
-        let next_url = if let Some(last) = &self.last_result {
-            match &last.next {
-                None => None,
-                Some(s) => {
-                    let mut url = Url::parse(s).unwrap(); // TODO: fix this unwrap
-                    url.set_scheme("https").unwrap();
-                    Some(url)
+    fn fetch_next(&mut self) -> Result<(), Error> {
+        let page = match self.pending.take() {
+            Some(pending) => pending.join(&self.paperless)?,
+            None => {
+                let next_url = if let Some(last) = &self.last_result {
+                    last.next_url(&self.paperless.server)?
+                } else {
+                    Some(self.url.clone())
+                };
+                match next_url {
+                    None => return Ok(()),
+                    Some(path) => self.paperless.get(path)?,
                 }
             }
-        } else {
-            Some(self.url.clone())
         };
+        self.last_result = Some(page);
+        self.current_index = 0;
+        self.pages_fetched += 1;
+        if self.prefetch {
+            self.prefetch_next()?;
+        }
+        Ok(())
+    }
 
-        match next_url {
-            None => {}
-            Some(path) => {
-                println!("{} ", path.to_string());
-                self.last_result = Some(
-                    self.paperless
-                        .http_client
-                        .execute(self.paperless.request(Method::GET, path))?
-                        .json()?,
-                );
-                self.current_index = 0;
+    /// Request the page after the current one in background, if there is one
+    fn prefetch_next(&mut self) -> Result<(), Error> {
+        if let Some(last) = &self.last_result {
+            if let Some(url) = last.next_url(&self.paperless.server)? {
+                self.pending = Some(self.paperless.get_in_background(url));
             }
         }
         Ok(())
     }
+
+    /// Request each page in a background thread while the previous one is consumed, instead of
+    /// waiting for the previous one to be consumed
+    ///
+    /// It speeds up the iteration over many items, but a page may be requested even if it is
+    /// never consumed.
+    pub fn prefetch(mut self) -> Result<Self, Error> {
+        self.prefetch = true;
+        if self.pending.is_none() {
+            self.prefetch_next()?;
+        }
+        Ok(self)
+    }
+
+    /// Get the items of the current page which were not returned yet, or the next page if they
+    /// all were
+    ///
+    /// Returns `None` once every item has been returned. It can be mixed with the iterator, which
+    /// continues after the returned page.
+    pub fn next_page(&mut self) -> Result<Option<Vec<T>>, Error> {
+        if self.last_result.iter().all(|last| last.results.is_empty()) {
+            self.fetch_next()?;
+        }
+        match &mut self.last_result {
+            Some(last) if !last.results.is_empty() => {
+                let page = std::mem::take(&mut last.results);
+                self.yielded += page.len() as u64;
+                Ok(Some(page))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Get a page of the listing, without changing the items returned by the iterator
+    ///
+    /// Fails with [`Error::NotFound`] for pages after the last one.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - Number of the page, starting at 1
+    pub fn page(&self, n: u64) -> Result<Vec<T>, Error> {
+        let url = page_url(&self.url, n)?;
+        let page: PaginatedResult<T> = self.paperless.get(url)?;
+        Ok(page.results)
+    }
+
+    /// Number of items of the listing, announced by the server in the first page
+    ///
+    /// The first page is requested if needed, its items are still returned by the iterator.
+    pub fn total_count(&mut self) -> Result<u64, Error> {
+        if self.last_result.is_none() {
+            self.fetch_next()?;
+        }
+        Ok(self.last_result.as_ref().map_or(0, |last| last.count))
+    }
+
+    /// Number of pages requested to the server so far
+    pub fn pages_fetched(&self) -> u64 {
+        self.pages_fetched
+    }
+
+    /// Request the first page if needed, so that the number of items is known, and return an
+    /// iterator implementing [`ExactSizeIterator`]
+    ///
+    /// The number of items is the one announced by the server in the first page: if documents
+    /// are added or deleted while iterating, the iterator stops after this number of items, or
+    /// returns fewer.
+    pub fn exact_size(mut self) -> Result<ExactSize<'p, T>, Error> {
+        if self.last_result.is_none() {
+            self.fetch_next()?;
+        }
+        Ok(ExactSize(self))
+    }
+
+    /// Number of items not returned yet, if the first page was requested
+    fn remaining(&self) -> Option<u64> {
+        let last = self.last_result.as_ref()?;
+        Some(last.count.saturating_sub(self.yielded))
+    }
 }
 
 impl<'p, T> Iterator for Paginated<'p, T>
 where
     T: DeserializeOwned,
 {
-    type Item = Result<T, reqwest::Error>;
+    type Item = Result<T, Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
         match &self.last_result {
@@ -93,9 +307,50 @@ where
                 if last.results.is_empty() {
                     None
                 } else {
+                    self.yielded += 1;
                     Some(Ok(last.results.remove(0)))
                 }
             }
         }
     }
+
+    /// At least the items of the current page, the count of the server is not an upper bound as
+    /// the list can change while iterating, see [`Paginated::exact_size`]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let buffered = self
+            .last_result
+            .as_ref()
+            .map_or(0, |last| last.results.len());
+        (buffered, None)
+    }
 }
+
+/// A [`Paginated`] of which the number of items is known, see [`Paginated::exact_size`]
+pub struct ExactSize<'p, T>(Paginated<'p, T>);
+
+impl<'p, T> Iterator for ExactSize<'p, T>
+where
+    T: DeserializeOwned,
+{
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.remaining() == Some(0) {
+            return None;
+        }
+        self.0.next()
+    }
+
+    /// Exact once the first page was requested, from the count of the server
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.0.remaining() {
+            Some(remaining) => {
+                let remaining = usize::try_from(remaining).unwrap_or(usize::MAX);
+                (remaining, Some(remaining))
+            }
+            None => (0, None),
+        }
+    }
+}
+
+impl<'p, T> ExactSizeIterator for ExactSize<'p, T> where T: DeserializeOwned {}
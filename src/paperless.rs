@@ -1,13 +1,115 @@
-use crate::{correspondent, document, document_type, saved_view, tag, Paginated};
+use crate::cache::Cache;
+use crate::http_cache::HttpCache;
+use crate::paginated::PaginatedResult;
+use crate::priority::{self, Priority, Scheduler};
+use crate::rate_limit::{self, Limiter, RateLimit};
+use crate::retry::RetryPolicy;
+use crate::rollback::{Rollback, Step};
+use crate::routes;
+use crate::server::Server;
+use crate::size;
+use crate::trace;
+use crate::{
+    asn, bulk_edit, config, correspondent, custom_field, dashboard, date, document, document_type,
+    group, history, mail_account, mail_rule, note, permissions, saved_view, search, share_link,
+    statistics, storage_path, tag, task, trash, ui_settings, user, version, workflow, Error,
+    Paginated, PaperlessBuilder,
+};
 
-use reqwest::blocking::{Client, Request};
-use reqwest::header::HeaderValue;
-use reqwest::{Method, Url};
+use reqwest::blocking::{Client, Request, Response};
+use reqwest::header::{HeaderMap, HeaderValue, RANGE};
+use reqwest::{Method, StatusCode, Url};
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::Serialize;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle, ScopedJoinHandle};
+use std::time::{Duration, Instant};
+
+/// Everything needed to send a request, shared with the threads prefetching pages
+#[derive(Clone)]
+struct Transport {
+    client: Client,
+    scheduler: Arc<Scheduler>,
+    retry: RetryPolicy,
+    limiter: Arc<Limiter>,
+    max_retry_after: Duration,
+}
+
+impl Transport {
+    /// Send a request, again when the server asks to wait with `Retry-After`, and after
+    /// transient failures if it is idempotent
+    fn execute(&self, priority: Priority, mut request: Request) -> reqwest::Result<Response> {
+        let mut attempt = 1;
+        let mut rate_limited = 0;
+        loop {
+            // Rate limited requests were not processed, so any method can be sent again
+            let next = request.try_clone();
+            self.limiter.wait();
+            let result = {
+                let _permit = self.scheduler.acquire(priority);
+                let traced = trace::Request::start(request.method(), request.url());
+                let result = self.client.execute(request);
+                traced.finish(result.as_ref().map(Response::status));
+                result
+            };
+            let Some(next) = next else {
+                return result;
+            };
+
+            let retry_after = result
+                .as_ref()
+                .ok()
+                .and_then(|r| rate_limit::retry_after(r.status(), r.headers()));
+            match retry_after {
+                Some(delay)
+                    if rate_limited < rate_limit::MAX_RETRIES && delay <= self.max_retry_after =>
+                {
+                    trace::retry(next.url(), delay, "rate limited");
+                    thread::sleep(delay);
+                    rate_limited += 1;
+                }
+                Some(_) => return result,
+                None if attempt < self.retry.max_attempts
+                    && RetryPolicy::is_idempotent(next.method())
+                    && RetryPolicy::is_transient(&result.as_ref().map(Response::status)) =>
+                {
+                    let delay = self.retry.delay(attempt);
+                    trace::retry(next.url(), delay, "transient failure");
+                    thread::sleep(delay);
+                    attempt += 1;
+                }
+                None => return result,
+            }
+            request = next;
+        }
+    }
+}
+
+/// Status, headers and body of a response
+type RawResponse = (StatusCode, HeaderMap, Vec<u8>);
+
+/// A GET request sent by [`Paperless::get_in_background`]
+pub(crate) struct Background(JoinHandle<Result<RawResponse, reqwest::Error>>);
+
+impl Background {
+    /// Wait for the response and deserialize it
+    pub(crate) fn join<T: DeserializeOwned>(self, paperless: &Paperless) -> Result<T, Error> {
+        let (status, headers, body) = self
+            .0
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))?;
+        paperless.check_response(status, &headers)?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+}
 
 pub struct Paperless {
-    pub(crate) http_client: Client,
-    root: Url,
-    token: HeaderValue,
+    transport: Transport,
+    pub(crate) server: Server,
+    size_probe: size::Probe,
+    cache: Cache,
+    /// Responses to GET requests, to send conditional requests
+    http_cache: Option<HttpCache>,
 }
 
 impl Paperless {
@@ -15,44 +117,303 @@ impl Paperless {
     ///
     /// # Arguments
     ///
-    /// * `root` - Root URL of the api, for example `https://paperless.com/api/`
+    /// * `root` - Root URL of the api, for example `https://paperless.com/api/`, or of the server
     /// * `token` - A token to access this instance
     ///
+    /// Fails with [`Error::InvalidArgument`] if the root is not an http(s) url, and with
+    /// [`Error::InvalidToken`] if the token contains characters which are not allowed in a header.
+    ///
     /// # Examples
     ///
     /// You can create a new instance like this:
     /// ```rust
     /// use paperless::Paperless;
-    /// let paperless = Paperless::new("https://example.com/paperless/api/", "thisIsAToken");
+    /// let paperless = Paperless::new("https://example.com/paperless/api/", "thisIsAToken").unwrap();
+    /// assert!(Paperless::new("ftp://example.com/", "thisIsAToken").is_err());
+    /// assert!(Paperless::new("https://example.com/", "this\nIsAToken").is_err());
     /// ```
-    pub fn new(root: &str, token: &str) -> Self {
+    pub fn new(root: &str, token: &str) -> Result<Self, Error> {
+        PaperlessBuilder::new(root, token).build()
+    }
+
+    /// Create a new instance of paperless API, with a token obtained from the credentials of a
+    /// user
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - Root URL of the api, for example `https://paperless.com/api/`, or of the server
+    /// * `username` - Name of the user
+    /// * `password` - Password of the user
+    ///
+    /// Fails with [`Error::Unauthorized`] if the server refuses the credentials. Use
+    /// [`PaperlessBuilder::login`] to configure the connection used to log in.
+    pub fn login(root: &str, username: &str, password: &str) -> Result<Self, Error> {
+        PaperlessBuilder::new(root, "").login(username, password)
+    }
+
+    pub(crate) fn with_server(
+        server: Server,
+        http_client: Client,
+        retry: RetryPolicy,
+        rate_limit: RateLimit,
+        http_cache: Option<usize>,
+        cache_ttl: Option<Duration>,
+    ) -> Self {
         Self {
-            http_client: Client::new(),
-            root: Url::parse(root).unwrap(),
-            token: HeaderValue::from_str(&format!("Token {token}")).unwrap(),
+            transport: Transport {
+                client: http_client,
+                scheduler: Arc::default(),
+                retry,
+                limiter: Arc::new(Limiter::new(rate_limit.per_second)),
+                max_retry_after: rate_limit.max_retry_after,
+            },
+            server,
+            size_probe: size::Probe::default(),
+            cache: Cache::new(cache_ttl),
+            http_cache: http_cache.map(HttpCache::new),
         }
     }
 
     /// Generate a request object with authorization tokens.
     ///
     /// Caution: this will take any url and can leak token to wrong destination
-    pub(crate) fn request(&self, method: Method, path: Url) -> Request {
+    pub(crate) fn request(&self, method: Method, mut path: Url) -> Request {
+        if method == Method::GET {
+            self.server.prepare_get(&mut path);
+        }
         let mut request = Request::new(method, path);
+        request.headers_mut().extend(self.server.headers());
         request
-            .headers_mut()
-            .append("Authorization", self.token.clone());
-        request.headers_mut().append(
-            "Accept",
-            HeaderValue::from_str("application/json; version=2").unwrap(),
-        );
-        request
     }
 
-    /// Generate a request object for an endpoint
+    /// Send a request and check the status of the response
+    pub(crate) fn send(&self, request: Request) -> Result<Response, Error> {
+        let response = self.transport.execute(priority::current(), request)?;
+        self.check(response)
+    }
+
+    /// Check the status of a response, parsing the errors of validation of the server
+    fn check(&self, response: Response) -> Result<Response, Error> {
+        if response.status() == StatusCode::BAD_REQUEST {
+            return Err(Error::bad_request(&response.bytes()?));
+        }
+        self.check_response(response.status(), response.headers())?;
+        Ok(response)
+    }
+
+    /// Check the status of a response, and remember the version of the server
+    fn check_response(&self, status: StatusCode, headers: &HeaderMap) -> Result<(), Error> {
+        Error::check_status(status)?;
+        if let Some(version) = headers.get("X-Version").and_then(|v| v.to_str().ok()) {
+            self.cache.observe_version(version);
+        }
+        Ok(())
+    }
+
+    /// Send a GET request from another thread, with the priority of the current one
+    ///
+    /// The response is read by the thread, then checked and deserialized by [`Background::join`].
+    pub(crate) fn get_in_background(&self, url: Url) -> Background {
+        let request = self.request(Method::GET, url);
+        let transport = self.transport.clone();
+        let priority = priority::current();
+        Background(thread::spawn(move || {
+            let response = transport.execute(priority, request)?;
+            let (status, headers) = (response.status(), response.headers().clone());
+            Ok((status, headers, response.bytes()?.to_vec()))
+        }))
+    }
+
+    /// Send a GET request and deserialize the response
+    pub(crate) fn get<T: DeserializeOwned>(&self, url: Url) -> Result<T, Error> {
+        let Some(http_cache) = &self.http_cache else {
+            let body = self.send(self.request(Method::GET, url))?.bytes()?;
+            return Ok(serde_json::from_slice(&body)?);
+        };
+
+        let mut request = self.request(Method::GET, url.clone());
+        let key = request.url().to_string();
+        let conditions = http_cache.conditions(&key);
+        let response = if conditions.is_empty() {
+            self.send(request)?
+        } else {
+            request.headers_mut().extend(conditions);
+            let response = self.transport.execute(priority::current(), request)?;
+            if response.status() != StatusCode::NOT_MODIFIED {
+                self.check(response)?
+            } else if let Some(body) = http_cache.body(&key) {
+                return Ok(serde_json::from_slice(&body)?);
+            } else {
+                // The body was forgotten since the conditions were read, ask for it again
+                self.send(self.request(Method::GET, url))?
+            }
+        };
+        let headers = response.headers().clone();
+        let body = response.bytes()?;
+        http_cache.store(&key, &headers, &body);
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Send a request with a json body and deserialize the response
+    pub(crate) fn send_json<B: Serialize, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        url: Url,
+        body: &B,
+    ) -> Result<T, Error> {
+        let request = self
+            .transport
+            .client
+            .request(method, url)
+            .headers(self.server.headers())
+            .json(body)
+            .build()?;
+        let body = self.send(request)?.bytes()?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Send a DELETE request
+    pub(crate) fn delete(&self, url: Url) -> Result<(), Error> {
+        self.send(self.request(Method::DELETE, url))?;
+        Ok(())
+    }
+
+    /// Check if an object exists with a HEAD request, without downloading it
+    pub(crate) fn exists(&self, url: Url) -> Result<bool, Error> {
+        match self.send(self.request(Method::HEAD, url)) {
+            Ok(_) => Ok(true),
+            Err(Error::NotFound) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Count the elements of a list endpoint, by requesting only the first element
+    pub(crate) fn count(&self, mut url: Url) -> Result<u64, Error> {
+        url.query_pairs_mut().append_pair("page_size", "1");
+        let page: PaginatedResult<IgnoredAny> = self.get(url)?;
+        Ok(page.count)
+    }
+
+    /// Number of documents matching a filter, counted by the server
+    pub(crate) fn document_count(&self, filter: document::Filter) -> Result<u64, Error> {
+        let mut url = self.server.url_list(routes::DOCUMENTS)?;
+        filter.insert_query(&mut url);
+        self.count(url)
+    }
+
+    /// Run `f` with all the requests it sends from the current thread sent with `priority`
+    ///
+    /// Iterators must be consumed inside `f`, as pages are requested while iterating.
+    ///
+    /// # Arguments
+    ///
+    /// * `priority` - Priority of the requests
+    /// * `f` - Function sending the requests
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use paperless::priority::Priority;
+    /// use paperless::Paperless;
+    /// let paperless = Paperless::new("https://example.com/paperless/api/", "thisIsAToken").unwrap();
+    /// let tags = paperless.with_priority(Priority::Background, |p| {
+    ///     p.tags(Default::default())?.collect::<Result<Vec<_>, _>>()
+    /// });
+    /// ```
+    pub fn with_priority<T>(&self, priority: Priority, f: impl FnOnce(&Self) -> T) -> T {
+        priority::scoped(priority, || f(self))
+    }
+
+    /// Forget all the cached correspondents, document types, storage paths, tags and custom fields
+    ///
+    /// The caches are kept up to date with the modifications done by this client, call this after
+    /// modifications done by others, for example by a bulk edit from the interface.
+    pub fn invalidate_all(&self) {
+        self.cache.invalidate_all();
+    }
+
+    /// Forget the cached correspondents, they are loaded again on next use
+    pub fn invalidate_correspondents(&self) {
+        self.cache.correspondents.clear();
+    }
+
+    /// Forget the cached document types, they are loaded again on next use
+    pub fn invalidate_document_types(&self) {
+        self.cache.document_types.clear();
+    }
+
+    /// Forget the cached storage paths, they are loaded again on next use
+    pub fn invalidate_storage_paths(&self) {
+        self.cache.storage_paths.clear();
+    }
+
+    /// Forget the cached tags, they are loaded again on next use
+    pub fn invalidate_tags(&self) {
+        self.cache.tags.clear();
+    }
+
+    /// All the correspondents, sorted by name, from the cache shared with
+    /// [`Paperless::documents_detailed`]
+    ///
+    /// They are loaded on first use and again once the time set with
+    /// [`PaperlessBuilder::cache_ttl`] has elapsed.
+    pub fn cached_correspondents(&self) -> Result<Vec<correspondent::Correspondent>, Error> {
+        let mut correspondents = self.cache.cached_correspondents(self)?;
+        correspondents.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(correspondents)
+    }
+
+    /// All the document types, sorted by name, from the cache shared with
+    /// [`Paperless::documents_detailed`]
+    ///
+    /// They are loaded on first use and again once the time set with
+    /// [`PaperlessBuilder::cache_ttl`] has elapsed.
+    pub fn cached_document_types(&self) -> Result<Vec<document_type::DocumentType>, Error> {
+        let mut document_types = self.cache.cached_document_types(self)?;
+        document_types.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(document_types)
+    }
+
+    /// All the storage paths, sorted by name, from the cache shared with
+    /// [`Paperless::documents_detailed`]
+    ///
+    /// They are loaded on first use and again once the time set with
+    /// [`PaperlessBuilder::cache_ttl`] has elapsed.
+    pub fn cached_storage_paths(&self) -> Result<Vec<storage_path::StoragePath>, Error> {
+        let mut storage_paths = self.cache.cached_storage_paths(self)?;
+        storage_paths.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(storage_paths)
+    }
+
+    /// All the tags, sorted by name, from the cache shared with
+    /// [`Paperless::documents_detailed`]
     ///
-    /// The caller must ensure that path is valid, otherwise it will panic
-    fn url_api(&self, path: &str) -> Url {
-        self.root.join(path).unwrap()
+    /// They are loaded on first use and again once the time set with
+    /// [`PaperlessBuilder::cache_ttl`] has elapsed.
+    pub fn cached_tags(&self) -> Result<Vec<tag::Tag>, Error> {
+        let mut tags = self.cache.cached_tags(self)?;
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(tags)
+    }
+
+    /// Send a GET request and keep the content type of the response
+    fn file(&self, url: Url) -> Result<document::File, Error> {
+        let response = self.send(self.request(Method::GET, url))?;
+        let headers = response.headers().clone();
+        let content = response.bytes()?.to_vec();
+        Ok(document::File::from_headers(&headers, content))
+    }
+
+    /// Generate the url to download a version of a document
+    fn url_download(&self, id: document::Id, version: document::FileVersion) -> Result<Url, Error> {
+        let mut url = self.url_api(&routes::document_download(id))?;
+        version.insert_query(&mut url);
+        Ok(url)
+    }
+
+    /// Generate the url of an endpoint
+    fn url_api(&self, path: &str) -> Result<Url, Error> {
+        self.server.url_api(path)
     }
 
     /// List all the correspondents, in form of iterator to avoid loading everything
@@ -63,10 +424,10 @@ impl Paperless {
     pub fn correspondents(
         &self,
         filter: correspondent::Filter,
-    ) -> Paginated<correspondent::Correspondent> {
-        let mut url = self.url_api("correspondents/");
+    ) -> Result<Paginated<'_, correspondent::Correspondent>, Error> {
+        let mut url = self.server.url_list(routes::CORRESPONDENTS)?;
         filter.insert_query(&mut url);
-        Paginated::new(self, url)
+        Ok(Paginated::new(self, url))
     }
 
     /// List all the document types, in form of iterator to avoid loading everything
@@ -77,10 +438,10 @@ impl Paperless {
     pub fn document_types(
         &self,
         filter: document_type::Filter,
-    ) -> Paginated<document_type::DocumentType> {
-        let mut url = self.url_api("document_types/");
+    ) -> Result<Paginated<'_, document_type::DocumentType>, Error> {
+        let mut url = self.server.url_list(routes::DOCUMENT_TYPES)?;
         filter.insert_query(&mut url);
-        Paginated::new(self, url)
+        Ok(Paginated::new(self, url))
     }
 
     /// List all documents, in for of iterator to avoid loading everything
@@ -88,10 +449,184 @@ impl Paperless {
     /// # Arguments
     ///
     /// * `filter` - Filter to apply during the listing of all documents
-    pub fn documents(&self, filter: document::Filter) -> Paginated<document::Document> {
-        let mut url = self.url_api("documents/");
+    pub fn documents(
+        &self,
+        filter: document::Filter,
+    ) -> Result<Paginated<'_, document::Document>, Error> {
+        let mut url = self.server.url_list(routes::DOCUMENTS)?;
+        filter.insert_query(&mut url);
+        Ok(Paginated::new(self, url))
+    }
+
+    /// List the documents modified strictly after a date, from the oldest to the most recent
+    /// modification
+    ///
+    /// To poll for changes, keep the `modified` date of the last document as the next bound, or
+    /// use a [`SyncState`](crate::SyncState) with [`sync::incremental`](crate::sync::incremental)
+    /// to also see the documents added with an old modification date.
+    ///
+    /// # Arguments
+    ///
+    /// * `since` - Date of the last modification already seen
+    pub fn documents_modified_since(
+        &self,
+        since: date::DateTime,
+    ) -> Result<Paginated<'_, document::Document>, Error> {
+        self.documents(document::Filter {
+            modified_gt: Some(since),
+            ordering: vec![document::Ordering::Modified.ascending()],
+            ..Default::default()
+        })
+    }
+
+    /// List all documents with the names of their correspondent, document type, storage path and
+    /// tags
+    ///
+    /// Names are resolved with a cache of all correspondents, document types, storage paths and
+    /// tags, loaded on first use.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - Filter to apply during the listing of all documents
+    pub fn documents_detailed(
+        &self,
+        filter: document::Filter,
+    ) -> Result<impl Iterator<Item = Result<document::DocumentDetailed, Error>> + '_, Error> {
+        Ok(self
+            .documents(filter)?
+            .map(|document| self.document_detailed(document?)))
+    }
+
+    /// Resolve the names of the metadata of a document
+    fn document_detailed(
+        &self,
+        document: document::Document,
+    ) -> Result<document::DocumentDetailed, Error> {
+        let correspondent = document
+            .correspondent
+            .map(|id| self.cache.correspondent(self, id))
+            .transpose()?
+            .map(|c| c.name);
+        let document_type = document
+            .document_type
+            .map(|id| self.cache.document_type(self, id))
+            .transpose()?
+            .map(|t| t.name);
+        let storage_path = document
+            .storage_path
+            .map(|id| self.cache.storage_path(self, id))
+            .transpose()?
+            .map(|p| p.name);
+        let tags = document
+            .tags
+            .iter()
+            .map(|id| self.cache.tag(self, *id).map(|t| t.name))
+            .collect::<Result<_, _>>()?;
+        Ok(document::DocumentDetailed {
+            document,
+            correspondent,
+            document_type,
+            storage_path,
+            tags,
+        })
+    }
+
+    /// List all storage paths, in form of iterator to avoid loading everything
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - Filter to apply during the listing of all storage paths
+    pub fn storage_paths(
+        &self,
+        filter: storage_path::Filter,
+    ) -> Result<Paginated<'_, storage_path::StoragePath>, Error> {
+        let mut url = self.server.url_list(routes::STORAGE_PATHS)?;
         filter.insert_query(&mut url);
-        Paginated::new(self, url)
+        Ok(Paginated::new(self, url))
+    }
+
+    /// List all share links, in form of iterator to avoid loading everything
+    pub fn share_links(&self) -> Result<Paginated<'_, share_link::ShareLink>, Error> {
+        Ok(Paginated::new(
+            self,
+            self.server.url_list(routes::SHARE_LINKS)?,
+        ))
+    }
+
+    /// Get the share links of a document
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document
+    pub fn document_share_links(
+        &self,
+        id: document::Id,
+    ) -> Result<Vec<share_link::ShareLink>, Error> {
+        self.get(self.url_api(&routes::document_share_links(id))?)
+    }
+
+    /// Create a share link
+    ///
+    /// # Arguments
+    ///
+    /// * `share_link` - The share link to create
+    pub fn create_share_link(
+        &self,
+        share_link: &share_link::Create,
+    ) -> Result<share_link::ShareLink, Error> {
+        self.send_json(Method::POST, self.url_api(routes::SHARE_LINKS)?, share_link)
+    }
+
+    /// Delete a share link, the file is not accessible through it anymore
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the share link to delete
+    pub fn delete_share_link(&self, id: share_link::Id) -> Result<(), Error> {
+        self.delete(self.url_api(&routes::share_link(id))?)
+    }
+
+    /// Full url of a share link, to give to the people who need the document
+    ///
+    /// The web interface is expected next to the api: the share link of
+    /// `https://example.com/paperless/api/` is `https://example.com/paperless/share/<slug>`.
+    ///
+    /// # Arguments
+    ///
+    /// * `share_link` - The share link
+    pub fn share_link_url(&self, share_link: &share_link::ShareLink) -> Result<Url, Error> {
+        self.server
+            .url_ui(&routes::shared_document(&share_link.slug))
+    }
+
+    /// Search every kind of objects by their name, and documents by their title and content
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Text to search
+    pub fn search(&self, query: &str) -> Result<search::SearchResults, Error> {
+        let mut url = self.url_api(routes::SEARCH)?;
+        url.query_pairs_mut().append_pair("query", query);
+        self.get(url)
+    }
+
+    /// Search documents by their content, the most relevant first
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Full text query, with the syntax of the documents search of the interface
+    pub fn search_documents(
+        &self,
+        query: &str,
+    ) -> Result<search::DocumentResults<Paginated<'_, search::Hit>>, Error> {
+        let mut url = self.server.url_list(routes::DOCUMENTS)?;
+        url.query_pairs_mut().append_pair("query", query);
+        let mut first_page: PaginatedResult<search::Hit> = self.get(url.clone())?;
+        Ok(search::DocumentResults {
+            count: first_page.count,
+            corrected_query: first_page.corrected_query.take(),
+            documents: Paginated::with_first_page(self, url, first_page),
+        })
     }
 
     /// List all tags, in form of iterator to avoid loading everything
@@ -99,15 +634,48 @@ impl Paperless {
     /// # Arguments
     ///
     /// * `filters` - Filter to apply during the listing of all tags
-    pub fn tags(&self, filters: tag::Filter) -> Paginated<tag::Tag> {
-        let mut url = self.url_api("tags/");
+    pub fn tags(&self, filters: tag::Filter) -> Result<Paginated<'_, tag::Tag>, Error> {
+        let mut url = self.server.url_list(routes::TAGS)?;
+        filters.insert_query(&mut url);
+        Ok(Paginated::new(self, url))
+    }
+
+    /// List all users, in form of an iterator to avoid loading everything
+    ///
+    /// # Arguments
+    ///
+    /// * `filters` - Filter to apply during the listing of all users
+    pub fn users(&self, filters: user::Filter) -> Result<Paginated<'_, user::User>, Error> {
+        let mut url = self.server.url_list(routes::USERS)?;
+        filters.insert_query(&mut url);
+        Ok(Paginated::new(self, url))
+    }
+
+    /// List all groups of users, in form of an iterator to avoid loading everything
+    ///
+    /// # Arguments
+    ///
+    /// * `filters` - Filter to apply during the listing of all groups
+    pub fn groups(&self, filters: group::Filter) -> Result<Paginated<'_, group::Group>, Error> {
+        let mut url = self.server.url_list(routes::GROUPS)?;
         filters.insert_query(&mut url);
-        Paginated::new(self, url)
+        Ok(Paginated::new(self, url))
     }
 
     /// List all saved views, in form of an iterator to avoid load everything
-    pub fn saved_views(&self) -> Paginated<saved_view::SaveView> {
-        Paginated::new(self, self.url_api("saved_views/"))
+    pub fn saved_views(&self) -> Result<Paginated<'_, saved_view::SaveView>, Error> {
+        Ok(Paginated::new(
+            self,
+            self.server.url_list(routes::SAVED_VIEWS)?,
+        ))
+    }
+
+    /// List all custom fields, in form of iterator to avoid loading everything
+    pub fn custom_fields(&self) -> Result<Paginated<'_, custom_field::CustomField>, Error> {
+        Ok(Paginated::new(
+            self,
+            self.server.url_list(routes::CUSTOM_FIELDS)?,
+        ))
     }
 
     /// Get information about a correspondent
@@ -118,12 +686,17 @@ impl Paperless {
     pub fn correspondent(
         &self,
         id: correspondent::Id,
-    ) -> Result<correspondent::Correspondent, reqwest::Error> {
-        let request = self.request(
-            Method::GET,
-            self.url_api(&format!("correspondents/{}/", u64::from(id))),
-        );
-        self.http_client.execute(request)?.json()
+    ) -> Result<correspondent::Correspondent, Error> {
+        self.get(self.url_api(&routes::correspondent(id))?)
+    }
+
+    /// Get information about a custom field
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the custom field from which you are trying to retrieve information
+    pub fn custom_field(&self, id: custom_field::Id) -> Result<custom_field::CustomField, Error> {
+        self.get(self.url_api(&routes::custom_field(id))?)
     }
 
     /// Get information about a document_type
@@ -134,12 +707,8 @@ impl Paperless {
     pub fn document_type(
         &self,
         id: document_type::Id,
-    ) -> Result<document_type::DocumentType, reqwest::Error> {
-        let request = self.request(
-            Method::GET,
-            self.url_api(&format!("document_types/{}/", u64::from(id))),
-        );
-        self.http_client.execute(request)?.json()
+    ) -> Result<document_type::DocumentType, Error> {
+        self.get(self.url_api(&routes::document_type(id))?)
     }
 
     /// Get information about a document
@@ -147,12 +716,19 @@ impl Paperless {
     /// # Arguments
     ///
     /// * `id` - The id of the document from which you are trying to retrieve information
-    pub fn document(&self, id: document::Id) -> Result<document::Document, reqwest::Error> {
-        let request = self.request(
-            Method::GET,
-            self.url_api(&format!("documents/{}/", u64::from(id))),
-        );
-        self.http_client.execute(request)?.json()
+    pub fn document(&self, id: document::Id) -> Result<document::Document, Error> {
+        let document: document::Document = self.get(self.url_api(&routes::document(id))?)?;
+        self.cache.observe_title(document.id, &document.title);
+        Ok(document)
+    }
+
+    /// Get information about a storage path
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the storage path from which you are trying to retrieve information
+    pub fn storage_path(&self, id: storage_path::Id) -> Result<storage_path::StoragePath, Error> {
+        self.get(self.url_api(&routes::storage_path(id))?)
     }
 
     /// Get information about a tag
@@ -160,12 +736,40 @@ impl Paperless {
     /// # Arguments
     ///
     /// * `id` - The id of the tag from which you are trying to retrieve information
-    pub fn tag(&self, id: tag::Id) -> Result<tag::Tag, reqwest::Error> {
-        let request = self.request(
-            Method::GET,
-            self.url_api(&format!("tags/{}/", u64::from(id))),
-        );
-        self.http_client.execute(request)?.json()
+    pub fn tag(&self, id: tag::Id) -> Result<tag::Tag, Error> {
+        self.get(self.url_api(&routes::tag(id))?)
+    }
+
+    /// Get information about a user, for example the owner of a document
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the user
+    pub fn user(&self, id: user::Id) -> Result<user::User, Error> {
+        self.get(self.url_api(&routes::user(id))?)
+    }
+
+    /// Get information about a group of users
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the group
+    pub fn group(&self, id: group::Id) -> Result<group::Group, Error> {
+        self.get(self.url_api(&routes::group(id))?)
+    }
+
+    /// Find a group from its name, ignoring the case
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the group
+    pub fn group_by_name(&self, name: &str) -> Result<Option<group::Group>, Error> {
+        self.groups(group::Filter {
+            name_is: Some(name.to_string()),
+            ..Default::default()
+        })?
+        .next()
+        .transpose()
     }
 
     /// Get information about a view
@@ -173,40 +777,1442 @@ impl Paperless {
     /// # Arguments
     ///
     /// * `id` - The id of the view from which you are trying to retrieve information
-    pub fn saved_view(&self, id: saved_view::Id) -> Result<saved_view::SaveView, reqwest::Error> {
-        let request = self.request(
-            Method::GET,
-            self.url_api(&format!("saved_views/{}/", u64::from(id))),
-        );
-        self.http_client.execute(request)?.json()
-    }
-
-    pub fn document_size(&self, id: document::Id) -> usize {
-        let request = self.request(
-            Method::HEAD,
-            self.url_api(&format!("/documents/{}/download/", id.to_string())),
-        );
-        let r = self.http_client.execute(request).unwrap();
-
-        r.headers()
-            .get("content-length")
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .parse()
-            .unwrap()
-    }
-
-    pub fn document_download(&self, id: document::Id) -> Vec<u8> {
-        let request = self.request(
-            Method::GET,
-            self.url_api(&format!("/documents/{}/download/", id.to_string())),
-        );
-        self.http_client
-            .execute(request)
-            .unwrap()
-            .bytes()
-            .unwrap()
-            .to_vec()
+    pub fn saved_view(&self, id: saved_view::Id) -> Result<saved_view::SaveView, Error> {
+        self.get(self.url_api(&routes::saved_view(id))?)
+    }
+
+    /// Check if a correspondent exists
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the correspondent
+    pub fn correspondent_exists(&self, id: correspondent::Id) -> Result<bool, Error> {
+        self.exists(self.url_api(&routes::correspondent(id))?)
+    }
+
+    /// Check if a document type exists
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document type
+    pub fn document_type_exists(&self, id: document_type::Id) -> Result<bool, Error> {
+        self.exists(self.url_api(&routes::document_type(id))?)
+    }
+
+    /// Check if a document exists
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document
+    pub fn document_exists(&self, id: document::Id) -> Result<bool, Error> {
+        self.exists(self.url_api(&routes::document(id))?)
+    }
+
+    /// Check if a storage path exists
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the storage path
+    pub fn storage_path_exists(&self, id: storage_path::Id) -> Result<bool, Error> {
+        self.exists(self.url_api(&routes::storage_path(id))?)
+    }
+
+    /// Check if a tag exists
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the tag
+    pub fn tag_exists(&self, id: tag::Id) -> Result<bool, Error> {
+        self.exists(self.url_api(&routes::tag(id))?)
+    }
+
+    /// Check if a saved view exists
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the view
+    pub fn saved_view_exists(&self, id: saved_view::Id) -> Result<bool, Error> {
+        self.exists(self.url_api(&routes::saved_view(id))?)
+    }
+
+    /// Get the thumbnail of a document, an image of its first page
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document
+    pub fn document_thumbnail(&self, id: document::Id) -> Result<document::File, Error> {
+        self.file(self.url_api(&routes::document_thumbnail(id))?)
     }
+
+    /// Get the version of a document meant to be shown inline, the archived PDF when there is one
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document
+    pub fn document_preview(&self, id: document::Id) -> Result<document::File, Error> {
+        self.file(self.url_api(&routes::document_preview(id))?)
+    }
+
+    /// Get the size in bytes of a document, without downloading it
+    ///
+    /// The size is read with a HEAD request. If a proxy blocks them, the first byte of the file
+    /// or the metadata of the document are requested instead, and this method is remembered.
+    /// Encrypted documents (see [`document::Document::is_encrypted`]) can't be read partially,
+    /// their size is read from the metadata when the server sends the whole file instead of its
+    /// first byte.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document
+    /// * `version` - The version of the file
+    pub fn document_size(
+        &self,
+        id: document::Id,
+        version: document::FileVersion,
+    ) -> Result<usize, Error> {
+        let url = self.url_download(id, version)?;
+        match self.size_probe.get() {
+            Some(size::Method::Head) => return self.size_head(url),
+            Some(size::Method::Range) => {
+                return match self.size_range(url)? {
+                    Some(size) => Ok(size),
+                    None => self.size_metadata(id, version),
+                }
+            }
+            Some(size::Method::Metadata) => return self.size_metadata(id, version),
+            None => {}
+        }
+
+        let (method, result) = match self.size_head(url.clone()) {
+            Err(e) if size::is_blocked(&e) => match self.size_range(url) {
+                Err(e) if size::is_blocked(&e) => {
+                    (size::Method::Metadata, self.size_metadata(id, version))
+                }
+                // Nothing is learnt from an encrypted file, the next one may support ranges
+                Ok(None) => return self.size_metadata(id, version),
+                Ok(Some(size)) => (size::Method::Range, Ok(size)),
+                Err(e) => (size::Method::Range, Err(e)),
+            },
+            result => (size::Method::Head, result),
+        };
+        if result.is_ok() {
+            self.size_probe.set(method);
+        }
+        result
+    }
+
+    fn size_head(&self, url: Url) -> Result<usize, Error> {
+        let response = self.send(self.request(Method::HEAD, url))?;
+        size::from_content_length(response.headers())
+    }
+
+    fn size_range(&self, url: Url) -> Result<Option<usize>, Error> {
+        let mut request = self.request(Method::GET, url);
+        request
+            .headers_mut()
+            .insert(RANGE, HeaderValue::from_static(size::FIRST_BYTE));
+        let response = self.send(request)?;
+        size::from_content_range(response.status(), response.headers())
+    }
+
+    fn size_metadata(
+        &self,
+        id: document::Id,
+        version: document::FileVersion,
+    ) -> Result<usize, Error> {
+        size::from_metadata(self.document_metadata(id)?.size(version))
+    }
+
+    /// Get the checksums, sizes and types of the files of a document
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document
+    pub fn document_metadata(&self, id: document::Id) -> Result<document::Metadata, Error> {
+        self.get(self.url_api(&routes::document_metadata(id))?)
+    }
+
+    /// Download a document
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document to download
+    /// * `version` - The version of the file, the original one or the archived PDF
+    pub fn document_download(
+        &self,
+        id: document::Id,
+        version: document::FileVersion,
+    ) -> Result<Vec<u8>, Error> {
+        let request = self.request(Method::GET, self.url_download(id, version)?);
+        Ok(self.send(request)?.bytes()?.to_vec())
+    }
+
+    /// Download a document without loading it in memory
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document to download
+    /// * `version` - The version of the file, the original one or the archived PDF
+    pub fn document_download_stream(
+        &self,
+        id: document::Id,
+        version: document::FileVersion,
+    ) -> Result<document::Download, Error> {
+        let request = self.request(Method::GET, self.url_download(id, version)?);
+        Ok(document::Download(self.send(request)?))
+    }
+
+    /// Upload a new document
+    ///
+    /// The document is consumed in background by the server, this returns the id of the
+    /// consumption task.
+    ///
+    /// # Arguments
+    ///
+    /// * `upload` - The document to upload, and its metadata
+    pub fn upload_document(&self, upload: document::Upload) -> Result<String, Error> {
+        let request = self
+            .transport
+            .client
+            .post(self.url_api(routes::POST_DOCUMENT)?)
+            .headers(self.server.headers())
+            .multipart(upload.into_form())
+            .build()?;
+        let body = self.send(request)?.bytes()?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Check that no correspondent has this name yet, before trying to create one
+    ///
+    /// Names are compared ignoring the case, like the server does. Existing correspondents are
+    /// loaded once and kept in cache, so this can be used to validate a batch of names.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the correspondent to create
+    pub fn check_correspondent_name(&self, name: &str) -> Result<(), Error> {
+        match self.cache.correspondent_by_name(self, name)? {
+            Some(existing) => Err(Error::Conflict(existing.name)),
+            None => Ok(()),
+        }
+    }
+
+    /// Create a new correspondent
+    ///
+    /// # Arguments
+    ///
+    /// * `correspondent` - The correspondent to create
+    pub fn create_correspondent(
+        &self,
+        correspondent: &correspondent::Create,
+    ) -> Result<correspondent::Correspondent, Error> {
+        let created: correspondent::Correspondent = self.send_json(
+            Method::POST,
+            self.url_api(routes::CORRESPONDENTS)?,
+            correspondent,
+        )?;
+        self.cache
+            .correspondents
+            .update(created.id, Some(created.clone()));
+        Ok(created)
+    }
+
+    /// Modify a correspondent, and return its new version
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the correspondent to modify
+    /// * `update` - The modifications to apply, fields left to `None` are not modified
+    pub fn update_correspondent(
+        &self,
+        id: correspondent::Id,
+        update: &correspondent::Update,
+    ) -> Result<correspondent::Correspondent, Error> {
+        let updated: correspondent::Correspondent = self.send_json(
+            Method::PATCH,
+            self.url_api(&routes::correspondent(id))?,
+            update,
+        )?;
+        self.cache.correspondents.update(id, Some(updated.clone()));
+        Ok(updated)
+    }
+
+    /// Delete a correspondent, the documents assigned to it are kept without correspondent
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the correspondent to delete
+    pub fn delete_correspondent(&self, id: correspondent::Id) -> Result<(), Error> {
+        self.delete(self.url_api(&routes::correspondent(id))?)?;
+        self.cache.correspondents.update(id, None);
+        Ok(())
+    }
+
+    /// Create a new custom field
+    ///
+    /// # Arguments
+    ///
+    /// * `custom_field` - The custom field to create
+    pub fn create_custom_field(
+        &self,
+        custom_field: &custom_field::Create,
+    ) -> Result<custom_field::CustomField, Error> {
+        let created: custom_field::CustomField = self.send_json(
+            Method::POST,
+            self.url_api(routes::CUSTOM_FIELDS)?,
+            custom_field,
+        )?;
+        self.cache
+            .custom_fields
+            .update(created.id, Some(created.clone()));
+        Ok(created)
+    }
+
+    /// Delete a custom field, its values are removed from all documents
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the custom field to delete
+    pub fn delete_custom_field(&self, id: custom_field::Id) -> Result<(), Error> {
+        self.delete(self.url_api(&routes::custom_field(id))?)?;
+        self.cache.custom_fields.update(id, None);
+        Ok(())
+    }
+
+    /// Load again all the custom fields, and return them
+    ///
+    /// Custom fields are cached to decode the values of the documents, and the cache is only
+    /// dropped when the version of the server changes. Use this if they were modified by someone
+    /// else.
+    pub fn refresh_custom_fields(&self) -> Result<Vec<custom_field::CustomField>, Error> {
+        let fields = Cache::all_custom_fields(self)?;
+        self.cache.custom_fields.replace(fields.clone());
+        Ok(fields.into_iter().map(|(_, f)| f).collect())
+    }
+
+    /// Typed value of a custom field of a document, `None` if the field has no value
+    ///
+    /// The type of the field is read from a cache of all the custom fields, loaded on first use.
+    ///
+    /// # Arguments
+    ///
+    /// * `instance` - The custom field of the document
+    pub fn custom_field_value(
+        &self,
+        instance: &custom_field::Instance,
+    ) -> Result<Option<custom_field::Value>, Error> {
+        let field = self.cache.custom_field(self, instance.field)?;
+        instance.value(field.data_type)
+    }
+
+    /// Create a new document type
+    ///
+    /// # Arguments
+    ///
+    /// * `document_type` - The document type to create
+    pub fn create_document_type(
+        &self,
+        document_type: &document_type::Create,
+    ) -> Result<document_type::DocumentType, Error> {
+        let created: document_type::DocumentType = self.send_json(
+            Method::POST,
+            self.url_api(routes::DOCUMENT_TYPES)?,
+            document_type,
+        )?;
+        self.cache
+            .document_types
+            .update(created.id, Some(created.clone()));
+        Ok(created)
+    }
+
+    /// Modify a document type, and return its new version
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document type to modify
+    /// * `update` - The modifications to apply, fields left to `None` are not modified
+    pub fn update_document_type(
+        &self,
+        id: document_type::Id,
+        update: &document_type::Update,
+    ) -> Result<document_type::DocumentType, Error> {
+        let updated: document_type::DocumentType = self.send_json(
+            Method::PATCH,
+            self.url_api(&routes::document_type(id))?,
+            update,
+        )?;
+        self.cache.document_types.update(id, Some(updated.clone()));
+        Ok(updated)
+    }
+
+    /// Delete a document type
+    ///
+    /// The documents which have this type are kept, without document type.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document type to delete
+    pub fn delete_document_type(&self, id: document_type::Id) -> Result<(), Error> {
+        self.delete(self.url_api(&routes::document_type(id))?)?;
+        self.cache.document_types.update(id, None);
+        Ok(())
+    }
+
+    /// Create a new storage path
+    ///
+    /// # Arguments
+    ///
+    /// * `storage_path` - The storage path to create
+    pub fn create_storage_path(
+        &self,
+        storage_path: &storage_path::Create,
+    ) -> Result<storage_path::StoragePath, Error> {
+        let created: storage_path::StoragePath = self.send_json(
+            Method::POST,
+            self.url_api(routes::STORAGE_PATHS)?,
+            storage_path,
+        )?;
+        self.cache
+            .storage_paths
+            .update(created.id, Some(created.clone()));
+        Ok(created)
+    }
+
+    /// Modify a storage path, and return its new version
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the storage path to modify
+    /// * `update` - The modifications to apply, fields left to `None` are not modified
+    pub fn update_storage_path(
+        &self,
+        id: storage_path::Id,
+        update: &storage_path::Update,
+    ) -> Result<storage_path::StoragePath, Error> {
+        let updated: storage_path::StoragePath = self.send_json(
+            Method::PATCH,
+            self.url_api(&routes::storage_path(id))?,
+            update,
+        )?;
+        self.cache.storage_paths.update(id, Some(updated.clone()));
+        Ok(updated)
+    }
+
+    /// Delete a storage path, the files of its documents are moved to the default location
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the storage path to delete
+    pub fn delete_storage_path(&self, id: storage_path::Id) -> Result<(), Error> {
+        self.delete(self.url_api(&routes::storage_path(id))?)?;
+        self.cache.storage_paths.update(id, None);
+        Ok(())
+    }
+
+    /// Move all the documents of a tag to another one, then delete the first tag
+    ///
+    /// If it fails after modifying some documents, the error is an [`Error::Interrupted`] with the
+    /// modifications already done. Fails with [`Error::InvalidArgument`] if both tags are the same,
+    /// before sending any request.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The tag to remove
+    /// * `into` - The tag to add instead of `from`
+    pub fn merge_tags(&self, from: tag::Id, into: tag::Id) -> Result<(), Error> {
+        if from == into {
+            return Err(Error::InvalidArgument(format!(
+                "can't merge the tag {from} into itself"
+            )));
+        }
+        let mut rollback = Rollback::default();
+        self.merge_tags_steps(from, into, &mut rollback)
+            .map_err(|e| e.interrupted(rollback))
+    }
+
+    fn merge_tags_steps(
+        &self,
+        from: tag::Id,
+        into: tag::Id,
+        rollback: &mut Rollback,
+    ) -> Result<(), Error> {
+        let tag = self.tag(from)?;
+        // Documents are collected first, as modifying them changes the pages of the listing
+        let documents = self
+            .documents(document::Filter {
+                tag_id: Some(from),
+                ..Default::default()
+            })?
+            .collect::<Result<Vec<_>, Error>>()?;
+        for document in documents {
+            let mut tags: Vec<_> = document
+                .tags
+                .iter()
+                .copied()
+                .filter(|t| *t != from)
+                .collect();
+            if !tags.contains(&into) {
+                tags.push(into);
+            }
+            self.update_document(
+                document.id,
+                &document::Update {
+                    tags: Some(tags),
+                    ..Default::default()
+                },
+            )?;
+            rollback.record(Step::DocumentTags {
+                document: document.id,
+                previous: document.tags,
+            });
+        }
+        self.delete_tag(from)?;
+        rollback.record(Step::TagDeleted(tag));
+        Ok(())
+    }
+
+    /// Replace the file of a document by a new one, and return the id of the new document
+    ///
+    /// The new file is uploaded with the metadata of the document, then the document is deleted
+    /// and its archive serial number, storage path and custom fields are moved to the new one. If
+    /// it fails after the upload, the error is an [`Error::Interrupted`] with the steps already
+    /// done.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The document to replace
+    /// * `file_name` - Name of the new file, the server uses its extension to detect the file type
+    /// * `content` - Content of the new file
+    /// * `timeout` - Maximum time to wait for the consumption of the new file
+    pub fn replace_document(
+        &self,
+        id: document::Id,
+        file_name: &str,
+        content: Vec<u8>,
+        timeout: Duration,
+    ) -> Result<document::Id, Error> {
+        let mut rollback = Rollback::default();
+        self.replace_document_steps(id, file_name, content, timeout, &mut rollback)
+            .map_err(|e| e.interrupted(rollback))
+    }
+
+    fn replace_document_steps(
+        &self,
+        id: document::Id,
+        file_name: &str,
+        content: Vec<u8>,
+        timeout: Duration,
+        rollback: &mut Rollback,
+    ) -> Result<document::Id, Error> {
+        let document = self.document(id)?;
+        let upload = document::Upload {
+            title: Some(document.title.clone()),
+            created: Some(document.created),
+            correspondent: document.correspondent,
+            document_type: document.document_type,
+            tags: document.tags.clone(),
+            ..document::Upload::from_bytes(file_name, content)
+        };
+        let replacement = self.consume(upload, timeout, rollback)?;
+
+        // The archive serial number is unique, it can only be moved once the document is deleted
+        self.delete_document(id)?;
+        rollback.record(Step::DocumentDeleted(id));
+        self.update_document(
+            replacement,
+            &document::Update {
+                storage_path: Some(document.storage_path),
+                archive_serial_number: Some(document.archive_serial_number),
+                custom_fields: Some(document.custom_fields),
+                ..Default::default()
+            },
+        )?;
+        Ok(replacement)
+    }
+
+    /// Move a document to another server, and return its id on this server
+    ///
+    /// The original file is uploaded to `target` with the title and the creation date of the
+    /// document, the other metadata are not copied as their ids differ between servers. The
+    /// document is then deleted from this server. If it fails after the upload, the error is an
+    /// [`Error::Interrupted`] with the steps already done, the created document being on `target`.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The document to move
+    /// * `target` - Client of the server receiving the document
+    /// * `timeout` - Maximum time to wait for the consumption of the file by `target`
+    pub fn migrate_document(
+        &self,
+        id: document::Id,
+        target: &Paperless,
+        timeout: Duration,
+    ) -> Result<document::Id, Error> {
+        let mut rollback = Rollback::default();
+        self.migrate_document_steps(id, target, timeout, &mut rollback)
+            .map_err(|e| e.interrupted(rollback))
+    }
+
+    fn migrate_document_steps(
+        &self,
+        id: document::Id,
+        target: &Paperless,
+        timeout: Duration,
+        rollback: &mut Rollback,
+    ) -> Result<document::Id, Error> {
+        let document = self.document(id)?;
+        let file = self.file(self.url_download(id, document::FileVersion::Original)?)?;
+        // The consumer of the target picks the parser from the extension, the title is only used
+        // as the name when the server sends none
+        let file_name = match (document.original_file_name.clone(), file.file_name.clone()) {
+            (Some(name), _) | (None, Some(name)) => name,
+            (None, None) => match file.extension() {
+                Some(extension) => format!("{}.{extension}", document.title),
+                None => document.title.clone(),
+            },
+        };
+        let upload = document::Upload {
+            title: Some(document.title),
+            created: Some(document.created),
+            ..document::Upload::from_bytes(&file_name, file.content)
+        };
+        let migrated = target.consume(upload, timeout, rollback)?;
+        self.delete_document(id)?;
+        rollback.record(Step::DocumentDeleted(id));
+        Ok(migrated)
+    }
+
+    /// Upload a document and wait for its consumption, recording the steps
+    fn consume(
+        &self,
+        upload: document::Upload,
+        timeout: Duration,
+        rollback: &mut Rollback,
+    ) -> Result<document::Id, Error> {
+        let task = self.upload_document(upload)?;
+        rollback.record(Step::DocumentUploaded(task.clone()));
+        let created = self.wait_for_consumption(&task, timeout)?;
+        rollback.record(Step::DocumentCreated(created));
+        Ok(created)
+    }
+
+    /// Create a new tag
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - The tag to create
+    pub fn create_tag(&self, tag: &tag::Create) -> Result<tag::Tag, Error> {
+        let created: tag::Tag = self.send_json(Method::POST, self.url_api(routes::TAGS)?, tag)?;
+        self.cache.tags.update(created.id, Some(created.clone()));
+        Ok(created)
+    }
+
+    /// Modify a tag, and return its new version
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the tag to modify
+    /// * `update` - The modifications to apply, fields left to `None` are not modified
+    pub fn update_tag(&self, id: tag::Id, update: &tag::Update) -> Result<tag::Tag, Error> {
+        let updated: tag::Tag =
+            self.send_json(Method::PATCH, self.url_api(&routes::tag(id))?, update)?;
+        self.cache.tags.update(id, Some(updated.clone()));
+        Ok(updated)
+    }
+
+    /// Delete a tag, it is removed from all the documents
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the tag to delete
+    pub fn delete_tag(&self, id: tag::Id) -> Result<(), Error> {
+        self.delete(self.url_api(&routes::tag(id))?)?;
+        self.cache.tags.update(id, None);
+        Ok(())
+    }
+
+    /// List all workflows, in form of an iterator to avoid loading everything
+    pub fn workflows(&self) -> Result<Paginated<'_, workflow::Workflow>, Error> {
+        Ok(Paginated::new(
+            self,
+            self.server.url_list(routes::WORKFLOWS)?,
+        ))
+    }
+
+    /// Get a workflow, with its triggers and actions
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the workflow
+    pub fn workflow(&self, id: workflow::Id) -> Result<workflow::Workflow, Error> {
+        self.get(self.url_api(&routes::workflow(id))?)
+    }
+
+    /// Create a new workflow
+    ///
+    /// # Arguments
+    ///
+    /// * `workflow` - The workflow to create
+    pub fn create_workflow(
+        &self,
+        workflow: &workflow::Create,
+    ) -> Result<workflow::Workflow, Error> {
+        self.send_json(Method::POST, self.url_api(routes::WORKFLOWS)?, workflow)
+    }
+
+    /// Modify a workflow, and return its new version
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the workflow to modify
+    /// * `update` - The modifications to apply, fields left to `None` are not modified
+    pub fn update_workflow(
+        &self,
+        id: workflow::Id,
+        update: &workflow::Update,
+    ) -> Result<workflow::Workflow, Error> {
+        self.send_json(Method::PATCH, self.url_api(&routes::workflow(id))?, update)
+    }
+
+    /// Delete a workflow, with its triggers and actions
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the workflow to delete
+    pub fn delete_workflow(&self, id: workflow::Id) -> Result<(), Error> {
+        self.delete(self.url_api(&routes::workflow(id))?)
+    }
+
+    /// List all mail accounts, in form of an iterator to avoid loading everything
+    pub fn mail_accounts(&self) -> Result<Paginated<'_, mail_account::MailAccount>, Error> {
+        Ok(Paginated::new(
+            self,
+            self.server.url_list(routes::MAIL_ACCOUNTS)?,
+        ))
+    }
+
+    /// Get a mail account, its password is hidden by the server
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the mail account
+    pub fn mail_account(&self, id: mail_account::Id) -> Result<mail_account::MailAccount, Error> {
+        self.get(self.url_api(&routes::mail_account(id))?)
+    }
+
+    /// Create a new mail account
+    ///
+    /// # Arguments
+    ///
+    /// * `mail_account` - The mail account to create
+    pub fn create_mail_account(
+        &self,
+        mail_account: &mail_account::Create,
+    ) -> Result<mail_account::MailAccount, Error> {
+        self.send_json(
+            Method::POST,
+            self.url_api(routes::MAIL_ACCOUNTS)?,
+            mail_account,
+        )
+    }
+
+    /// Modify a mail account, and return its new version
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the mail account to modify
+    /// * `update` - The modifications to apply, fields left to `None` are not modified
+    pub fn update_mail_account(
+        &self,
+        id: mail_account::Id,
+        update: &mail_account::Update,
+    ) -> Result<mail_account::MailAccount, Error> {
+        self.send_json(
+            Method::PATCH,
+            self.url_api(&routes::mail_account(id))?,
+            update,
+        )
+    }
+
+    /// Delete a mail account, with its mail rules
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the mail account to delete
+    pub fn delete_mail_account(&self, id: mail_account::Id) -> Result<(), Error> {
+        self.delete(self.url_api(&routes::mail_account(id))?)
+    }
+
+    /// Check that the server can connect to a mailbox, and return whether it succeeded
+    ///
+    /// # Arguments
+    ///
+    /// * `mail_account` - The settings of the mailbox
+    /// * `id` - The id of the saved account, whose password is used if `mail_account` has the
+    ///   hidden password sent by the server
+    pub fn test_mail_account(
+        &self,
+        mail_account: &mail_account::Create,
+        id: Option<mail_account::Id>,
+    ) -> Result<bool, Error> {
+        let mut body = serde_json::to_value(mail_account)?;
+        if let Some(id) = id {
+            body["id"] = serde_json::json!(id);
+        }
+        match self.send_json::<_, IgnoredAny>(
+            Method::POST,
+            self.url_api(routes::MAIL_ACCOUNT_TEST)?,
+            &body,
+        ) {
+            Ok(_) => Ok(true),
+            // The server answers 400 when it can't connect
+            Err(Error::Status(StatusCode::BAD_REQUEST) | Error::Validation(_)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// List all mail rules, in form of an iterator to avoid loading everything
+    pub fn mail_rules(&self) -> Result<Paginated<'_, mail_rule::MailRule>, Error> {
+        Ok(Paginated::new(
+            self,
+            self.server.url_list(routes::MAIL_RULES)?,
+        ))
+    }
+
+    /// Get a mail rule
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the mail rule
+    pub fn mail_rule(&self, id: mail_rule::Id) -> Result<mail_rule::MailRule, Error> {
+        self.get(self.url_api(&routes::mail_rule(id))?)
+    }
+
+    /// Create a new mail rule
+    ///
+    /// # Arguments
+    ///
+    /// * `mail_rule` - The mail rule to create
+    pub fn create_mail_rule(
+        &self,
+        mail_rule: &mail_rule::Create,
+    ) -> Result<mail_rule::MailRule, Error> {
+        self.send_json(Method::POST, self.url_api(routes::MAIL_RULES)?, mail_rule)
+    }
+
+    /// Modify a mail rule, and return its new version
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the mail rule to modify
+    /// * `update` - The modifications to apply, fields left to `None` are not modified
+    pub fn update_mail_rule(
+        &self,
+        id: mail_rule::Id,
+        update: &mail_rule::Update,
+    ) -> Result<mail_rule::MailRule, Error> {
+        self.send_json(Method::PATCH, self.url_api(&routes::mail_rule(id))?, update)
+    }
+
+    /// Delete a mail rule
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the mail rule to delete
+    pub fn delete_mail_rule(&self, id: mail_rule::Id) -> Result<(), Error> {
+        self.delete(self.url_api(&routes::mail_rule(id))?)
+    }
+
+    /// Modify a document, and return its new version
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document to modify
+    /// * `update` - The modifications to apply, fields left to `None` are not modified
+    pub fn update_document(
+        &self,
+        id: document::Id,
+        update: &document::Update,
+    ) -> Result<document::Document, Error> {
+        let updated: document::Document =
+            self.send_json(Method::PATCH, self.url_api(&routes::document(id))?, update)?;
+        self.cache.observe_title(updated.id, &updated.title);
+        Ok(updated)
+    }
+
+    /// Previous titles of a document, from the oldest to the most recent
+    ///
+    /// They are read from the audit log of the server when it is enabled. Otherwise, only the
+    /// renames seen by this client, through [`Paperless::document`] and
+    /// [`Paperless::update_document`], are known.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document
+    pub fn previous_titles(&self, id: document::Id) -> Result<Vec<String>, Error> {
+        match self.document_history(id) {
+            Ok(entries) => {
+                // The most recent changes come first
+                let mut titles: Vec<String> = entries
+                    .iter()
+                    .rev()
+                    .filter_map(|e| match e.change("title")? {
+                        history::Change::Replaced(old, _) => Some(old.as_str()?.to_string()),
+                        history::Change::Other(_) => None,
+                    })
+                    .collect();
+                titles.dedup();
+                Ok(titles)
+            }
+            // Servers without audit log
+            Err(Error::NotFound) => Ok(self.cache.previous_titles(id)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Modifications of a document, the most recent first
+    ///
+    /// Fails with [`Error::NotFound`] if the audit log is disabled on the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document
+    pub fn document_history(&self, id: document::Id) -> Result<Vec<history::Entry>, Error> {
+        self.get(self.url_api(&routes::document_history(id))?)
+    }
+
+    /// Replace all the custom fields of a document, and return its new version
+    ///
+    /// Fields missing from `values` are removed from the document. Fails with
+    /// [`Error::InvalidArgument`] if a float or an amount is not finite, before sending any
+    /// request.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document to modify
+    /// * `values` - The custom fields and their value, `None` to add a field without value
+    pub fn set_custom_fields(
+        &self,
+        id: document::Id,
+        values: Vec<(custom_field::Id, Option<custom_field::Value>)>,
+    ) -> Result<document::Document, Error> {
+        self.update_document(
+            id,
+            &document::Update {
+                custom_fields: Some(
+                    values
+                        .into_iter()
+                        .map(|(field, value)| custom_field::Instance::new(field, value))
+                        .collect::<Result<_, _>>()?,
+                ),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Get the text of a page of a document, `None` if the document has less pages
+    ///
+    /// See [`document::Document::pages`] for how pages are found.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document
+    /// * `page` - Number of the page, starting at 1
+    pub fn document_page_text(
+        &self,
+        id: document::Id,
+        page: usize,
+    ) -> Result<Option<String>, Error> {
+        let document = self.document(id)?;
+        Ok(page
+            .checked_sub(1)
+            .and_then(|index| document.pages().get(index).map(|p| p.to_string())))
+    }
+
+    /// Apply an operation to several documents at once
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The ids of the documents to modify
+    /// * `operation` - The operation to apply
+    pub fn bulk_edit(
+        &self,
+        ids: &[document::Id],
+        operation: bulk_edit::Operation,
+    ) -> Result<(), Error> {
+        // The server only answers `{"result": "OK"}`, the tasks it starts are not identified
+        let body = serde_json::json!({
+            "documents": ids,
+            "method": operation.method(),
+            "parameters": operation.parameters(),
+        });
+        let _: serde_json::Value =
+            self.send_json(Method::POST, self.url_api(routes::BULK_EDIT)?, &body)?;
+        Ok(())
+    }
+
+    /// Set the owner and the permissions of several documents
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The ids of the documents to modify
+    /// * `owner` - The new owner, `None` to remove it
+    /// * `permissions` - The users and groups allowed to see and modify the documents
+    /// * `merge` - Add to the current permissions instead of replacing them, the owner is then
+    ///   only set on documents without owner
+    pub fn set_document_permissions(
+        &self,
+        ids: &[document::Id],
+        owner: Option<user::Id>,
+        permissions: permissions::Permissions,
+        merge: bool,
+    ) -> Result<(), Error> {
+        self.bulk_edit(
+            ids,
+            bulk_edit::Operation::SetPermissions {
+                owner,
+                permissions,
+                merge,
+            },
+        )
+    }
+
+    /// Merge documents into a new one, in the given order
+    ///
+    /// The new document is created in background by the server, which doesn't send the id of the
+    /// task creating it: no handle is available to wait for it, [`Paperless::tasks`] lists it
+    /// with the other tasks.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The ids of the documents to merge
+    /// * `metadata_from` - The document from which the metadata of the new document are copied,
+    ///   none are set if `None`
+    /// * `delete_originals` - Delete the merged documents once the new one is created
+    pub fn merge_documents(
+        &self,
+        ids: &[document::Id],
+        metadata_from: Option<document::Id>,
+        delete_originals: bool,
+    ) -> Result<(), Error> {
+        self.bulk_edit(
+            ids,
+            bulk_edit::Operation::Merge {
+                metadata_document: metadata_from,
+                delete_originals,
+            },
+        )
+    }
+
+    /// Split a document, each range of pages becomes a new document
+    ///
+    /// The new documents are created in background by the server, without handle to wait for
+    /// them, like [`Paperless::merge_documents`].
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document to split
+    /// * `pages` - The pages of each new document
+    /// * `delete_original` - Delete the document once the new ones are created
+    pub fn split_document(
+        &self,
+        id: document::Id,
+        pages: Vec<bulk_edit::PageRange>,
+        delete_original: bool,
+    ) -> Result<(), Error> {
+        if pages.is_empty() {
+            return Err(Error::InvalidArgument("no pages to split".to_string()));
+        }
+        self.bulk_edit(
+            &[id],
+            bulk_edit::Operation::Split {
+                pages,
+                delete_originals: delete_original,
+            },
+        )
+    }
+
+    /// Rotate all the pages of a document, clockwise
+    ///
+    /// The document is modified in background by the server, without handle to wait for it, like
+    /// [`Paperless::merge_documents`].
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document to rotate
+    /// * `degrees` - The rotation, 90, 180 or 270 degrees
+    pub fn rotate_document(&self, id: document::Id, degrees: u16) -> Result<(), Error> {
+        let rotation = bulk_edit::Rotation::try_from(degrees)?;
+        self.bulk_edit(&[id], bulk_edit::Operation::Rotate(rotation))
+    }
+
+    /// Delete pages of a document
+    ///
+    /// The document is modified in background by the server, without handle to wait for it, like
+    /// [`Paperless::merge_documents`].
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document to modify
+    /// * `pages` - The pages to delete, starting at 1
+    pub fn delete_pages(&self, id: document::Id, pages: Vec<u32>) -> Result<(), Error> {
+        if pages.is_empty() {
+            return Err(Error::InvalidArgument("no pages to delete".to_string()));
+        }
+        if pages.contains(&0) {
+            return Err(Error::InvalidArgument("pages start at 1".to_string()));
+        }
+        self.bulk_edit(&[id], bulk_edit::Operation::DeletePages(pages))
+    }
+
+    /// Send a document by email, from the server
+    ///
+    /// The server must have an email server configured.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document to send
+    /// * `addresses` - The addresses of the recipients
+    /// * `subject` - The subject of the email
+    /// * `message` - The body of the email
+    /// * `use_archive_version` - Attach the archived PDF instead of the original file
+    pub fn email_document(
+        &self,
+        id: document::Id,
+        addresses: &[&str],
+        subject: &str,
+        message: &str,
+        use_archive_version: bool,
+    ) -> Result<(), Error> {
+        if addresses.is_empty() {
+            return Err(Error::InvalidArgument("no recipient".to_string()));
+        }
+        let body = serde_json::json!({
+            "addresses": addresses.join(","),
+            "subject": subject,
+            "message": message,
+            "use_archive_version": use_archive_version,
+        });
+        let _: IgnoredAny = self.send_json(
+            Method::POST,
+            self.url_api(&routes::document_email(id))?,
+            &body,
+        )?;
+        Ok(())
+    }
+
+    /// Get the notes of a document
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document
+    pub fn document_notes(&self, id: document::Id) -> Result<Vec<note::Note>, Error> {
+        self.get(self.url_api(&routes::document_notes(id))?)
+    }
+
+    /// Add a note on a document, and return all the notes of the document
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document
+    /// * `text` - Content of the note
+    pub fn add_note(&self, id: document::Id, text: &str) -> Result<Vec<note::Note>, Error> {
+        self.send_json(
+            Method::POST,
+            self.url_api(&routes::document_notes(id))?,
+            &serde_json::json!({ "note": text }),
+        )
+    }
+
+    /// Delete a note of a document, and return the remaining notes of the document
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document
+    /// * `note_id` - The id of the note to delete
+    pub fn delete_note(
+        &self,
+        id: document::Id,
+        note_id: note::Id,
+    ) -> Result<Vec<note::Note>, Error> {
+        let mut url = self.url_api(&routes::document_notes(id))?;
+        url.query_pairs_mut()
+            .append_pair("id", &note_id.to_string());
+        let body = self.send(self.request(Method::DELETE, url))?.bytes()?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Delete a document
+    ///
+    /// Fails with [`Error::NotFound`] if the document doesn't exist, and with [`Error::Forbidden`]
+    /// if the user is not allowed to delete it.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the document to delete
+    pub fn delete_document(&self, id: document::Id) -> Result<(), Error> {
+        self.delete(self.url_api(&routes::document(id))?)
+    }
+
+    /// List the documents in the trash, in form of an iterator to avoid loading everything
+    pub fn trash(&self) -> Result<Paginated<'_, trash::Deleted>, Error> {
+        Ok(Paginated::new(self, self.server.url_list(routes::TRASH)?))
+    }
+
+    /// Move documents from the trash back to the archive
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The ids of the documents to restore
+    pub fn restore_documents(&self, ids: &[document::Id]) -> Result<(), Error> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        self.trash_action("restore", Some(ids))
+    }
+
+    /// Permanently delete documents from the trash
+    ///
+    /// # Arguments
+    ///
+    /// * `selection` - The documents to delete, or all the documents in the trash
+    pub fn empty_trash(&self, selection: trash::Selection) -> Result<(), Error> {
+        match selection {
+            trash::Selection::Documents(ids) if ids.is_empty() => Ok(()),
+            trash::Selection::Documents(ids) => self.trash_action("empty", Some(&ids)),
+            trash::Selection::All => self.trash_action("empty", None),
+        }
+    }
+
+    /// Apply an action to documents of the trash, the server applies it to all of them if `ids`
+    /// is `None`
+    fn trash_action(&self, action: &str, ids: Option<&[document::Id]>) -> Result<(), Error> {
+        let mut body = serde_json::json!({ "action": action });
+        if let Some(ids) = ids {
+            body["documents"] = serde_json::json!(ids);
+        }
+        let _: IgnoredAny = self.send_json(Method::POST, self.url_api(routes::TRASH)?, &body)?;
+        Ok(())
+    }
+
+    /// Get a task from its uuid
+    ///
+    /// Returns `None` if the server doesn't know this task (yet).
+    ///
+    /// # Arguments
+    ///
+    /// * `uuid` - The uuid of the task, as returned by [`Paperless::upload_document`]
+    pub fn task(&self, uuid: &str) -> Result<Option<task::Task>, Error> {
+        let tasks = self.tasks(task::Filter {
+            task_id: Some(uuid.to_string()),
+            ..Default::default()
+        })?;
+        Ok(tasks.into_iter().next())
+    }
+
+    /// List the tasks known by the server, the most recent first
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - Filter to apply on the tasks
+    pub fn tasks(&self, filter: task::Filter) -> Result<Vec<task::Task>, Error> {
+        let mut url = self.url_api(routes::TASKS)?;
+        filter.insert_query(&mut url);
+        let mut tasks: Vec<task::Task> = self.get(url)?;
+        // Older servers ignore some filters
+        tasks.retain(|t| filter.matches(t));
+        tasks.sort_by_key(|t| std::cmp::Reverse(t.date_created));
+        Ok(tasks)
+    }
+
+    /// Mark tasks as handled, so that they are not reported as failures anymore, and return the
+    /// number of tasks acknowledged
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The ids of the tasks, not their uuid
+    pub fn acknowledge_tasks(&self, ids: &[task::Id]) -> Result<u64, Error> {
+        #[derive(serde::Deserialize)]
+        struct Response {
+            result: u64,
+        }
+
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let response: Response = self.send_json(
+            Method::POST,
+            self.url_api(routes::TASKS_ACKNOWLEDGE)?,
+            &serde_json::json!({ "tasks": ids }),
+        )?;
+        Ok(response.result)
+    }
+
+    /// Wait for the consumption of an uploaded document, and return the id of the new document
+    ///
+    /// # Arguments
+    ///
+    /// * `uuid` - The uuid of the consumption task, as returned by [`Paperless::upload_document`]
+    /// * `timeout` - Maximum time to wait for the task to finish
+    pub fn wait_for_consumption(
+        &self,
+        uuid: &str,
+        timeout: Duration,
+    ) -> Result<document::Id, Error> {
+        const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(task) = self.task(uuid)? {
+                match task.status {
+                    task::Status::Success => {
+                        return task
+                            .related_document
+                            .ok_or_else(|| Error::TaskFailed(task.result.unwrap_or_default()))
+                    }
+                    task::Status::Failure | task::Status::Revoked => {
+                        return Err(Error::TaskFailed(task.result.unwrap_or_default()))
+                    }
+                    task::Status::Pending | task::Status::Started | task::Status::Retry => {}
+                }
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(Error::Timeout);
+            }
+            thread::sleep(POLL_INTERVAL.min(deadline - now));
+        }
+    }
+
+    /// Get the next archive serial number, after the highest one used by the documents
+    ///
+    /// The number is not reserved, two calls made before a document uses it return the same one.
+    pub fn next_asn(&self) -> Result<asn::ASN, Error> {
+        self.get(self.url_api(routes::DOCUMENTS_NEXT_ASN)?)
+    }
+
+    /// Get global statistics about the documents
+    pub fn statistics(&self) -> Result<statistics::Statistics, Error> {
+        self.get(self.url_api(routes::STATISTICS)?)
+    }
+
+    /// Get the latest release of paperless, the server must be allowed to check for updates
+    pub fn remote_version(&self) -> Result<version::RemoteVersion, Error> {
+        self.get(self.url_api(routes::REMOTE_VERSION)?)
+    }
+
+    /// List the names of the log files of the server, like `paperless` or `mail`, only available
+    /// to administrators
+    pub fn logs(&self) -> Result<Vec<String>, Error> {
+        self.get(self.url_api(routes::LOGS)?)
+    }
+
+    /// Get the lines of a log file of the server, oldest first
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the log file, as returned by [`Paperless::logs`], the `.log` extension
+    ///   is optional. Names which are not a plain file name, such as `../tags`, fail with
+    ///   [`Error::InvalidArgument`]
+    pub fn log(&self, name: &str) -> Result<Vec<String>, Error> {
+        let name = name.strip_suffix(".log").unwrap_or(name);
+        self.get(self.url_api(&routes::log(name)?)?)
+    }
+
+    /// Get the settings of the interface and the permissions of the current user
+    pub fn ui_settings(&self) -> Result<ui_settings::UiSettings, Error> {
+        self.get(self.url_api(routes::UI_SETTINGS)?)
+    }
+
+    /// Get the configuration of the application, only available to administrators
+    pub fn config(&self) -> Result<config::Config, Error> {
+        let configs: Vec<config::Config> = self.get(self.url_api(routes::CONFIG)?)?;
+        configs.into_iter().next().ok_or(Error::NotFound)
+    }
+
+    /// Modify the configuration of the application, and return its new version
+    ///
+    /// # Arguments
+    ///
+    /// * `update` - Fields to modify
+    pub fn update_config(&self, update: &config::Update) -> Result<config::Config, Error> {
+        let id = self.config()?.id;
+        self.send_json(Method::PATCH, self.url_api(&routes::config(id))?, update)
+    }
+
+    /// Gather everything needed to render the dashboard
+    ///
+    /// Statistics, saved views and failed tasks are requested concurrently, then the documents of
+    /// the saved views shown on the dashboard are counted, a few views at a time. The requests
+    /// keep the priority of the calling thread.
+    pub fn dashboard_summary(&self) -> Result<dashboard::Summary, Error> {
+        /// Views counted at the same time, each one in its own thread
+        const CONCURRENT_COUNTS: usize = 4;
+
+        let priority = priority::current();
+        let (statistics, views, failed_tasks) = thread::scope(|s| {
+            let statistics = s.spawn(|| priority::scoped(priority, || self.statistics()));
+            let views = s.spawn(|| {
+                priority::scoped(priority, || {
+                    self.saved_views()?
+                        .filter(|view| view.as_ref().map_or(true, |v| v.show_on_dashboard))
+                        .collect::<Result<Vec<_>, Error>>()
+                })
+            });
+            let failed_tasks = s.spawn(|| priority::scoped(priority, || self.failed_tasks()));
+            (join(statistics), join(views), join(failed_tasks))
+        });
+        let (statistics, views, failed_tasks) = (statistics?, views?, failed_tasks?);
+
+        let mut counts = Vec::with_capacity(views.len());
+        for chunk in views.chunks(CONCURRENT_COUNTS) {
+            let chunk_counts = thread::scope(|s| {
+                let handles: Vec<_> = chunk
+                    .iter()
+                    .map(|view| {
+                        s.spawn(move || {
+                            let filter = document::Filter::from_filter_rules(&view.filter_rules);
+                            priority::scoped(priority, || self.document_count(filter))
+                        })
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(join)
+                    .collect::<Result<Vec<_>, Error>>()
+            })?;
+            counts.extend(chunk_counts);
+        }
+
+        Ok(dashboard::Summary {
+            inbox_count: statistics.documents_inbox.unwrap_or_default(),
+            statistics,
+            views: views
+                .into_iter()
+                .zip(counts)
+                .map(|(view, document_count)| dashboard::View {
+                    unsupported_rules: saved_view::FilterRule::unsupported(&view.filter_rules),
+                    view,
+                    document_count,
+                })
+                .collect(),
+            failed_tasks,
+        })
+    }
+
+    /// Failed tasks which have not been acknowledged, most recent first
+    fn failed_tasks(&self) -> Result<Vec<task::Task>, Error> {
+        self.tasks(task::Filter {
+            status: Some(task::Status::Failure),
+            acknowledged: Some(false),
+            ..Default::default()
+        })
+    }
+}
+
+/// Wait for a scoped thread, forwarding its panic if any
+pub(crate) fn join<T>(handle: ScopedJoinHandle<'_, T>) -> T {
+    handle
+        .join()
+        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
 }
@@ -0,0 +1,26 @@
+//! # Permissions
+//!
+//! Objects of multi-user servers have an owner, and can be shared with other users and groups.
+//! The details of the permissions are only sent when the client is built with
+//! [`PaperlessBuilder::full_permissions`](crate::PaperlessBuilder::full_permissions).
+
+use crate::{group, user};
+use serde::{Deserialize, Serialize};
+
+/// Users and groups allowed to do something on an object
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Access {
+    #[serde(default)]
+    pub users: Vec<user::Id>,
+    #[serde(default)]
+    pub groups: Vec<group::Id>,
+}
+
+/// Who can see and modify an object, in addition to its owner
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Permissions {
+    #[serde(default)]
+    pub view: Access,
+    #[serde(default)]
+    pub change: Access,
+}
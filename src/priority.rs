@@ -0,0 +1,93 @@
+//! # Priority
+//!
+//! Requests sent by a [`Paperless`](crate::Paperless) client are interactive by default. Long
+//! running jobs sharing the same client, like warming a cache or synchronising documents, can run
+//! in background with [`Paperless::with_priority`](crate::Paperless::with_priority): their
+//! requests wait while interactive requests are in flight, so that they never slow down what a
+//! user is waiting for.
+
+use std::cell::Cell;
+use std::sync::{Condvar, Mutex};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    /// Someone is waiting for the response
+    #[default]
+    Interactive,
+    /// Only sent when no interactive request is in flight
+    Background,
+}
+
+thread_local! {
+    static CURRENT: Cell<Priority> = const { Cell::new(Priority::Interactive) };
+}
+
+/// Priority of the requests sent from the current thread
+pub(crate) fn current() -> Priority {
+    CURRENT.with(Cell::get)
+}
+
+/// Run `f` with the requests of the current thread sent with `priority`
+pub(crate) fn scoped<T>(priority: Priority, f: impl FnOnce() -> T) -> T {
+    /// Restore the previous priority, even if `f` panics
+    struct Restore(Priority);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT.with(|c| c.set(self.0));
+        }
+    }
+
+    let _restore = Restore(CURRENT.with(|c| c.replace(priority)));
+    f()
+}
+
+/// Delay background requests while interactive ones are in flight
+#[derive(Default)]
+pub(crate) struct Scheduler {
+    interactive: Mutex<usize>,
+    idle: Condvar,
+}
+
+/// Held while a request is in flight
+pub(crate) struct Permit<'s> {
+    scheduler: &'s Scheduler,
+    priority: Priority,
+}
+
+impl Scheduler {
+    /// Wait until a request with this priority can be sent
+    pub(crate) fn acquire(&self, priority: Priority) -> Permit<'_> {
+        let mut interactive = self.interactive.lock().unwrap_or_else(|e| e.into_inner());
+        match priority {
+            Priority::Interactive => *interactive += 1,
+            Priority::Background => {
+                while *interactive > 0 {
+                    interactive = self
+                        .idle
+                        .wait(interactive)
+                        .unwrap_or_else(|e| e.into_inner());
+                }
+            }
+        }
+        Permit {
+            scheduler: self,
+            priority,
+        }
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        if self.priority == Priority::Interactive {
+            let mut interactive = self
+                .scheduler
+                .interactive
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            *interactive -= 1;
+            if *interactive == 0 {
+                self.scheduler.idle.notify_all();
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Encoding of values in query strings, the way the paperless API (django-filter) expects them
+
+use reqwest::Url;
+
+/// Append a list of values to the query string of `url` as `key=1,2,3`, nothing is appended for
+/// an empty list
+///
+/// Every list filter of paperless is a comma separated value:
+///
+/// * the `__in` lookups are `BaseInFilter`s of django-filter, which split the value on commas
+/// * `tags__id__all`, `tags__id__none` and the other `__all` and `__none` lookups of documents
+///   are the `ObjectFilter`s of paperless, which split it the same way
+/// * `ordering` is read by the `OrderingFilter` of DRF, which splits it too
+///
+/// No endpoint of paperless uses a multiple choice filter, which would expect the key to be
+/// repeated. If the key is repeated anyway, django only keeps the last value and the server
+/// silently ignores the others.
+pub(crate) fn append_list<T: ToString>(url: &mut Url, key: &str, values: &[T]) {
+    if values.is_empty() {
+        return;
+    }
+    let joined = values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<String>>()
+        .join(",");
+    url.query_pairs_mut().append_pair(key, &joined);
+}
+
+/// Append a parameter to the query string of `url`, only if it is set
+///
+/// Unset parameters are not sent as empty `key=` pairs, which make the urls much longer and are
+/// applied as filters on empty values by some versions of paperless.
+pub(crate) fn append_some<T: ToString>(url: &mut Url, key: &str, value: Option<T>) {
+    if let Some(value) = value {
+        url.query_pairs_mut().append_pair(key, &value.to_string());
+    }
+}
+
+/// Set a parameter of the query string of `url`, replacing its previous values
+pub(crate) fn replace_pair(url: &mut Url, key: &str, value: &str) {
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(k, _)| k != key)
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    url.query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs)
+        .append_pair(key, value);
+}
@@ -0,0 +1,80 @@
+//! Rate limiting, by the server with `429 Too Many Requests` responses, and by the client to
+//! avoid them
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Number of times a rate limited request is sent again before failing
+pub(crate) const MAX_RETRIES: u32 = 3;
+
+/// Settings of the builder
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RateLimit {
+    /// Maximum number of requests sent per second, unlimited if `None`
+    pub(crate) per_second: Option<f64>,
+    /// Longest `Retry-After` waited before sending a request again, longer ones fail immediately
+    pub(crate) max_retry_after: Duration,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            per_second: None,
+            max_retry_after: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Space the requests sent by a client
+#[derive(Debug)]
+pub(crate) struct Limiter {
+    interval: Option<Duration>,
+    /// When the next request can be sent
+    next: Mutex<Instant>,
+}
+
+impl Limiter {
+    pub(crate) fn new(per_second: Option<f64>) -> Self {
+        Self {
+            interval: per_second
+                .filter(|p| *p > 0.0)
+                .map(|p| Duration::from_secs_f64(1.0 / p)),
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Wait until a request can be sent
+    pub(crate) fn wait(&self) {
+        let Some(interval) = self.interval else {
+            return;
+        };
+        let now = Instant::now();
+        let slot = {
+            let mut next = self.next.lock().unwrap_or_else(|e| e.into_inner());
+            let slot = (*next).max(now);
+            *next = slot + interval;
+            slot
+        };
+        thread::sleep(slot - now);
+    }
+}
+
+/// Delay asked by the server before sending a request again, for rate limited requests
+///
+/// Only the number of seconds is understood, other responses are not retried.
+pub(crate) fn retry_after(status: StatusCode, headers: &HeaderMap) -> Option<Duration> {
+    if status != StatusCode::TOO_MANY_REQUESTS && status != StatusCode::SERVICE_UNAVAILABLE {
+        return None;
+    }
+    let seconds: u64 = headers
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(Duration::from_secs(seconds))
+}
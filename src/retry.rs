@@ -0,0 +1,81 @@
+//! # Retry
+//!
+//! Transient failures, like a server restarting or a flaky network, make idempotent requests
+//! (`GET` and `HEAD`) fail. A [`RetryPolicy`] set with
+//! [`PaperlessBuilder::retry`](crate::PaperlessBuilder::retry) sends them again after an
+//! exponential backoff, including the ones of [`Paginated`](crate::Paginated).
+
+use reqwest::{Method, StatusCode};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Number of attempts, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled before each of the next ones
+    pub base_delay: Duration,
+    /// Maximum delay between two attempts
+    pub max_delay: Duration,
+    /// Fraction of each delay which is random, between 0 and 1, so that clients failing at the
+    /// same time don't retry at the same time
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    /// 3 attempts, 500ms then 1s later
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+            jitter: 0.5,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retry, the default of the clients
+    pub fn never() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Whether a request can be sent again after failing
+    pub(crate) fn is_idempotent(method: &Method) -> bool {
+        *method == Method::GET || *method == Method::HEAD
+    }
+
+    /// Whether the error may disappear by itself
+    pub(crate) fn is_transient(result: &Result<StatusCode, &reqwest::Error>) -> bool {
+        match result {
+            Ok(status) => matches!(
+                *status,
+                StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT
+            ),
+            Err(e) => e.is_connect() || e.is_timeout(),
+        }
+    }
+
+    /// Delay before an attempt, starting at 1 for the first retry
+    pub(crate) fn delay(&self, retry: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_delay);
+        let jitter = self.jitter.clamp(0.0, 1.0) * random_fraction();
+        delay.mul_f64(1.0 - jitter)
+    }
+}
+
+/// A number between 0 and 1, random enough to spread retries
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u32(std::process::id());
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
@@ -0,0 +1,49 @@
+//! # Rollback
+//!
+//! Operations made of several requests, like
+//! [`Paperless::merge_tags`](crate::Paperless::merge_tags),
+//! [`Paperless::replace_document`](crate::Paperless::replace_document) or
+//! [`Paperless::migrate_document`](crate::Paperless::migrate_document), can fail in the middle. In
+//! this case they fail with [`Error::Interrupted`](crate::Error::Interrupted), which lists the
+//! steps already done so that the caller can undo them, or finish the operation.
+
+use crate::{correspondent, document, document_type, storage_path, tag};
+
+/// A modification done on the server
+#[derive(Debug, Clone)]
+pub enum Step {
+    /// The tags of a document were replaced
+    DocumentTags {
+        document: document::Id,
+        /// Tags of the document before the modification
+        previous: Vec<tag::Id>,
+    },
+    /// A tag was deleted, this is its last version
+    TagDeleted(tag::Tag),
+    /// A correspondent was deleted, this is its last version
+    CorrespondentDeleted(correspondent::Correspondent),
+    /// A document type was deleted, this is its last version
+    DocumentTypeDeleted(document_type::DocumentType),
+    /// A storage path was deleted, this is its last version
+    StoragePathDeleted(storage_path::StoragePath),
+    /// A file was uploaded, this is the id of its consumption task, which may still create a
+    /// document after the failure
+    DocumentUploaded(String),
+    /// An uploaded file was consumed into a new document
+    DocumentCreated(document::Id),
+    /// A document was deleted, servers with a trash can restore it with
+    /// [`Paperless::restore_documents`](crate::Paperless::restore_documents)
+    DocumentDeleted(document::Id),
+}
+
+/// Steps done before an operation failed, in the order in which they were done
+#[derive(Debug, Clone, Default)]
+pub struct Rollback {
+    pub steps: Vec<Step>,
+}
+
+impl Rollback {
+    pub(crate) fn record(&mut self, step: Step) {
+        self.steps.push(step);
+    }
+}
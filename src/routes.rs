@@ -0,0 +1,187 @@
+//! Paths of the endpoints, relative to the root of the api
+//!
+//! Every path used by the clients is built here, so that they can be checked in a single place
+//! when paperless changes its api.
+
+use crate::{
+    config, correspondent, custom_field, document, document_type, group, mail_account, mail_rule,
+    saved_view, share_link, storage_path, tag, user, workflow, Error,
+};
+
+pub(crate) const BULK_EDIT: &str = "documents/bulk_edit/";
+pub(crate) const CONFIG: &str = "config/";
+pub(crate) const CORRESPONDENTS: &str = "correspondents/";
+pub(crate) const CUSTOM_FIELDS: &str = "custom_fields/";
+pub(crate) const DOCUMENT_TYPES: &str = "document_types/";
+pub(crate) const DOCUMENTS: &str = "documents/";
+pub(crate) const DOCUMENTS_NEXT_ASN: &str = "documents/next_asn/";
+pub(crate) const GROUPS: &str = "groups/";
+pub(crate) const LOGS: &str = "logs/";
+pub(crate) const MAIL_ACCOUNTS: &str = "mail_accounts/";
+pub(crate) const MAIL_ACCOUNT_TEST: &str = "mail_accounts/test/";
+pub(crate) const MAIL_RULES: &str = "mail_rules/";
+pub(crate) const POST_DOCUMENT: &str = "documents/post_document/";
+pub(crate) const REMOTE_VERSION: &str = "remote_version/";
+pub(crate) const SAVED_VIEWS: &str = "saved_views/";
+pub(crate) const SEARCH: &str = "search/";
+pub(crate) const SHARE_LINKS: &str = "share_links/";
+pub(crate) const STATISTICS: &str = "statistics/";
+pub(crate) const STORAGE_PATHS: &str = "storage_paths/";
+pub(crate) const TAGS: &str = "tags/";
+pub(crate) const TASKS: &str = "tasks/";
+pub(crate) const TASKS_ACKNOWLEDGE: &str = "tasks/acknowledge/";
+pub(crate) const TOKEN: &str = "token/";
+pub(crate) const TRASH: &str = "trash/";
+pub(crate) const UI_SETTINGS: &str = "ui_settings/";
+pub(crate) const USERS: &str = "users/";
+pub(crate) const WORKFLOWS: &str = "workflows/";
+/// Websocket of the web interface, not of the api
+#[cfg(feature = "events")]
+pub(crate) const WS_STATUS: &str = "ws/status/";
+
+pub(crate) fn config(id: config::Id) -> String {
+    format!("{CONFIG}{}/", u64::from(id))
+}
+
+pub(crate) fn correspondent(id: correspondent::Id) -> String {
+    format!("{CORRESPONDENTS}{}/", u64::from(id))
+}
+
+pub(crate) fn custom_field(id: custom_field::Id) -> String {
+    format!("{CUSTOM_FIELDS}{}/", u64::from(id))
+}
+
+pub(crate) fn document_type(id: document_type::Id) -> String {
+    format!("{DOCUMENT_TYPES}{}/", u64::from(id))
+}
+
+pub(crate) fn document(id: document::Id) -> String {
+    format!("{DOCUMENTS}{}/", u64::from(id))
+}
+
+pub(crate) fn document_download(id: document::Id) -> String {
+    format!("{}download/", document(id))
+}
+
+pub(crate) fn document_email(id: document::Id) -> String {
+    format!("{}email/", document(id))
+}
+
+pub(crate) fn document_history(id: document::Id) -> String {
+    format!("{}history/", document(id))
+}
+
+pub(crate) fn document_metadata(id: document::Id) -> String {
+    format!("{}metadata/", document(id))
+}
+
+pub(crate) fn document_notes(id: document::Id) -> String {
+    format!("{}notes/", document(id))
+}
+
+pub(crate) fn document_preview(id: document::Id) -> String {
+    format!("{}preview/", document(id))
+}
+
+pub(crate) fn document_share_links(id: document::Id) -> String {
+    format!("{}share_links/", document(id))
+}
+
+pub(crate) fn document_thumbnail(id: document::Id) -> String {
+    format!("{}thumb/", document(id))
+}
+
+pub(crate) fn group(id: group::Id) -> String {
+    format!("{GROUPS}{}/", u64::from(id))
+}
+
+/// Fails for names which are not a single segment of the path, they could reach other endpoints
+pub(crate) fn log(name: &str) -> Result<String, Error> {
+    let valid = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if name.is_empty() || !valid || name.contains("..") {
+        return Err(Error::InvalidArgument(format!(
+            "{name:?} is not the name of a log file"
+        )));
+    }
+    Ok(format!("{LOGS}{name}/"))
+}
+
+pub(crate) fn mail_account(id: mail_account::Id) -> String {
+    format!("{MAIL_ACCOUNTS}{}/", u64::from(id))
+}
+
+pub(crate) fn mail_rule(id: mail_rule::Id) -> String {
+    format!("{MAIL_RULES}{}/", u64::from(id))
+}
+
+pub(crate) fn saved_view(id: saved_view::Id) -> String {
+    format!("{SAVED_VIEWS}{}/", u64::from(id))
+}
+
+pub(crate) fn share_link(id: share_link::Id) -> String {
+    format!("{SHARE_LINKS}{}/", u64::from(id))
+}
+
+/// Page of the web interface, not of the api
+pub(crate) fn shared_document(slug: &str) -> String {
+    format!("share/{slug}")
+}
+
+pub(crate) fn storage_path(id: storage_path::Id) -> String {
+    format!("{STORAGE_PATHS}{}/", u64::from(id))
+}
+
+pub(crate) fn tag(id: tag::Id) -> String {
+    format!("{TAGS}{}/", u64::from(id))
+}
+
+pub(crate) fn user(id: user::Id) -> String {
+    format!("{USERS}{}/", u64::from(id))
+}
+
+pub(crate) fn workflow(id: workflow::Id) -> String {
+    format!("{WORKFLOWS}{}/", u64::from(id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths() {
+        assert_eq!(config(1.into()), "config/1/");
+        assert_eq!(correspondent(1.into()), "correspondents/1/");
+        assert_eq!(custom_field(1.into()), "custom_fields/1/");
+        assert_eq!(document_type(1.into()), "document_types/1/");
+        assert_eq!(document(1.into()), "documents/1/");
+        assert_eq!(document_download(1.into()), "documents/1/download/");
+        assert_eq!(document_email(1.into()), "documents/1/email/");
+        assert_eq!(document_history(1.into()), "documents/1/history/");
+        assert_eq!(document_metadata(1.into()), "documents/1/metadata/");
+        assert_eq!(document_notes(1.into()), "documents/1/notes/");
+        assert_eq!(document_preview(1.into()), "documents/1/preview/");
+        assert_eq!(document_share_links(1.into()), "documents/1/share_links/");
+        assert_eq!(document_thumbnail(1.into()), "documents/1/thumb/");
+        assert_eq!(group(1.into()), "groups/1/");
+        assert_eq!(mail_account(1.into()), "mail_accounts/1/");
+        assert_eq!(mail_rule(1.into()), "mail_rules/1/");
+        assert_eq!(saved_view(1.into()), "saved_views/1/");
+        assert_eq!(share_link(1.into()), "share_links/1/");
+        assert_eq!(shared_document("abc"), "share/abc");
+        assert_eq!(storage_path(1.into()), "storage_paths/1/");
+        assert_eq!(tag(1.into()), "tags/1/");
+        assert_eq!(user(1.into()), "users/1/");
+        assert_eq!(workflow(1.into()), "workflows/1/");
+    }
+
+    #[test]
+    fn log_names() {
+        assert_eq!(log("paperless").unwrap(), "logs/paperless/");
+        assert_eq!(log("mail.1").unwrap(), "logs/mail.1/");
+        for name in ["", "..", "../tags", "a/b", "a\\b", "a?b", "a#b", "a%2Fb"] {
+            assert!(log(name).is_err(), "{name:?} was accepted");
+        }
+    }
+}
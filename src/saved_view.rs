@@ -3,13 +3,14 @@
 //! A saved view is a saved filtered view, which can be created from the interface. It allows to
 //! keep certain set of filters and have a fast access to a selection of documents
 
-use crate::{asn, correspondent, document, document_type, storage_path, tag};
-use chrono::{DateTime, Utc};
+use crate::document::DateBound;
+use crate::{asn, correspondent, custom_field, document, document_type, storage_path, tag, user};
 use serde::de::{MapAccess, Visitor};
-use serde::{de, Deserialize, Deserializer};
+use serde::ser::SerializeStruct;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 
-#[derive(Debug, Copy, Clone, Deserialize)]
+#[derive(Debug, Copy, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
 pub struct Id(u64);
 
 impl From<u64> for Id {
@@ -23,7 +24,9 @@ impl From<Id> for u64 {
     }
 }
 
-#[derive(Debug)]
+/// A rule of a saved view, see [`document::Filter::from_filter_rules`] and
+/// [`document::Filter::to_filter_rules`]
+#[derive(Debug, Clone, PartialEq)]
 pub enum FilterRule {
     TitleContains(Option<String>),
     ContentContains(Option<String>),
@@ -33,15 +36,15 @@ pub enum FilterRule {
     IsInInbox(Option<bool>),
     HasTag(Option<tag::Id>),
     HasAnyTag(Option<bool>),
-    CreatedBefore(Option<DateTime<Utc>>),
-    CreatedAfter(Option<DateTime<Utc>>),
+    CreatedBefore(Option<DateBound>),
+    CreatedAfter(Option<DateBound>),
     CreatedYearIs(Option<usize>),
     CreatedMountIs(Option<usize>),
     CreatedDayIs(Option<usize>),
-    AddedBefore(Option<DateTime<Utc>>),
-    AddedAfter(Option<DateTime<Utc>>),
-    ModifiedBefore(Option<DateTime<Utc>>),
-    ModifiedAfter(Option<DateTime<Utc>>),
+    AddedBefore(Option<DateBound>),
+    AddedAfter(Option<DateBound>),
+    ModifiedBefore(Option<DateBound>),
+    ModifiedAfter(Option<DateBound>),
     DontHaveTag(Option<tag::Id>),
     DontHaveASN(Option<bool>),
     TitleOrContentContains(Option<String>),
@@ -51,7 +54,161 @@ pub enum FilterRule {
     ASNGreaterThan(Option<asn::ASN>),
     ASNLessThan(Option<asn::ASN>),
     StoragePathIs(Option<storage_path::Id>),
+    /// `None` means without correspondent
+    HasCorrespondentIn(Option<correspondent::Id>),
+    /// `None` means with a correspondent
+    DontHaveCorrespondent(Option<correspondent::Id>),
+    /// `None` means without document type
+    HasDocumentTypeIn(Option<document_type::Id>),
+    /// `None` means with a document type
+    DontHaveDocumentType(Option<document_type::Id>),
+    /// `None` means without storage path
+    HasStoragePathIn(Option<storage_path::Id>),
+    /// `None` means with a storage path
+    DontHaveStoragePath(Option<storage_path::Id>),
+    OwnerIs(Option<user::Id>),
+    OwnerIn(Option<user::Id>),
+    /// `Some(true)` means without owner
+    OwnerIsNull(Option<bool>),
+    DontHaveOwner(Option<user::Id>),
+    /// A value of a custom field contains this text
+    CustomFieldsContains(Option<String>),
+    /// The document is owned by this user and shared with others
+    SharedBy(Option<user::Id>),
+    HasCustomFieldsAll(Option<custom_field::Id>),
+    HasCustomFieldsIn(Option<custom_field::Id>),
+    DontHaveCustomFields(Option<custom_field::Id>),
+    HasAnyCustomFields(Option<bool>),
+    /// A rule unknown to this version of the crate, kept as sent by the server
+    Unknown {
+        rule_type: u64,
+        value: Option<String>,
+    },
 }
+
+impl FilterRule {
+    /// Number identifying the rule on the server
+    pub fn rule_type(&self) -> u64 {
+        match self {
+            FilterRule::TitleContains(_) => 0,
+            FilterRule::ContentContains(_) => 1,
+            FilterRule::ASNIs(_) => 2,
+            FilterRule::CorrespondentIs(_) => 3,
+            FilterRule::DocumentTypeIs(_) => 4,
+            FilterRule::IsInInbox(_) => 5,
+            FilterRule::HasTag(_) => 6,
+            FilterRule::HasAnyTag(_) => 7,
+            FilterRule::CreatedBefore(_) => 8,
+            FilterRule::CreatedAfter(_) => 9,
+            FilterRule::CreatedYearIs(_) => 10,
+            FilterRule::CreatedMountIs(_) => 11,
+            FilterRule::CreatedDayIs(_) => 12,
+            FilterRule::AddedBefore(_) => 13,
+            FilterRule::AddedAfter(_) => 14,
+            FilterRule::ModifiedBefore(_) => 15,
+            FilterRule::ModifiedAfter(_) => 16,
+            FilterRule::DontHaveTag(_) => 17,
+            FilterRule::DontHaveASN(_) => 18,
+            FilterRule::TitleOrContentContains(_) => 19,
+            FilterRule::FullTextQuery(_) => 20,
+            FilterRule::MoreLikeThis(_) => 21,
+            FilterRule::HasTagIn(_) => 22,
+            FilterRule::ASNGreaterThan(_) => 23,
+            FilterRule::ASNLessThan(_) => 24,
+            FilterRule::StoragePathIs(_) => 25,
+            FilterRule::HasCorrespondentIn(_) => 26,
+            FilterRule::DontHaveCorrespondent(_) => 27,
+            FilterRule::HasDocumentTypeIn(_) => 28,
+            FilterRule::DontHaveDocumentType(_) => 29,
+            FilterRule::HasStoragePathIn(_) => 30,
+            FilterRule::DontHaveStoragePath(_) => 31,
+            FilterRule::OwnerIs(_) => 32,
+            FilterRule::OwnerIn(_) => 33,
+            FilterRule::OwnerIsNull(_) => 34,
+            FilterRule::DontHaveOwner(_) => 35,
+            FilterRule::CustomFieldsContains(_) => 36,
+            FilterRule::SharedBy(_) => 37,
+            FilterRule::HasCustomFieldsAll(_) => 38,
+            FilterRule::HasCustomFieldsIn(_) => 39,
+            FilterRule::DontHaveCustomFields(_) => 40,
+            FilterRule::HasAnyCustomFields(_) => 41,
+            FilterRule::Unknown { rule_type, .. } => *rule_type,
+        }
+    }
+
+    /// Rules which [`document::Filter::from_filter_rules`] can't apply, the filter selects more
+    /// documents than the saved view if there are some
+    ///
+    /// # Arguments
+    ///
+    /// * `rules` - Rules of a saved view
+    pub fn unsupported(rules: &[FilterRule]) -> Vec<FilterRule> {
+        rules
+            .iter()
+            .filter(|rule| matches!(rule, FilterRule::Unknown { .. }))
+            .cloned()
+            .collect()
+    }
+
+    /// Value of the rule, as sent to the server
+    pub fn value(&self) -> Option<String> {
+        match self {
+            FilterRule::TitleContains(v)
+            | FilterRule::ContentContains(v)
+            | FilterRule::TitleOrContentContains(v)
+            | FilterRule::FullTextQuery(v)
+            | FilterRule::CustomFieldsContains(v)
+            | FilterRule::Unknown { value: v, .. } => v.clone(),
+            FilterRule::ASNIs(v) | FilterRule::ASNGreaterThan(v) | FilterRule::ASNLessThan(v) => {
+                v.map(|v| v.to_string())
+            }
+            FilterRule::CorrespondentIs(v)
+            | FilterRule::HasCorrespondentIn(v)
+            | FilterRule::DontHaveCorrespondent(v) => v.map(|v| v.to_string()),
+            FilterRule::DocumentTypeIs(v)
+            | FilterRule::HasDocumentTypeIn(v)
+            | FilterRule::DontHaveDocumentType(v) => v.map(|v| v.to_string()),
+            FilterRule::StoragePathIs(v)
+            | FilterRule::HasStoragePathIn(v)
+            | FilterRule::DontHaveStoragePath(v) => v.map(|v| v.to_string()),
+            FilterRule::HasTag(v) | FilterRule::DontHaveTag(v) | FilterRule::HasTagIn(v) => {
+                v.map(|v| v.to_string())
+            }
+            FilterRule::IsInInbox(v)
+            | FilterRule::HasAnyTag(v)
+            | FilterRule::DontHaveASN(v)
+            | FilterRule::OwnerIsNull(v)
+            | FilterRule::HasAnyCustomFields(v) => v.map(|v| v.to_string()),
+            FilterRule::OwnerIs(v)
+            | FilterRule::OwnerIn(v)
+            | FilterRule::DontHaveOwner(v)
+            | FilterRule::SharedBy(v) => v.map(|v| v.to_string()),
+            FilterRule::HasCustomFieldsAll(v)
+            | FilterRule::HasCustomFieldsIn(v)
+            | FilterRule::DontHaveCustomFields(v) => v.map(|v| v.to_string()),
+            FilterRule::CreatedBefore(v)
+            | FilterRule::CreatedAfter(v)
+            | FilterRule::AddedBefore(v)
+            | FilterRule::AddedAfter(v)
+            | FilterRule::ModifiedBefore(v)
+            | FilterRule::ModifiedAfter(v) => v.map(|v| v.to_rule_value()),
+            FilterRule::CreatedYearIs(v)
+            | FilterRule::CreatedMountIs(v)
+            | FilterRule::CreatedDayIs(v) => v.map(|v| v.to_string()),
+            FilterRule::MoreLikeThis(v) => v.map(|v| v.to_string()),
+        }
+    }
+}
+
+impl Serialize for FilterRule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut rule = serializer.serialize_struct("FilterRule", 2)?;
+        rule.serialize_field("rule_type", &self.rule_type())?;
+        rule.serialize_field("value", &self.value())?;
+        rule.end()
+    }
+}
+
 impl<'de> Deserialize<'de> for FilterRule {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -113,20 +270,13 @@ impl<'de> Deserialize<'de> for FilterRule {
                         Err(_) => None,
                     },
                 };
-                let bool_value: Option<bool> = match &value {
-                    None => None,
-                    Some(v) => match v.parse() {
-                        Ok(v) => Some(v),
-                        Err(_) => None,
-                    },
-                };
-                let date_value = match &value {
-                    None => None,
-                    Some(v) => match DateTime::parse_from_rfc3339(v) {
-                        Ok(v) => Some(v.naive_utc().and_utc()),
-                        Err(_) => None,
-                    },
+                // The interface sends `1` and `0` for some rules
+                let bool_value: Option<bool> = match value.as_deref() {
+                    Some("true") | Some("1") => Some(true),
+                    Some("false") | Some("0") => Some(false),
+                    _ => None,
                 };
+                let date_value = value.as_deref().and_then(DateBound::parse);
                 match rule_type {
                     0 => Ok(FilterRule::TitleContains(value)),
                     1 => Ok(FilterRule::ContentContains(value)),
@@ -154,7 +304,29 @@ impl<'de> Deserialize<'de> for FilterRule {
                     23 => Ok(FilterRule::ASNGreaterThan(u64_value.map(|v| v.into()))),
                     24 => Ok(FilterRule::ASNLessThan(u64_value.map(|v| v.into()))),
                     25 => Ok(FilterRule::StoragePathIs(u64_value.map(|v| v.into()))),
-                    r => Err(de::Error::custom(format!("Invalid rule_type {}", r))),
+                    26 => Ok(FilterRule::HasCorrespondentIn(u64_value.map(|v| v.into()))),
+                    27 => Ok(FilterRule::DontHaveCorrespondent(
+                        u64_value.map(|v| v.into()),
+                    )),
+                    28 => Ok(FilterRule::HasDocumentTypeIn(u64_value.map(|v| v.into()))),
+                    29 => Ok(FilterRule::DontHaveDocumentType(
+                        u64_value.map(|v| v.into()),
+                    )),
+                    30 => Ok(FilterRule::HasStoragePathIn(u64_value.map(|v| v.into()))),
+                    31 => Ok(FilterRule::DontHaveStoragePath(u64_value.map(|v| v.into()))),
+                    32 => Ok(FilterRule::OwnerIs(u64_value.map(|v| v.into()))),
+                    33 => Ok(FilterRule::OwnerIn(u64_value.map(|v| v.into()))),
+                    34 => Ok(FilterRule::OwnerIsNull(bool_value)),
+                    35 => Ok(FilterRule::DontHaveOwner(u64_value.map(|v| v.into()))),
+                    36 => Ok(FilterRule::CustomFieldsContains(value)),
+                    37 => Ok(FilterRule::SharedBy(u64_value.map(|v| v.into()))),
+                    38 => Ok(FilterRule::HasCustomFieldsAll(u64_value.map(|v| v.into()))),
+                    39 => Ok(FilterRule::HasCustomFieldsIn(u64_value.map(|v| v.into()))),
+                    40 => Ok(FilterRule::DontHaveCustomFields(
+                        u64_value.map(|v| v.into()),
+                    )),
+                    41 => Ok(FilterRule::HasAnyCustomFields(bool_value)),
+                    rule_type => Ok(FilterRule::Unknown { rule_type, value }),
                 }
             }
         }
@@ -174,3 +346,26 @@ pub struct SaveView {
     pub sort_reverse: bool,
     pub filter_rules: Vec<FilterRule>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_rules_are_kept() {
+        let json = r#"{"rule_type":99,"value":"2024-01-31"}"#;
+        let rule: FilterRule = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            rule,
+            FilterRule::Unknown {
+                rule_type: 99,
+                value: Some("2024-01-31".to_string())
+            }
+        );
+        assert_eq!(serde_json::to_string(&rule).unwrap(), json);
+        assert_eq!(
+            FilterRule::unsupported(std::slice::from_ref(&rule)),
+            vec![rule]
+        );
+    }
+}
@@ -0,0 +1,80 @@
+//! # Search
+//!
+//! Search of every kind of objects by their name with [`Paperless::search`], like the search bar
+//! of the interface, and full text search in the documents with
+//! [`Paperless::search_documents`].
+//!
+//! [`Paperless::search`]: crate::Paperless::search
+//! [`Paperless::search_documents`]: crate::Paperless::search_documents
+
+use crate::{
+    correspondent, custom_field, document, document_type, group, mail_account, mail_rule,
+    saved_view, storage_path, tag, user, workflow,
+};
+use serde::Deserialize;
+
+/// A document matching a search
+#[derive(Debug, Deserialize)]
+pub struct Hit {
+    #[serde(flatten)]
+    pub document: document::Document,
+    /// How well the document matches, not sent by old versions of paperless
+    #[serde(rename = "__search_hit__")]
+    pub search_hit: Option<SearchHit>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchHit {
+    pub score: Option<f64>,
+    /// Parts of the content matching the query, as HTML
+    pub highlights: Option<String>,
+    pub rank: Option<u64>,
+}
+
+/// Result of a full text search, with the matching documents sorted by relevance
+///
+/// The documents are a [`Paginated`] for [`Paperless`], or the listing of the
+/// [`PaperlessApi`] implementation.
+///
+/// [`Paginated`]: crate::Paginated
+/// [`Paperless`]: crate::Paperless
+/// [`PaperlessApi`]: crate::PaperlessApi
+pub struct DocumentResults<D> {
+    /// Number of matching documents
+    pub count: u64,
+    /// Query corrected by the server when it looks like a spelling mistake, to show "did you
+    /// mean ..."
+    pub corrected_query: Option<String>,
+    pub documents: D,
+}
+
+/// Objects matching a global search, only the first few of each kind are returned
+#[derive(Debug, Deserialize)]
+pub struct SearchResults {
+    /// Number of returned objects
+    pub total: u64,
+    #[serde(default)]
+    pub documents: Vec<document::Document>,
+    #[serde(default)]
+    pub saved_views: Vec<saved_view::SaveView>,
+    #[serde(default)]
+    pub correspondents: Vec<correspondent::Correspondent>,
+    #[serde(default)]
+    pub document_types: Vec<document_type::DocumentType>,
+    #[serde(default)]
+    pub storage_paths: Vec<storage_path::StoragePath>,
+    #[serde(default)]
+    pub tags: Vec<tag::Tag>,
+    #[serde(default)]
+    pub users: Vec<user::User>,
+    #[serde(default)]
+    pub groups: Vec<group::Group>,
+    #[serde(default)]
+    pub mail_accounts: Vec<mail_account::MailAccount>,
+    #[serde(default)]
+    pub mail_rules: Vec<mail_rule::MailRule>,
+    #[serde(default)]
+    pub custom_fields: Vec<custom_field::CustomField>,
+    #[serde(default)]
+    pub workflows: Vec<workflow::Workflow>,
+}
@@ -0,0 +1,172 @@
+use crate::auth::Auth;
+use crate::Error;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::Url;
+
+/// Connection information shared by the blocking and the async clients
+#[derive(Debug, Clone)]
+pub(crate) struct Server {
+    root: Url,
+    /// Headers authenticating the requests, and the custom ones
+    headers: HeaderMap,
+    default_query: Vec<(String, String)>,
+    /// Ask for the details of the permissions of the objects
+    full_permissions: bool,
+}
+
+impl Server {
+    pub(crate) fn new(
+        root: &str,
+        auth: &Auth,
+        custom_headers: &[(String, String)],
+        default_query: Vec<(String, String)>,
+        full_permissions: bool,
+    ) -> Result<Self, Error> {
+        let mut headers = auth.headers()?;
+        for (name, value) in custom_headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| Error::InvalidArgument(format!("invalid header name {name}")))?;
+            let mut value = HeaderValue::from_str(value)
+                .map_err(|_| Error::InvalidArgument(format!("invalid value for header {name}")))?;
+            // Reverse proxies often expect secrets, which must not be printed
+            value.set_sensitive(true);
+            headers.append(name, value);
+        }
+        Ok(Self {
+            root: Self::parse_root(root)?,
+            headers,
+            default_query,
+            full_permissions,
+        })
+    }
+
+    /// Parse the root url of the api
+    ///
+    /// The url of the server itself is accepted too, `api/` is appended to it. Urls which are not
+    /// http(s) ones fail with [`Error::InvalidArgument`].
+    pub(crate) fn parse_root(root: &str) -> Result<Url, Error> {
+        let mut root = Url::parse(root)?;
+        if !matches!(root.scheme(), "http" | "https") || root.cannot_be_a_base() {
+            return Err(Error::InvalidArgument(format!(
+                "{root} is not an http(s) url"
+            )));
+        }
+        root.set_query(None);
+        root.set_fragment(None);
+        // Without a trailing slash, `join` replaces the last segment (`api`) instead of appending
+        let mut path = root.path().trim_end_matches('/').to_string();
+        if !path.ends_with("/api") {
+            path.push_str("/api");
+        }
+        path.push('/');
+        root.set_path(&path);
+        Ok(root)
+    }
+
+    /// Headers to add on every request sent to the server
+    pub(crate) fn headers(&self) -> HeaderMap {
+        let mut headers = self.headers.clone();
+        headers.append(
+            "Accept",
+            HeaderValue::from_static("application/json; version=2"),
+        );
+        headers
+    }
+
+    /// Add the query parameters sent with every GET request
+    pub(crate) fn prepare_get(&self, url: &mut Url) {
+        if self.full_permissions && !url.query_pairs().any(|(k, _)| k == "full_perms") {
+            url.query_pairs_mut().append_pair("full_perms", "true");
+        }
+    }
+
+    /// Generate the url of an endpoint
+    ///
+    /// The path is always relative to the root, and always ends with a slash, as the server
+    /// answers 404 to endpoints without it.
+    pub(crate) fn url_api(&self, path: &str) -> Result<Url, Error> {
+        let path = path.trim_start_matches('/');
+        if path.is_empty() || path.ends_with('/') {
+            Ok(self.root.join(path)?)
+        } else {
+            Ok(self.root.join(&format!("{path}/"))?)
+        }
+    }
+
+    /// Move an url sent by the server onto the root of the api, keeping its path inside the api
+    /// and its query
+    pub(crate) fn rebase(&self, url: &Url) -> Result<Url, Error> {
+        let path = url.path();
+        let relative = match path.rfind("/api/") {
+            Some(start) => &path[start + "/api/".len()..],
+            None => path.trim_start_matches('/'),
+        };
+        let mut rebased = self.root.join(relative)?;
+        rebased.set_query(url.query());
+        Ok(rebased)
+    }
+
+    /// Generate the url of a page of the web interface, which is served next to the api
+    pub(crate) fn url_ui(&self, path: &str) -> Result<Url, Error> {
+        Ok(self.root.join("../")?.join(path.trim_start_matches('/'))?)
+    }
+
+    /// Generate the url of a listing endpoint, with the default query parameters
+    pub(crate) fn url_list(&self, path: &str) -> Result<Url, Error> {
+        let mut url = self.url_api(path)?;
+        if !self.default_query.is_empty() {
+            url.query_pairs_mut().extend_pairs(&self.default_query);
+        }
+        Ok(url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(root: &str) -> Server {
+        Server::new(root, &Auth::Token("token".to_string()), &[], vec![], false).unwrap()
+    }
+
+    #[test]
+    fn roots() {
+        for root in ["https://h", "https://h/", "https://h/api", "https://h/api/"] {
+            assert_eq!(Server::parse_root(root).unwrap().as_str(), "https://h/api/");
+        }
+        assert_eq!(
+            Server::parse_root("https://h/sub/api/").unwrap().as_str(),
+            "https://h/sub/api/"
+        );
+        assert_eq!(
+            Server::parse_root("https://h/sub?a=1#b").unwrap().as_str(),
+            "https://h/sub/api/"
+        );
+        assert!(Server::parse_root("ftp://h/").is_err());
+        assert!(Server::parse_root("mailto:admin@h").is_err());
+    }
+
+    #[test]
+    fn urls_of_endpoints() {
+        for root in ["https://h", "https://h/", "https://h/api", "https://h/api/"] {
+            let server = server(root);
+            for path in ["tags/1/", "/tags/1/", "tags/1", "/tags/1"] {
+                assert_eq!(
+                    server.url_api(path).unwrap().as_str(),
+                    "https://h/api/tags/1/"
+                );
+            }
+        }
+        let server = server("https://h/sub/api/");
+        for path in ["tags/1/", "/tags/1"] {
+            assert_eq!(
+                server.url_api(path).unwrap().as_str(),
+                "https://h/sub/api/tags/1/"
+            );
+        }
+        assert_eq!(
+            server.url_ui("/share/abc").unwrap().as_str(),
+            "https://h/sub/share/abc"
+        );
+    }
+}
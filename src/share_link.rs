@@ -0,0 +1,64 @@
+//! # Share link
+//!
+//! A share link gives access to the file of a document without an account, until it expires.
+
+use crate::date::{self, DateTime};
+use crate::document;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Deserialize, Copy, Clone, Serialize, PartialEq, Eq, Hash)]
+pub struct Id(u64);
+
+impl From<u64> for Id {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+impl From<Id> for u64 {
+    fn from(value: Id) -> Self {
+        value.0
+    }
+}
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ShareLink {
+    pub id: Id,
+    #[serde(with = "date::datetime")]
+    pub created: DateTime,
+    /// `None` if the link never expires
+    #[serde(default, with = "date::datetime::option")]
+    pub expiration: Option<DateTime>,
+    /// Part of the url identifying the link, see
+    /// [`Paperless::share_link_url`](crate::Paperless::share_link_url) for the full url
+    pub slug: String,
+    pub document: document::Id,
+    #[serde(alias = "fileVersion")]
+    pub file_version: document::FileVersion,
+}
+
+/// A new share link
+#[derive(Debug, Clone, Serialize)]
+pub struct Create {
+    pub document: document::Id,
+    /// `None` for a link which never expires
+    #[serde(with = "date::datetime::option")]
+    pub expiration: Option<DateTime>,
+    pub file_version: document::FileVersion,
+}
+
+impl Create {
+    /// A link to the archived version of a document, which never expires
+    pub fn new(document: document::Id) -> Self {
+        Self {
+            document,
+            expiration: None,
+            file_version: document::FileVersion::Archive,
+        }
+    }
+}
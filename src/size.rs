@@ -0,0 +1,94 @@
+//! Size of the files of documents
+//!
+//! Sizes are read from a HEAD request, but some reverse proxies block them. In this case, a GET
+//! of the first byte gives the size in its `Content-Range`, and the metadata endpoint is the last
+//! resort. The first method that works is remembered by the client.
+
+use crate::Error;
+use reqwest::header::{HeaderMap, CONTENT_LENGTH, CONTENT_RANGE};
+use reqwest::StatusCode;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Method {
+    Head,
+    /// GET with `Range: bytes=0-0`
+    Range,
+    Metadata,
+}
+
+/// Method which worked for a client, unknown until a size is requested
+#[derive(Debug, Default)]
+pub(crate) struct Probe(AtomicU8);
+
+impl Probe {
+    pub(crate) fn get(&self) -> Option<Method> {
+        match self.0.load(Ordering::Relaxed) {
+            1 => Some(Method::Head),
+            2 => Some(Method::Range),
+            3 => Some(Method::Metadata),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn set(&self, method: Method) {
+        let value = match method {
+            Method::Head => 1,
+            Method::Range => 2,
+            Method::Metadata => 3,
+        };
+        self.0.store(value, Ordering::Relaxed);
+    }
+}
+
+/// Value of the `Range` header requesting only the first byte
+pub(crate) const FIRST_BYTE: &str = "bytes=0-0";
+
+/// Whether the next method should be tried after this error
+pub(crate) fn is_blocked(error: &Error) -> bool {
+    match error {
+        Error::Forbidden | Error::MissingHeader(_) | Error::Validation(_) => true,
+        Error::Status(status) => matches!(
+            *status,
+            StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED | StatusCode::BAD_REQUEST
+        ),
+        _ => false,
+    }
+}
+
+pub(crate) fn from_content_length(headers: &HeaderMap) -> Result<usize, Error> {
+    headers
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+        .ok_or(Error::MissingHeader("content-length"))
+}
+
+/// Total size from a `Content-Range: bytes 0-0/<size>` header, only sent for partial responses
+///
+/// `None` when the server ignored the range and sent the whole file, as it does for encrypted
+/// files which are decrypted in memory. The body of the response must not be read then.
+pub(crate) fn from_content_range(
+    status: StatusCode,
+    headers: &HeaderMap,
+) -> Result<Option<usize>, Error> {
+    if status == StatusCode::OK {
+        return Ok(None);
+    }
+    if status != StatusCode::PARTIAL_CONTENT {
+        return Err(Error::MissingHeader("content-range"));
+    }
+    headers
+        .get(CONTENT_RANGE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.rsplit_once('/'))
+        .and_then(|(_, size)| size.parse().ok())
+        .map(Some)
+        .ok_or(Error::MissingHeader("content-range"))
+}
+
+/// Size from the metadata of a document, which is unknown if the file is missing on the server
+pub(crate) fn from_metadata(size: Option<u64>) -> Result<usize, Error> {
+    size.and_then(|s| usize::try_from(s).ok())
+        .ok_or(Error::NotFound)
+}
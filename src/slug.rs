@@ -0,0 +1,49 @@
+//! # Slug
+//!
+//! Helpers to compute locally what the server derives from names, to validate names before
+//! creating correspondents, document types or tags.
+
+use unicode_normalization::UnicodeNormalization;
+
+/// Compute the slug of a name, the same way paperless (django) does
+///
+/// Accents are removed, characters which are not alphanumeric, `_`, `-` or spaces are dropped,
+/// and runs of spaces and `-` are replaced by a single `-`.
+///
+/// # Examples
+///
+/// ```rust
+/// use paperless::slug::slugify;
+/// assert_eq!(slugify("Électricité de France"), "electricite-de-france");
+/// assert_eq!(slugify("  Bank -- Account #2 "), "bank-account-2");
+/// ```
+pub fn slugify(name: &str) -> String {
+    let ascii: String = name
+        .nfkd()
+        .filter(|c| c.is_ascii())
+        .collect::<String>()
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '_' || *c == '-' || c.is_whitespace())
+        .collect();
+
+    let mut slug = String::with_capacity(ascii.len());
+    let mut separator = false;
+    for c in ascii.chars() {
+        if c == '-' || c.is_whitespace() {
+            separator = true;
+        } else {
+            if separator && !slug.is_empty() {
+                slug.push('-');
+            }
+            separator = false;
+            slug.push(c);
+        }
+    }
+    slug.trim_matches(|c| c == '-' || c == '_').to_string()
+}
+
+/// Whether two names are considered the same by the server, which ignores the case
+pub fn same_name(a: &str, b: &str) -> bool {
+    a.to_lowercase() == b.to_lowercase()
+}
@@ -0,0 +1,44 @@
+//! # Statistics
+//!
+//! Global statistics about the documents stored on the server, as shown on the dashboard.
+
+use crate::{asn, tag};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Statistics {
+    #[serde(alias = "documentsTotal")]
+    pub documents_total: u64,
+    /// Number of documents with the inbox tag, `None` if there is no inbox tag
+    #[serde(alias = "documentsInbox")]
+    pub documents_inbox: Option<u64>,
+    #[serde(alias = "inboxTag")]
+    pub inbox_tag: Option<tag::Id>,
+    /// Inbox tags, newer servers support several of them
+    #[serde(alias = "inboxTags", default)]
+    pub inbox_tags: Vec<tag::Id>,
+    #[serde(alias = "characterCount", default)]
+    pub character_count: u64,
+    /// Number of documents of each type of file, the most common first
+    #[serde(alias = "documentFileTypeCounts", default)]
+    pub document_file_type_counts: Vec<FileTypeCount>,
+    #[serde(alias = "tagCount", default)]
+    pub tag_count: u64,
+    #[serde(alias = "correspondentCount", default)]
+    pub correspondent_count: u64,
+    #[serde(alias = "documentTypeCount", default)]
+    pub document_type_count: u64,
+    #[serde(alias = "storagePathCount", default)]
+    pub storage_path_count: u64,
+    /// Highest archive serial number in use
+    #[serde(alias = "currentAsn", default)]
+    pub current_asn: Option<asn::ASN>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct FileTypeCount {
+    #[serde(alias = "mimeType")]
+    pub mime_type: String,
+    #[serde(alias = "mimeTypeCount")]
+    pub mime_type_count: u64,
+}
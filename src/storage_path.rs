@@ -1,10 +1,13 @@
 //! # Storage path
 //!
-//! This part of the library is not implemented
+//! A storage path defines where the files of the documents are stored on the server, with a
+//! template like `{correspondent}/{created_year}/{title}`.
 
-use serde::Deserialize;
+use crate::{matching, permissions, query, user};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Copy, Clone)]
+#[derive(Debug, Deserialize, Copy, Clone, Serialize, PartialEq, Eq, Hash)]
 pub struct Id(u64);
 
 impl From<u64> for Id {
@@ -22,3 +25,97 @@ impl ToString for Id {
         self.0.to_string()
     }
 }
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct StoragePath {
+    pub id: Id,
+    pub slug: String,
+    pub name: String,
+    /// Template of the path of the files
+    pub path: String,
+    #[serde(rename = "match")]
+    pub match_: String,
+    #[serde(alias = "matchingAlgorithm")]
+    pub matching_algorithm: matching::Algorithm,
+    #[serde(alias = "isInsensitive")]
+    pub is_insensitive: bool,
+    /// Not sent by the server when the storage path has just been created
+    #[serde(alias = "documentCount", default)]
+    pub document_count: u64,
+    /// Owner of the object, `None` if everyone can see it
+    #[serde(default)]
+    pub owner: Option<user::Id>,
+    /// Only sent when the client asks for the full permissions
+    #[serde(default)]
+    pub permissions: Option<permissions::Permissions>,
+    /// Whether the current user can modify the object, not sent by old versions of paperless
+    #[serde(default, alias = "userCanChange")]
+    pub user_can_change: Option<bool>,
+}
+
+/// A new storage path
+#[derive(Debug, Clone, Serialize)]
+pub struct Create {
+    pub name: String,
+    /// Template of the path of the files
+    pub path: String,
+    #[serde(rename = "match")]
+    pub match_: String,
+    pub matching_algorithm: matching::Algorithm,
+    pub is_insensitive: bool,
+}
+
+impl Create {
+    /// A storage path automatically assigned by the server, like when created from the interface
+    pub fn new(name: &str, path: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            path: path.to_string(),
+            match_: String::new(),
+            matching_algorithm: matching::Algorithm::Auto,
+            is_insensitive: true,
+        }
+    }
+}
+
+/// Modification of a storage path, only the fields set to `Some` are modified
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct Update {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(rename = "match", skip_serializing_if = "Option::is_none")]
+    pub match_: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matching_algorithm: Option<matching::Algorithm>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_insensitive: Option<bool>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Filter {
+    name_starts_with: Option<String>,
+    name_ends_with: Option<String>,
+    name_contains: Option<String>,
+    name_is: Option<String>,
+}
+
+impl Filter {
+    /// Merge two filters into a new one, every field set in `other` takes precedence over `self`
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            name_starts_with: other.name_starts_with.or(self.name_starts_with),
+            name_ends_with: other.name_ends_with.or(self.name_ends_with),
+            name_contains: other.name_contains.or(self.name_contains),
+            name_is: other.name_is.or(self.name_is),
+        }
+    }
+
+    pub fn insert_query(&self, url: &mut Url) {
+        query::append_some(url, "name__istartswith", self.name_starts_with.as_deref());
+        query::append_some(url, "name__iendswith", self.name_ends_with.as_deref());
+        query::append_some(url, "name__icontains", self.name_contains.as_deref());
+        query::append_some(url, "name__iexact", self.name_is.as_deref());
+    }
+}
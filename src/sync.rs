@@ -0,0 +1,90 @@
+//! # Sync
+//!
+//! Incremental synchronisation of documents: only the documents added or modified since the
+//! previous synchronisation are listed.
+//!
+//! Both the `added` and the `modified` dates are tracked, as relying on only one of them misses
+//! either the documents edited after being added, or the documents added with an old
+//! modification date (for example when imported).
+
+use crate::date::{self, DateTime};
+use crate::{document, Error, Paperless};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// High-water marks of a synchronisation, to keep between two synchronisations
+///
+/// Also available as [`SyncState`](crate::SyncState).
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct State {
+    /// Most recent `added` date seen
+    #[serde(default, with = "date::datetime::option")]
+    pub added: Option<DateTime>,
+    /// Most recent `modified` date seen
+    #[serde(default, with = "date::datetime::option")]
+    pub modified: Option<DateTime>,
+}
+
+impl State {
+    /// Move the high-water marks after this document
+    pub fn observe(&mut self, document: &document::Document) {
+        self.added = self.added.max(Some(document.added));
+        self.modified = self.modified.max(Some(document.modified));
+    }
+}
+
+/// Result of an incremental synchronisation
+#[derive(Debug)]
+pub struct Changes {
+    /// Documents added or modified since the previous synchronisation
+    pub documents: Vec<document::Document>,
+    /// State to use for the next synchronisation
+    pub state: State,
+}
+
+/// List the documents added or modified since the previous synchronisation
+///
+/// A document can be returned by two consecutive synchronisations when it was modified during the
+/// same second as the high-water mark, but a change is never missed.
+///
+/// # Arguments
+///
+/// * `paperless` - The client to use
+/// * `filter` - Filter restricting the synchronised documents
+/// * `state` - State returned by the previous synchronisation, or the default one for the first
+pub fn incremental(
+    paperless: &Paperless,
+    filter: document::Filter,
+    state: &State,
+) -> Result<Changes, Error> {
+    let mut changes = Changes {
+        documents: Vec::new(),
+        state: state.clone(),
+    };
+    let mut seen = HashSet::new();
+
+    let filters = match (state.added, state.modified) {
+        (Some(added), Some(modified)) => vec![
+            filter.clone().merge(document::Filter {
+                added_gt: Some(added),
+                ..Default::default()
+            }),
+            filter.merge(document::Filter {
+                modified_gt: Some(modified),
+                ..Default::default()
+            }),
+        ],
+        _ => vec![filter],
+    };
+
+    for filter in filters {
+        for document in paperless.documents(filter)? {
+            let document = document?;
+            changes.state.observe(&document);
+            if seen.insert(document.id) {
+                changes.documents.push(document);
+            }
+        }
+    }
+    Ok(changes)
+}
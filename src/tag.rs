@@ -1,8 +1,14 @@
+//! # Tag
+//!
+//! Tags are labels attached to documents, a document can have many tags.
+
+use crate::{matching, permissions, query, user};
 use hex_color::HexColor;
 use reqwest::Url;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize, Serializer};
+use std::fmt;
 
-#[derive(Debug, Deserialize, Copy, Clone)]
+#[derive(Debug, Deserialize, Copy, Clone, Serialize, PartialEq, Eq, Hash)]
 pub struct Id(u64);
 
 impl From<u64> for Id {
@@ -15,13 +21,13 @@ impl From<Id> for u64 {
         value.0
     }
 }
-impl ToString for Id {
-    fn to_string(&self) -> String {
-        self.0.to_string()
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct Tag {
     pub id: Id,
     pub slug: String,
@@ -30,13 +36,87 @@ pub struct Tag {
     pub text_color: HexColor,
     #[serde(rename = "match")]
     pub match_: String,
-    pub matching_algorithm: u64,
+    pub matching_algorithm: matching::Algorithm,
     pub is_insensitive: bool,
     pub is_inbox_tag: bool,
+    /// Not sent by the server when the tag has just been created
+    #[serde(default)]
     pub document_count: u64,
+    /// Owner of the object, `None` if everyone can see it
+    #[serde(default)]
+    pub owner: Option<user::Id>,
+    /// Only sent when the client asks for the full permissions
+    #[serde(default)]
+    pub permissions: Option<permissions::Permissions>,
+    /// Whether the current user can modify the object, not sent by old versions of paperless
+    #[serde(default, alias = "userCanChange")]
+    pub user_can_change: Option<bool>,
+}
+
+/// A new tag
+#[derive(Debug, Clone, Serialize)]
+pub struct Create {
+    pub name: String,
+    /// Background color, chosen by the server if not set
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_color"
+    )]
+    pub color: Option<HexColor>,
+    /// New documents get the inbox tags
+    pub is_inbox_tag: bool,
+    #[serde(rename = "match")]
+    pub match_: String,
+    pub matching_algorithm: matching::Algorithm,
+    pub is_insensitive: bool,
+}
+
+impl Create {
+    /// A tag automatically assigned by the server, like when created from the interface
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            color: None,
+            is_inbox_tag: false,
+            match_: String::new(),
+            matching_algorithm: matching::Algorithm::Auto,
+            is_insensitive: true,
+        }
+    }
 }
 
-#[derive(Debug, Default)]
+/// Modification of a tag, only the fields set to `Some` are modified
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct Update {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_color"
+    )]
+    pub color: Option<HexColor>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_inbox_tag: Option<bool>,
+    #[serde(rename = "match", skip_serializing_if = "Option::is_none")]
+    pub match_: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matching_algorithm: Option<matching::Algorithm>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_insensitive: Option<bool>,
+}
+
+/// The server expects colors as `#rrggbb`
+fn serialize_color<S>(color: &Option<HexColor>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match color {
+        Some(c) => serializer.serialize_str(&format!("#{:02x}{:02x}{:02x}", c.r, c.g, c.b)),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[derive(Debug, Default, Clone)]
 pub struct Filter {
     name_starts_with: Option<String>,
     name_ends_with: Option<String>,
@@ -45,14 +125,20 @@ pub struct Filter {
 }
 
 impl Filter {
-    pub fn insert_query(self, url: &mut Url) {
-        url.query_pairs_mut()
-            .append_pair(
-                "name__istartswith",
-                &self.name_starts_with.unwrap_or_default(),
-            )
-            .append_pair("name__iendswith", &self.name_ends_with.unwrap_or_default())
-            .append_pair("name__icontains", &self.name_contains.unwrap_or_default())
-            .append_pair("name__iexact", &self.name_is.unwrap_or_default());
+    /// Merge two filters into a new one, every field set in `other` takes precedence over `self`
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            name_starts_with: other.name_starts_with.or(self.name_starts_with),
+            name_ends_with: other.name_ends_with.or(self.name_ends_with),
+            name_contains: other.name_contains.or(self.name_contains),
+            name_is: other.name_is.or(self.name_is),
+        }
+    }
+
+    pub fn insert_query(&self, url: &mut Url) {
+        query::append_some(url, "name__istartswith", self.name_starts_with.as_deref());
+        query::append_some(url, "name__iendswith", self.name_ends_with.as_deref());
+        query::append_some(url, "name__icontains", self.name_contains.as_deref());
+        query::append_some(url, "name__iexact", self.name_is.as_deref());
     }
 }
@@ -0,0 +1,145 @@
+//! # Task
+//!
+//! Tasks are run in background by the server, for example to consume a new document.
+
+use crate::date::{self, DateTime};
+use crate::document;
+use reqwest::Url;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+
+#[derive(Debug, Deserialize, Copy, Clone, Serialize, PartialEq, Eq, Hash)]
+pub struct Id(u64);
+
+impl From<u64> for Id {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+impl From<Id> for u64 {
+    fn from(value: Id) -> Self {
+        value.0
+    }
+}
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// State of a task, as reported by celery, some proxies send it in lowercase
+#[derive(Debug, Deserialize, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Status {
+    #[serde(alias = "pending")]
+    Pending,
+    #[serde(alias = "started")]
+    Started,
+    #[serde(alias = "success")]
+    Success,
+    #[serde(alias = "failure")]
+    Failure,
+    #[serde(alias = "retry")]
+    Retry,
+    #[serde(alias = "revoked")]
+    Revoked,
+}
+
+impl Status {
+    /// Name of the status expected by the server
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Status::Pending => "PENDING",
+            Status::Started => "STARTED",
+            Status::Success => "SUCCESS",
+            Status::Failure => "FAILURE",
+            Status::Retry => "RETRY",
+            Status::Revoked => "REVOKED",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Task {
+    pub id: Id,
+    /// Identifier of the task, this is the value returned when uploading a document
+    #[serde(alias = "taskId")]
+    pub task_id: String,
+    #[serde(alias = "taskFileName")]
+    pub task_file_name: Option<String>,
+    #[serde(alias = "dateCreated", default, with = "date::datetime::option")]
+    pub date_created: Option<DateTime>,
+    #[serde(alias = "dateDone", default, with = "date::datetime::option")]
+    pub date_done: Option<DateTime>,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    pub status: Status,
+    /// Message returned by the task, the error message if it failed
+    pub result: Option<String>,
+    pub acknowledged: bool,
+    /// Document created by this task, if any
+    #[serde(
+        alias = "relatedDocument",
+        default,
+        deserialize_with = "deserialize_related_document"
+    )]
+    pub related_document: Option<document::Id>,
+}
+
+/// The server sends the related document as a string
+fn deserialize_related_document<'de, D>(deserializer: D) -> Result<Option<document::Id>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Related {
+        Number(u64),
+        String(String),
+    }
+
+    Ok(match Option::<Related>::deserialize(deserializer)? {
+        Some(Related::Number(id)) => Some(id.into()),
+        Some(Related::String(id)) => id.parse::<u64>().ok().map(|id| id.into()),
+        None => None,
+    })
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Filter {
+    /// Identifier of the task, as returned when uploading a document
+    pub task_id: Option<String>,
+    pub status: Option<Status>,
+    pub acknowledged: Option<bool>,
+}
+
+impl Filter {
+    /// Merge two filters into a new one, every field set in `other` takes precedence over `self`
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            task_id: other.task_id.or(self.task_id),
+            status: other.status.or(self.status),
+            acknowledged: other.acknowledged.or(self.acknowledged),
+        }
+    }
+
+    pub fn insert_query(&self, url: &mut Url) {
+        if let Some(task_id) = &self.task_id {
+            url.query_pairs_mut().append_pair("task_id", task_id);
+        }
+        if let Some(status) = self.status {
+            url.query_pairs_mut().append_pair("status", status.as_str());
+        }
+        if let Some(acknowledged) = self.acknowledged {
+            url.query_pairs_mut()
+                .append_pair("acknowledged", &acknowledged.to_string());
+        }
+    }
+
+    /// Whether a task matches the filter, for servers which ignore some parameters
+    pub(crate) fn matches(&self, task: &Task) -> bool {
+        self.task_id.iter().all(|id| *id == task.task_id)
+            && self.status.iter().all(|s| *s == task.status)
+            && self.acknowledged.iter().all(|a| *a == task.acknowledged)
+    }
+}
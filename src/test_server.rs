@@ -0,0 +1,67 @@
+//! Local http server for the tests which depend on the behavior of paperless
+//!
+//! Each request is answered by a handler, which replays responses recorded from a server.
+
+use reqwest::Url;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::thread;
+
+/// Serve requests in background until the end of the test, and return the root of the api
+///
+/// # Arguments
+///
+/// * `handler` - Status and json body answered to a GET of an url
+pub(crate) fn serve(handler: impl Fn(&Url) -> (u16, String) + Send + 'static) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let root = format!("http://{}/api/", listener.local_addr().unwrap());
+    let base = Url::parse(&root).unwrap();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut reader = BufReader::new(&stream);
+            let mut request_line = String::new();
+            if reader.read_line(&mut request_line).is_err() {
+                continue;
+            }
+            // Only GET requests without body are expected
+            let mut line = String::new();
+            while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+                line.clear();
+            }
+            let path = request_line.split(' ').nth(1).unwrap_or("/");
+            let (status, body) = handler(&base.join(path).unwrap());
+            let _ = write!(
+                stream,
+                "HTTP/1.1 {status} X\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+        }
+    });
+    root
+}
+
+/// Values of a parameter of the query string the way django reads it, the last one if repeated
+pub(crate) fn param(url: &Url, key: &str) -> Option<String> {
+    url.query_pairs()
+        .filter(|(k, _)| k == key)
+        .last()
+        .map(|(_, v)| v.into_owned())
+}
+
+/// Ids of a list parameter, split on commas like the `BaseInFilter` of django-filter
+pub(crate) fn ids(url: &Url, key: &str) -> Option<Vec<u64>> {
+    param(url, key).map(|v| v.split(',').filter_map(|id| id.parse().ok()).collect())
+}
+
+/// A page of a listing, with all the items
+pub(crate) fn page(results: Vec<serde_json::Value>) -> String {
+    serde_json::json!({
+        "count": results.len(),
+        "next": null,
+        "previous": null,
+        "results": results,
+    })
+    .to_string()
+}
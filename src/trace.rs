@@ -0,0 +1,78 @@
+//! Traces of the requests sent to the server, emitted with `tracing` when the `tracing` feature
+//! is enabled
+
+use reqwest::{Method, StatusCode, Url};
+use std::time::Duration;
+#[cfg(feature = "tracing")]
+use std::time::Instant;
+
+/// A request in flight, traced when it finishes
+pub(crate) struct Request {
+    #[cfg(feature = "tracing")]
+    method: Method,
+    #[cfg(feature = "tracing")]
+    url: String,
+    #[cfg(feature = "tracing")]
+    start: Instant,
+}
+
+impl Request {
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn start(method: &Method, url: &Url) -> Self {
+        Self {
+            #[cfg(feature = "tracing")]
+            method: method.clone(),
+            #[cfg(feature = "tracing")]
+            url: redact(url),
+            #[cfg(feature = "tracing")]
+            start: Instant::now(),
+        }
+    }
+
+    /// Trace the status of the response, or the error which prevented to get one
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn finish(self, result: Result<StatusCode, &reqwest::Error>) {
+        #[cfg(feature = "tracing")]
+        {
+            let duration_ms = self.start.elapsed().as_millis() as u64;
+            match result {
+                Ok(status) => tracing::debug!(
+                    method = %self.method,
+                    url = %self.url,
+                    status = status.as_u16(),
+                    duration_ms,
+                    "request"
+                ),
+                Err(error) => tracing::warn!(
+                    method = %self.method,
+                    url = %self.url,
+                    %error,
+                    duration_ms,
+                    "request failed"
+                ),
+            }
+        }
+    }
+}
+
+/// Trace a request sent again
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn retry(url: &Url, delay: Duration, reason: &str) {
+    #[cfg(feature = "tracing")]
+    tracing::info!(
+        url = %redact(url),
+        delay_ms = delay.as_millis() as u64,
+        reason,
+        "retrying request"
+    );
+}
+
+/// Url without the credentials it may contain
+#[cfg(feature = "tracing")]
+fn redact(url: &Url) -> String {
+    let mut url = url.clone();
+    // Only fails for urls which can't have credentials
+    let _ = url.set_username("");
+    let _ = url.set_password(None);
+    url.to_string()
+}
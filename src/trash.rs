@@ -0,0 +1,25 @@
+//! # Trash
+//!
+//! Deleted documents are moved to the trash of recent versions of paperless-ngx, from which they
+//! can be restored until the trash is emptied.
+
+use crate::date::{self, DateTime};
+use crate::document;
+use serde::Deserialize;
+
+/// A document in the trash
+#[derive(Debug, Deserialize)]
+pub struct Deleted {
+    #[serde(flatten)]
+    pub document: document::Document,
+    #[serde(default, alias = "deletedAt", with = "date::datetime::option")]
+    pub deleted_at: Option<DateTime>,
+}
+
+/// Documents of the trash affected by an operation
+#[derive(Debug, Clone)]
+pub enum Selection {
+    Documents(Vec<document::Id>),
+    /// Every document in the trash
+    All,
+}
@@ -0,0 +1,38 @@
+//! # UI settings
+//!
+//! Settings of the web interface for the current user, with the permissions of this user.
+
+use crate::user;
+use serde::Deserialize;
+
+/// The user to which the token belongs
+#[derive(Debug, Deserialize, Clone)]
+pub struct CurrentUser {
+    pub id: user::Id,
+    pub username: String,
+    #[serde(default, alias = "isStaff")]
+    pub is_staff: bool,
+    #[serde(default, alias = "isSuperuser")]
+    pub is_superuser: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct UiSettings {
+    pub user: CurrentUser,
+    /// Settings saved by the interface, as sent by the server
+    #[serde(default)]
+    pub settings: serde_json::Value,
+    /// Permissions of the user, like `change_document`
+    #[serde(default)]
+    pub permissions: Vec<String>,
+}
+
+impl UiSettings {
+    /// Whether the user has a permission, to know if an operation will be rejected before trying
+    /// it
+    ///
+    /// Superusers have every permission.
+    pub fn has_permission(&self, permission: &str) -> bool {
+        self.user.is_superuser || self.permissions.iter().any(|p| p == permission)
+    }
+}
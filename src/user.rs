@@ -0,0 +1,127 @@
+//! # User
+//!
+//! Users of the server, to which documents and other objects belong.
+
+use crate::date::{self, DateTime};
+use crate::{group, query};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Deserialize, Copy, Clone, Serialize, PartialEq, Eq, Hash)]
+pub struct Id(u64);
+
+impl From<u64> for Id {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+impl From<Id> for u64 {
+    fn from(value: Id) -> Self {
+        value.0
+    }
+}
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct User {
+    pub id: Id,
+    pub username: String,
+    #[serde(default)]
+    pub email: String,
+    #[serde(default, alias = "firstName")]
+    pub first_name: String,
+    #[serde(default, alias = "lastName")]
+    pub last_name: String,
+    #[serde(default, alias = "dateJoined", with = "date::datetime::option")]
+    pub date_joined: Option<DateTime>,
+    #[serde(default, alias = "isStaff")]
+    pub is_staff: bool,
+    #[serde(default = "active", alias = "isActive")]
+    pub is_active: bool,
+    #[serde(default, alias = "isSuperuser")]
+    pub is_superuser: bool,
+    #[serde(default)]
+    pub groups: Vec<group::Id>,
+    /// Permissions given to the user directly, like `view_document`
+    #[serde(default, alias = "userPermissions", alias = "user_permissions")]
+    pub permissions: Vec<String>,
+    /// Permissions given to the user by its groups
+    #[serde(default, alias = "inheritedPermissions")]
+    pub inherited_permissions: Vec<String>,
+}
+
+fn active() -> bool {
+    true
+}
+
+impl User {
+    /// Name to show for this user: its full name, or its username if it has no name
+    pub fn display_name(&self) -> String {
+        let full_name = [self.first_name.as_str(), self.last_name.as_str()]
+            .into_iter()
+            .filter(|n| !n.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if full_name.is_empty() {
+            self.username.clone()
+        } else {
+            full_name
+        }
+    }
+
+    /// Whether the user has a permission, directly or through its groups
+    ///
+    /// Superusers have every permission.
+    pub fn has_permission(&self, permission: &str) -> bool {
+        self.is_superuser
+            || self
+                .permissions
+                .iter()
+                .chain(&self.inherited_permissions)
+                .any(|p| p == permission)
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Filter {
+    pub username_starts_with: Option<String>,
+    pub username_ends_with: Option<String>,
+    pub username_contains: Option<String>,
+    pub username_is: Option<String>,
+}
+
+impl Filter {
+    /// Merge two filters into a new one, every field set in `other` takes precedence over `self`
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            username_starts_with: other.username_starts_with.or(self.username_starts_with),
+            username_ends_with: other.username_ends_with.or(self.username_ends_with),
+            username_contains: other.username_contains.or(self.username_contains),
+            username_is: other.username_is.or(self.username_is),
+        }
+    }
+
+    pub fn insert_query(&self, url: &mut Url) {
+        query::append_some(
+            url,
+            "username__istartswith",
+            self.username_starts_with.as_deref(),
+        );
+        query::append_some(
+            url,
+            "username__iendswith",
+            self.username_ends_with.as_deref(),
+        );
+        query::append_some(
+            url,
+            "username__icontains",
+            self.username_contains.as_deref(),
+        );
+        query::append_some(url, "username__iexact", self.username_is.as_deref());
+    }
+}
@@ -0,0 +1,15 @@
+//! # Version
+//!
+//! Releases of paperless, as known by the server.
+
+use serde::Deserialize;
+
+/// Latest release of paperless
+#[derive(Debug, Deserialize, Clone)]
+pub struct RemoteVersion {
+    /// Version of the latest release, `0.0.0` if the server doesn't check for updates
+    pub version: String,
+    /// Whether the latest release is newer than the version of the server
+    #[serde(alias = "updateAvailable")]
+    pub update_available: bool,
+}
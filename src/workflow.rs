@@ -0,0 +1,372 @@
+//! # Workflow
+//!
+//! Workflows modify documents when they are consumed, added or updated: when one of the
+//! triggers of a workflow matches a document, its actions are applied in order.
+
+use crate::{
+    correspondent, custom_field, document_type, group, mail_rule, matching, storage_path, tag, user,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+#[derive(Debug, Deserialize, Copy, Clone, Serialize, PartialEq, Eq, Hash)]
+pub struct Id(u64);
+
+impl From<u64> for Id {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+impl From<Id> for u64 {
+    fn from(value: Id) -> Self {
+        value.0
+    }
+}
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// When a trigger is evaluated
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TriggerType {
+    /// Before a file is consumed, only the file name and path are known
+    ConsumptionStarted,
+    DocumentAdded,
+    DocumentUpdated,
+    /// At a date relative to a date of the document
+    Scheduled,
+    /// A type unknown to this version of the crate
+    Unknown(u64),
+}
+
+impl From<u64> for TriggerType {
+    fn from(value: u64) -> Self {
+        match value {
+            1 => TriggerType::ConsumptionStarted,
+            2 => TriggerType::DocumentAdded,
+            3 => TriggerType::DocumentUpdated,
+            4 => TriggerType::Scheduled,
+            v => TriggerType::Unknown(v),
+        }
+    }
+}
+impl From<TriggerType> for u64 {
+    fn from(value: TriggerType) -> Self {
+        match value {
+            TriggerType::ConsumptionStarted => 1,
+            TriggerType::DocumentAdded => 2,
+            TriggerType::DocumentUpdated => 3,
+            TriggerType::Scheduled => 4,
+            TriggerType::Unknown(v) => v,
+        }
+    }
+}
+
+impl Serialize for TriggerType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64((*self).into())
+    }
+}
+impl<'de> Deserialize<'de> for TriggerType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(u64::deserialize(deserializer)?.into())
+    }
+}
+
+/// How a document reached the server
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Source {
+    ConsumeFolder,
+    ApiUpload,
+    MailFetch,
+    /// A source unknown to this version of the crate
+    Unknown(u64),
+}
+
+impl From<u64> for Source {
+    fn from(value: u64) -> Self {
+        match value {
+            1 => Source::ConsumeFolder,
+            2 => Source::ApiUpload,
+            3 => Source::MailFetch,
+            v => Source::Unknown(v),
+        }
+    }
+}
+impl From<Source> for u64 {
+    fn from(value: Source) -> Self {
+        match value {
+            Source::ConsumeFolder => 1,
+            Source::ApiUpload => 2,
+            Source::MailFetch => 3,
+            Source::Unknown(v) => v,
+        }
+    }
+}
+
+impl Serialize for Source {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64((*self).into())
+    }
+}
+impl<'de> Deserialize<'de> for Source {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(u64::deserialize(deserializer)?.into())
+    }
+}
+
+/// What an action does
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ActionType {
+    /// Set the `assign_*` fields on the document
+    Assignment,
+    /// Remove the `remove_*` fields from the document
+    Removal,
+    Email,
+    Webhook,
+    /// A type unknown to this version of the crate
+    Unknown(u64),
+}
+
+impl From<u64> for ActionType {
+    fn from(value: u64) -> Self {
+        match value {
+            1 => ActionType::Assignment,
+            2 => ActionType::Removal,
+            3 => ActionType::Email,
+            4 => ActionType::Webhook,
+            v => ActionType::Unknown(v),
+        }
+    }
+}
+impl From<ActionType> for u64 {
+    fn from(value: ActionType) -> Self {
+        match value {
+            ActionType::Assignment => 1,
+            ActionType::Removal => 2,
+            ActionType::Email => 3,
+            ActionType::Webhook => 4,
+            ActionType::Unknown(v) => v,
+        }
+    }
+}
+
+impl Serialize for ActionType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64((*self).into())
+    }
+}
+impl<'de> Deserialize<'de> for ActionType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(u64::deserialize(deserializer)?.into())
+    }
+}
+
+/// A condition starting a workflow
+///
+/// The `filter_*` fields left empty match every document.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Trigger {
+    /// `None` for triggers which are not created yet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    #[serde(rename = "type")]
+    pub type_: TriggerType,
+    #[serde(default)]
+    pub sources: Vec<Source>,
+    /// Pattern of the name of the file, with `*` wildcards
+    #[serde(default, alias = "filterFilename")]
+    pub filter_filename: Option<String>,
+    /// Pattern of the path of the file, with `*` wildcards
+    #[serde(default, alias = "filterPath")]
+    pub filter_path: Option<String>,
+    /// Mail rule which fetched the document
+    #[serde(default, alias = "filterMailrule")]
+    pub filter_mailrule: Option<mail_rule::Id>,
+    #[serde(default, alias = "filterHasTags")]
+    pub filter_has_tags: Vec<tag::Id>,
+    #[serde(default, alias = "filterHasCorrespondent")]
+    pub filter_has_correspondent: Option<correspondent::Id>,
+    #[serde(default, alias = "filterHasDocumentType")]
+    pub filter_has_document_type: Option<document_type::Id>,
+    #[serde(default, rename = "match")]
+    pub match_: String,
+    #[serde(alias = "matchingAlgorithm")]
+    pub matching_algorithm: matching::Algorithm,
+    #[serde(default, alias = "isInsensitive")]
+    pub is_insensitive: bool,
+}
+
+impl Trigger {
+    /// A trigger matching every document from any source
+    pub fn new(type_: TriggerType) -> Self {
+        Self {
+            id: None,
+            type_,
+            sources: vec![Source::ConsumeFolder, Source::ApiUpload, Source::MailFetch],
+            filter_filename: None,
+            filter_path: None,
+            filter_mailrule: None,
+            filter_has_tags: Vec::new(),
+            filter_has_correspondent: None,
+            filter_has_document_type: None,
+            match_: String::new(),
+            matching_algorithm: matching::Algorithm::None,
+            is_insensitive: true,
+        }
+    }
+}
+
+/// A modification applied by a workflow
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Action {
+    /// `None` for actions which are not created yet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    #[serde(rename = "type")]
+    pub type_: ActionType,
+    /// Title of the document, which can use placeholders like `{correspondent}`
+    #[serde(default, alias = "assignTitle")]
+    pub assign_title: Option<String>,
+    #[serde(default, alias = "assignTags")]
+    pub assign_tags: Vec<tag::Id>,
+    #[serde(default, alias = "assignCorrespondent")]
+    pub assign_correspondent: Option<correspondent::Id>,
+    #[serde(default, alias = "assignDocumentType")]
+    pub assign_document_type: Option<document_type::Id>,
+    #[serde(default, alias = "assignStoragePath")]
+    pub assign_storage_path: Option<storage_path::Id>,
+    #[serde(default, alias = "assignOwner")]
+    pub assign_owner: Option<user::Id>,
+    #[serde(default, alias = "assignViewUsers")]
+    pub assign_view_users: Vec<user::Id>,
+    #[serde(default, alias = "assignViewGroups")]
+    pub assign_view_groups: Vec<group::Id>,
+    #[serde(default, alias = "assignChangeUsers")]
+    pub assign_change_users: Vec<user::Id>,
+    #[serde(default, alias = "assignChangeGroups")]
+    pub assign_change_groups: Vec<group::Id>,
+    #[serde(default, alias = "assignCustomFields")]
+    pub assign_custom_fields: Vec<custom_field::Id>,
+    #[serde(default, alias = "removeTags")]
+    pub remove_tags: Vec<tag::Id>,
+    #[serde(default, alias = "removeAllTags")]
+    pub remove_all_tags: bool,
+    #[serde(default, alias = "removeCorrespondents")]
+    pub remove_correspondents: Vec<correspondent::Id>,
+    #[serde(default, alias = "removeAllCorrespondents")]
+    pub remove_all_correspondents: bool,
+    #[serde(default, alias = "removeDocumentTypes")]
+    pub remove_document_types: Vec<document_type::Id>,
+    #[serde(default, alias = "removeAllDocumentTypes")]
+    pub remove_all_document_types: bool,
+    #[serde(default, alias = "removeStoragePaths")]
+    pub remove_storage_paths: Vec<storage_path::Id>,
+    #[serde(default, alias = "removeAllStoragePaths")]
+    pub remove_all_storage_paths: bool,
+    #[serde(default, alias = "removeCustomFields")]
+    pub remove_custom_fields: Vec<custom_field::Id>,
+    #[serde(default, alias = "removeAllCustomFields")]
+    pub remove_all_custom_fields: bool,
+    #[serde(default, alias = "removeOwners")]
+    pub remove_owners: Vec<user::Id>,
+    #[serde(default, alias = "removeAllOwners")]
+    pub remove_all_owners: bool,
+    #[serde(default, alias = "removeAllPermissions")]
+    pub remove_all_permissions: bool,
+    /// Settings of email actions, as sent by the server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<serde_json::Value>,
+    /// Settings of webhook actions, as sent by the server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<serde_json::Value>,
+}
+
+impl Action {
+    /// An action which does nothing until some of its fields are set
+    pub fn new(type_: ActionType) -> Self {
+        Self {
+            id: None,
+            type_,
+            assign_title: None,
+            assign_tags: Vec::new(),
+            assign_correspondent: None,
+            assign_document_type: None,
+            assign_storage_path: None,
+            assign_owner: None,
+            assign_view_users: Vec::new(),
+            assign_view_groups: Vec::new(),
+            assign_change_users: Vec::new(),
+            assign_change_groups: Vec::new(),
+            assign_custom_fields: Vec::new(),
+            remove_tags: Vec::new(),
+            remove_all_tags: false,
+            remove_correspondents: Vec::new(),
+            remove_all_correspondents: false,
+            remove_document_types: Vec::new(),
+            remove_all_document_types: false,
+            remove_storage_paths: Vec::new(),
+            remove_all_storage_paths: false,
+            remove_custom_fields: Vec::new(),
+            remove_all_custom_fields: false,
+            remove_owners: Vec::new(),
+            remove_all_owners: false,
+            remove_all_permissions: false,
+            email: None,
+            webhook: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Workflow {
+    pub id: Id,
+    pub name: String,
+    /// Workflows are applied by increasing order
+    pub order: i64,
+    pub enabled: bool,
+    pub triggers: Vec<Trigger>,
+    pub actions: Vec<Action>,
+}
+
+/// A new workflow
+#[derive(Debug, Clone, Serialize)]
+pub struct Create {
+    pub name: String,
+    pub order: i64,
+    pub enabled: bool,
+    pub triggers: Vec<Trigger>,
+    pub actions: Vec<Action>,
+}
+
+impl Create {
+    /// An enabled workflow, applied first
+    pub fn new(name: &str, triggers: Vec<Trigger>, actions: Vec<Action>) -> Self {
+        Self {
+            name: name.to_string(),
+            order: 0,
+            enabled: true,
+            triggers,
+            actions,
+        }
+    }
+}
+
+/// Modification of a workflow, only the fields set to `Some` are modified
+///
+/// Triggers and actions are replaced: the ones missing from the new list are deleted.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct Update {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub triggers: Option<Vec<Trigger>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actions: Option<Vec<Action>>,
+}